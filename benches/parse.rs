@@ -0,0 +1,46 @@
+//! Times parsing of the OpenGL 4.6 state tables, the largest of the three.
+//! Run with `cargo bench`.
+//!
+//! Before parse_spec borrowed from the source it also read the file, so the
+//! second measurement, which includes read_spec, is the one to compare with
+//! older revisions.
+
+#![allow(non_snake_case)] // let me capitalize the crate name, Rust!
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use OpenGL_state_table_parser::{parse_spec, read_spec};
+
+const ITERATIONS: u32 = 500;
+
+fn time(mut f: impl FnMut()) -> Duration {
+    // Warm up
+    for _ in 0..10 {
+        f();
+    }
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let source = read_spec("gl");
+    let parse = time(|| {
+        black_box(parse_spec("gl", black_box(&source)));
+    });
+    let read_and_parse = time(|| {
+        let source = read_spec(black_box("gl"));
+        black_box(parse_spec("gl", &source));
+    });
+    println!(
+        "parse_spec(\"gl\"): {:?} per iteration ({} iterations)",
+        parse, ITERATIONS
+    );
+    println!(
+        "read_spec(\"gl\") + parse_spec(\"gl\"): {:?} per iteration ({} iterations)",
+        read_and_parse, ITERATIONS
+    );
+}
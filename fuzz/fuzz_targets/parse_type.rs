@@ -0,0 +1,12 @@
+#![no_main]
+#![allow(non_snake_case)]
+
+use libfuzzer_sys::fuzz_target;
+use OpenGL_state_table_parser::types::{parse_type, type_to_latex};
+
+fuzz_target!(|text: &str| {
+    if let Some(type_) = parse_type(text) {
+        let latex = type_to_latex(&type_);
+        assert_eq!(parse_type(&latex), Some(type_), "{}", latex);
+    }
+});
@@ -0,0 +1,15 @@
+#![no_main]
+#![allow(non_snake_case)]
+
+use libfuzzer_sys::fuzz_target;
+use OpenGL_state_table_parser::try_read_cell;
+
+fuzz_target!(|text: &str| {
+    if let Some((cell, remainder)) = try_read_cell(text) {
+        assert!(text.ends_with(remainder));
+        // The cell's own braces must be balanced, so wrapping it again should
+        // give back the same cell.
+        let rewrapped = format!("{{{}}}", cell);
+        assert_eq!(try_read_cell(&rewrapped), Some((cell, "")));
+    }
+});
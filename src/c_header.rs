@@ -0,0 +1,303 @@
+//! A C header describing every state variable, for debugging layers and the
+//! like that need to know how to query and print any pname. There's an array
+//! of structs per spec, e.g. `gl_state_gl`, with one element for each entry in
+//! its state tables that has a get value.
+//!
+//! The header doesn't need the GL headers, but it works with them: the
+//! `glPushAttrib` bits are only defined if they aren't already.
+
+use crate::SpecTables;
+use std::io::{self, Write};
+use OpenGL_state_table_parser::types::{component_count, scalar_kind, MaybeParsed, Quantity};
+use OpenGL_state_table_parser::{Condition, Entry};
+
+/// The attribute groups in the tables, and the `glPushAttrib` or
+/// `glPushClientAttrib` bit for each. Some groups can't be pushed at all.
+const ATTRIBUTE_BITS: [(&str, Option<(&str, u32)>); 26] = [
+    ("current", Some(("GL_CURRENT_BIT", 0x00000001))),
+    ("point", Some(("GL_POINT_BIT", 0x00000002))),
+    ("line", Some(("GL_LINE_BIT", 0x00000004))),
+    ("polygon", Some(("GL_POLYGON_BIT", 0x00000008))),
+    (
+        "polygon-stipple",
+        Some(("GL_POLYGON_STIPPLE_BIT", 0x00000010)),
+    ),
+    ("pixel", Some(("GL_PIXEL_MODE_BIT", 0x00000020))),
+    ("lighting", Some(("GL_LIGHTING_BIT", 0x00000040))),
+    ("fog", Some(("GL_FOG_BIT", 0x00000080))),
+    ("depth-buffer", Some(("GL_DEPTH_BUFFER_BIT", 0x00000100))),
+    ("accum-buffer", Some(("GL_ACCUM_BUFFER_BIT", 0x00000200))),
+    (
+        "stencil-buffer",
+        Some(("GL_STENCIL_BUFFER_BIT", 0x00000400)),
+    ),
+    ("viewport", Some(("GL_VIEWPORT_BIT", 0x00000800))),
+    ("transform", Some(("GL_TRANSFORM_BIT", 0x00001000))),
+    // ES 1.1's name for transform
+    ("xform", Some(("GL_TRANSFORM_BIT", 0x00001000))),
+    ("enable", Some(("GL_ENABLE_BIT", 0x00002000))),
+    ("color-buffer", Some(("GL_COLOR_BUFFER_BIT", 0x00004000))),
+    ("hint", Some(("GL_HINT_BIT", 0x00008000))),
+    ("eval", Some(("GL_EVAL_BIT", 0x00010000))),
+    ("list", Some(("GL_LIST_BIT", 0x00020000))),
+    ("texture", Some(("GL_TEXTURE_BIT", 0x00040000))),
+    ("scissor", Some(("GL_SCISSOR_BIT", 0x00080000))),
+    ("multisample", Some(("GL_MULTISAMPLE_BIT", 0x20000000))),
+    (
+        "pixel-store",
+        Some(("GL_CLIENT_PIXEL_STORE_BIT", 0x00000001)),
+    ),
+    (
+        "vertex-array",
+        Some(("GL_CLIENT_VERTEX_ARRAY_BIT", 0x00000002)),
+    ),
+    ("select", None),
+    ("feedback", None),
+];
+
+const PREAMBLE: &str = "\
+/* Generated by OpenGL-state-table-parser from the state tables of the OpenGL
+ * and OpenGL ES specs. Don't edit it by hand. */
+
+#ifndef GL_STATE_TABLES_H
+#define GL_STATE_TABLES_H
+
+#include <stddef.h>
+
+/* What kind of scalars a variable's values are made of */
+enum gl_state_kind {
+    GL_STATE_KIND_BOOLEAN,
+    GL_STATE_KIND_INTEGER,
+    GL_STATE_KIND_ENUM,
+    GL_STATE_KIND_FLOAT,
+    GL_STATE_KIND_POINTER,
+    GL_STATE_KIND_STRING,
+    /* Can't be queried as scalars, like an image */
+    GL_STATE_KIND_OPAQUE,
+    /* The spec's type couldn't be parsed */
+    GL_STATE_KIND_UNKNOWN
+};
+
+/* The profile a variable is limited to. Variables that are in every profile
+ * (and all OpenGL ES variables) have none of these. */
+#define GL_STATE_CORE_ONLY 0x1u
+#define GL_STATE_COMPATIBILITY_ONLY 0x2u
+#define GL_STATE_IMAGING_SUBSET 0x4u
+
+struct gl_state_variable {
+    /* e.g. \"GL_TEXTURE_MAG_FILTER\" */
+    const char *name;
+    /* Another name for the same variable, or NULL */
+    const char *alt_name;
+    /* e.g. \"glGetTexParameteriv\", or NULL if the tables don't say */
+    const char *query;
+    enum gl_state_kind kind;
+    /* The number of scalars in the whole value, or 0 if that isn't fixed */
+    unsigned components;
+    /* For a series like GL_LIGHT0, GL_LIGHT1, ..., the minimum number of
+     * variables in it, either as a number or as the name of an
+     * implementation-dependent limit. Both are 0 or NULL otherwise. */
+    unsigned series_minimum;
+    const char *series_minimum_name;
+    /* GL_STATE_* profile flags */
+    unsigned condition;
+    /* The glPushAttrib and glPushClientAttrib bits that save it, if any */
+    unsigned attrib_bits;
+    unsigned client_attrib_bits;
+};
+";
+
+/// The `glPushAttrib` and `glPushClientAttrib` bits for an entry's attribute
+/// groups, as C expressions. Groups that aren't in [ATTRIBUTE_BITS] are left
+/// out, with a comment saying so.
+fn attribute_bits(attribute: Option<&str>) -> (String, String) {
+    let mut bits = Vec::new();
+    let mut client_bits = Vec::new();
+    let mut unknown = Vec::new();
+    for group in attribute.iter().flat_map(|a| a.split('/')) {
+        match ATTRIBUTE_BITS.iter().find(|&&(name, _)| name == group) {
+            Some(&(_, Some((name, _)))) if name.starts_with("GL_CLIENT_") => client_bits.push(name),
+            Some(&(_, Some((name, _)))) => bits.push(name),
+            Some((_, None)) => (),
+            None => unknown.push(group),
+        }
+    }
+    let expression = |bits: Vec<&str>| {
+        if bits.is_empty() {
+            "0".to_string()
+        } else {
+            bits.join(" | ")
+        }
+    };
+    let mut bits = expression(bits);
+    if !unknown.is_empty() {
+        bits.push_str(&format!(
+            " /* unknown attribute group {} */",
+            unknown.join(", ").replace("*/", "* /")
+        ));
+    }
+    (bits, expression(client_bits))
+}
+
+/// Quote a string as a C string literal, or `NULL` if there isn't one.
+fn c_string(value: Option<&str>) -> String {
+    let Some(value) = value else {
+        return "NULL".to_string();
+    };
+    let mut literal = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            c if c.is_control() => literal.push_str(&format!("\\{:03o}", c as u32)),
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+fn write_entry(out: &mut impl Write, get_value: &str, entry: &Entry) -> io::Result<()> {
+    let (kind, components) = match entry.type_ {
+        Some(MaybeParsed::Parsed(ref type_)) => (
+            scalar_kind(type_).to_string().to_ascii_uppercase(),
+            component_count(type_).unwrap_or(0),
+        ),
+        _ => ("UNKNOWN".to_string(), 0),
+    };
+    let (series_minimum, series_minimum_name) = match entry.series {
+        Some(Quantity::Integer(n)) => (n, None),
+        Some(Quantity::Constant(c)) => (0, Some(format!("GL_{}", c))),
+        None => (0, None),
+    };
+    let condition = match entry.condition {
+        Some(Condition::Core) => "GL_STATE_CORE_ONLY",
+        Some(Condition::Compatibility) => "GL_STATE_COMPATIBILITY_ONLY",
+        Some(Condition::ImagingSubset) => "GL_STATE_COMPATIBILITY_ONLY | GL_STATE_IMAGING_SUBSET",
+        None => "0",
+    };
+    let (attrib_bits, client_attrib_bits) = attribute_bits(entry.attribute.as_deref());
+    writeln!(
+        out,
+        "    {{{}, {}, {}, GL_STATE_KIND_{}, {}, {}, {}, {}, {}, {}}},",
+        c_string(Some(&format!("GL_{}", get_value))),
+        c_string(
+            entry
+                .alt_get_value
+                .as_ref()
+                .map(|v| format!("GL_{}", v))
+                .as_deref()
+        ),
+        c_string(
+            entry
+                .get_cmnd
+                .as_ref()
+                .map(|c| format!("gl{}", c))
+                .as_deref()
+        ),
+        kind,
+        components,
+        series_minimum,
+        c_string(series_minimum_name.as_deref()),
+        condition,
+        attrib_bits,
+        client_attrib_bits
+    )
+}
+
+/// Write the header, with an array for each spec.
+pub fn write_header(out: &mut impl Write, specs: &[SpecTables]) -> io::Result<()> {
+    write!(out, "{}", PREAMBLE)?;
+    writeln!(out)?;
+    let mut defined = Vec::new();
+    for (_, bit) in ATTRIBUTE_BITS {
+        let Some((name, value)) = bit else {
+            continue;
+        };
+        if defined.contains(&name) {
+            continue;
+        }
+        defined.push(name);
+        writeln!(out, "#ifndef {}", name)?;
+        writeln!(out, "#define {} 0x{:08x}", name, value)?;
+        writeln!(out, "#endif")?;
+    }
+
+    for &(suffix, spec_name, _, tables) in specs {
+        writeln!(out)?;
+        writeln!(out, "/* {} */", spec_name)?;
+        writeln!(
+            out,
+            "static const struct gl_state_variable gl_state_{}[] = {{",
+            suffix
+        )?;
+        for table in tables {
+            writeln!(out, "    /* {} */", table.title.replace("*/", "* /"))?;
+            for entry in &table.entries {
+                let Some(get_value) = entry.get_value.as_deref() else {
+                    continue;
+                };
+                write_entry(out, get_value, entry)?;
+            }
+        }
+        writeln!(out, "}};")?;
+        writeln!(
+            out,
+            "#define GL_STATE_{}_COUNT (sizeof gl_state_{} / sizeof gl_state_{}[0])",
+            suffix.to_ascii_uppercase(),
+            suffix,
+            suffix
+        )?;
+    }
+    writeln!(out)?;
+    writeln!(out, "#endif /* GL_STATE_TABLES_H */")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{attribute_bits, c_string, write_header};
+
+    #[test]
+    fn strings() {
+        assert_eq!(c_string(None), "NULL");
+        assert_eq!(c_string(Some("GL_BLEND")), "\"GL_BLEND\"");
+        assert_eq!(c_string(Some("a \"b\" \\c\n")), "\"a \\\"b\\\" \\\\c\\n\"");
+    }
+
+    #[test]
+    fn attributes() {
+        assert_eq!(attribute_bits(None), ("0".to_string(), "0".to_string()));
+        assert_eq!(
+            attribute_bits(Some("lighting/enable")),
+            (
+                "GL_LIGHTING_BIT | GL_ENABLE_BIT".to_string(),
+                "0".to_string()
+            )
+        );
+        assert_eq!(
+            attribute_bits(Some("vertex-array/select")),
+            ("0".to_string(), "GL_CLIENT_VERTEX_ARRAY_BIT".to_string())
+        );
+        assert_eq!(
+            attribute_bits(Some("fog/new-group")),
+            (
+                "GL_FOG_BIT /* unknown attribute group new-group */".to_string(),
+                "0".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn header() {
+        let mut out = Vec::new();
+        crate::with_specs(|specs| write_header(&mut out, specs)).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains(
+            "{\"GL_LIGHT0\", NULL, \"glIsEnabled\", GL_STATE_KIND_BOOLEAN, 1, 8, NULL, 0, \
+             GL_LIGHTING_BIT | GL_ENABLE_BIT, 0},"
+        ));
+        assert!(out.contains("static const struct gl_state_variable gl_state_es[] = {"));
+        assert!(out.trim_end().ends_with("#endif /* GL_STATE_TABLES_H */"));
+    }
+}
@@ -0,0 +1,204 @@
+//! Flat CSV and TSV export, with one row per entry, for spreadsheets and the
+//! like. The columns are:
+//!
+//! - `spec`: the spec's file suffix (`es11`, `es` or `gl`)
+//! - `table_label`, `table_title`
+//! - `condition`: `core`, `compatibility`, `imaging subset`, or empty if the
+//!   entry isn't conditional
+//! - `get_value`, `alt_get_value`
+//! - `series_minimum`: if the entry is a series (like `LIGHT0`, `LIGHT1`, …),
+//!   the minimum number of values in it, and otherwise empty. The get value is
+//!   the first of the series, and the other columns describe each value.
+//! - `type`: the type in the spec's LaTeX notation. Types that could be parsed
+//!   are written canonically, and others are written as they were in the spec.
+//! - `scalar_kind`: `boolean`, `integer`, `enum`, `float`, `pointer`,
+//!   `string` or `opaque`, or empty if the type couldn't be parsed
+//! - `get_cmnd`, `initial_value`, `description`, `attribute`
+//! - `footnotes`: the text of every footnote the entry refers to, one per line
+//!
+//! Text is as it was in the spec's LaTeX source, so math is still in `$...$`.
+//! Missing fields are empty.
+//!
+//! CSV is quoted following RFC 4180. TSV has no quoting, so tabs, newlines and
+//! backslashes in fields are written as `\t`, `\n` and `\\` instead.
+
+use crate::SpecTables;
+use std::borrow::Cow;
+use std::io::{self, Write};
+use OpenGL_state_table_parser::types::{scalar_kind, type_to_latex, MaybeParsed};
+use OpenGL_state_table_parser::Condition;
+
+#[derive(Clone, Copy)]
+pub enum Format {
+    Csv,
+    Tsv,
+}
+
+const COLUMNS: [&str; 14] = [
+    "spec",
+    "table_label",
+    "table_title",
+    "condition",
+    "get_value",
+    "alt_get_value",
+    "series_minimum",
+    "type",
+    "scalar_kind",
+    "get_cmnd",
+    "initial_value",
+    "description",
+    "attribute",
+    "footnotes",
+];
+
+/// Quote a CSV field if needed, following RFC 4180.
+pub fn csv_field(value: &str) -> Cow<'_, str> {
+    if value.contains([',', '"', '\r', '\n']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+fn tsv_field(value: &str) -> Cow<'_, str> {
+    if value.contains(['\t', '\r', '\n', '\\']) {
+        Cow::Owned(
+            value
+                .replace('\\', "\\\\")
+                .replace('\t', "\\t")
+                .replace('\r', "\\r")
+                .replace('\n', "\\n"),
+        )
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+fn write_record<'a>(
+    out: &mut impl Write,
+    format: Format,
+    fields: impl IntoIterator<Item = &'a str>,
+) -> io::Result<()> {
+    for (index, field) in fields.into_iter().enumerate() {
+        match format {
+            Format::Csv => {
+                if index != 0 {
+                    write!(out, ",")?;
+                }
+                write!(out, "{}", csv_field(field))?;
+            }
+            Format::Tsv => {
+                if index != 0 {
+                    write!(out, "\t")?;
+                }
+                write!(out, "{}", tsv_field(field))?;
+            }
+        }
+    }
+    match format {
+        Format::Csv => write!(out, "\r\n"),
+        Format::Tsv => writeln!(out),
+    }
+}
+
+/// Write every entry of the tables of each spec in [crate::SPECS], with a
+/// header row.
+pub fn write_entries(out: &mut impl Write, format: Format, specs: &[SpecTables]) -> io::Result<()> {
+    write_record(out, format, COLUMNS)?;
+    for &(suffix, _, _, tables) in specs {
+        for table in tables {
+            for entry in &table.entries {
+                let condition = match entry.condition {
+                    Some(Condition::Core) => "core",
+                    Some(Condition::Compatibility) => "compatibility",
+                    Some(Condition::ImagingSubset) => "imaging subset",
+                    None => "",
+                };
+                let series_minimum = entry
+                    .series
+                    .as_ref()
+                    .map(|minimum| minimum.to_string())
+                    .unwrap_or_default();
+                let (type_, scalar_kind) = match entry.type_ {
+                    Some(MaybeParsed::Parsed(ref type_)) => {
+                        (type_to_latex(type_), scalar_kind(type_).to_string())
+                    }
+                    Some(MaybeParsed::Unparsed(ref type_)) => (type_.clone(), String::new()),
+                    None => (String::new(), String::new()),
+                };
+
+                let mut footnotes: Vec<usize> = [
+                    &entry.get_value_footnotes,
+                    &entry.type_footnotes,
+                    &entry.initial_value_footnotes,
+                    &entry.description_footnotes,
+                ]
+                .into_iter()
+                .flatten()
+                .copied()
+                .collect();
+                footnotes.sort();
+                footnotes.dedup();
+                let footnotes: Vec<&str> = footnotes
+                    .into_iter()
+                    .map(|index| &*table.footnotes[index])
+                    .collect();
+                let footnotes = footnotes.join("\n");
+
+                write_record(
+                    out,
+                    format,
+                    [
+                        suffix,
+                        &table.label,
+                        &table.title,
+                        condition,
+                        entry.get_value.as_deref().unwrap_or(""),
+                        entry.alt_get_value.as_deref().unwrap_or(""),
+                        &series_minimum,
+                        &type_,
+                        &scalar_kind,
+                        entry.get_cmnd.as_deref().unwrap_or(""),
+                        entry.initial_value.as_deref().unwrap_or(""),
+                        &entry.description,
+                        entry.attribute.as_deref().unwrap_or(""),
+                        &footnotes,
+                    ],
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{csv_field, tsv_field, write_entries, Format, COLUMNS};
+
+    #[test]
+    fn quoting() {
+        assert_eq!(csv_field("GetFloatv"), "GetFloatv");
+        assert_eq!(csv_field("a, \"b\""), "\"a, \"\"b\"\"\"");
+        assert_eq!(csv_field("a\nb"), "\"a\nb\"");
+        assert_eq!(tsv_field("a\tb\\c\nd"), "a\\tb\\\\c\\nd");
+    }
+
+    #[test]
+    fn one_record_per_entry() {
+        let mut out = Vec::new();
+        let entries = crate::with_specs(|specs| {
+            write_entries(&mut out, Format::Tsv, specs).unwrap();
+            specs
+                .iter()
+                .flat_map(|&(_, _, _, tables)| tables)
+                .map(|table| table.entries.len())
+                .sum::<usize>()
+        });
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.lines().next().unwrap(), COLUMNS.join("\t"));
+        assert_eq!(out.lines().count(), entries + 1);
+        assert!(out
+            .lines()
+            .all(|line| line.split('\t').count() == COLUMNS.len()));
+    }
+}
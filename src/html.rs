@@ -0,0 +1,468 @@
+//! HTML rendering of the state tables, shared by the single-page output and
+//! the multi-page site.
+
+use crate::site::file_name;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use OpenGL_state_table_parser::mathml::{escape_html, math_to_mathml, text_to_html, Macros};
+use OpenGL_state_table_parser::types::{write_quantity, write_type, MaybeParsed};
+use OpenGL_state_table_parser::{Condition, Entry, Table};
+
+pub const DISCLAIMER: &str = "<p><strong>This page is not produced by the Khronos Group, and cannot substitute for the Khronos Group specifications.</strong> This page is an independently created composite and reinterpretation that may contain inaccuracies; you rely on it at your own risk. Always consult <a href=\"https://registry.khronos.org/OpenGL/\">the Khronos Group specifications</a>. OpenGL® and OpenGL ES™ are trademarks used under license by the Khronos Group.</p>";
+
+pub const LICENSE: &str = "<p><a href=\"https://github.com/hikari-no-yume/OpenGL-state-table-parser\">OpenGL-state-table-parser</a> © 2023 hikari_no_yume. The content of this page may be redistributed under <a href=\"https://spdx.org/licenses/CC-BY-4.0.html\">CC BY 4.0</a>.</p>";
+
+pub fn class_for_condition(condition: &Option<Condition>) -> &str {
+    match condition {
+        Some(Condition::Compatibility) => "compatibility-only",
+        Some(Condition::Core) => "core-only",
+        Some(Condition::ImagingSubset) => "imaging-subset",
+        None => "no-condition",
+    }
+}
+
+/// The anchor for a table, which avoids clashing between specs.
+pub fn table_anchor(spec_prefix: &str, table: &Table) -> String {
+    format!("{}-{}", spec_prefix, table.label)
+}
+
+fn footnote_name(label: &str, index: usize) -> String {
+    format!("{}-fn-{}", label, index)
+}
+
+/// Footnote symbols in the traditional order. Once they're used up, they
+/// are doubled, then tripled, and so on.
+pub fn footnote_symbol(index: usize) -> String {
+    const SYMBOLS: [char; 5] = ['†', '‡', '§', '¶', '‖'];
+    let symbol = SYMBOLS[index % SYMBOLS.len()];
+    std::iter::repeat_n(symbol, index / SYMBOLS.len() + 1).collect()
+}
+
+/// An entry row's anchor, and whether the row carries it.
+pub type EntryAnchor = Option<(String, bool)>;
+
+/// Work out the anchors for all the entries in a spec's tables. Core and
+/// compatibility variants of an entry share the first one's anchor. Get values
+/// that appear in more than one table (e.g. both texture and sampler state) are
+/// qualified with the table label after the first. Get values are made safe
+/// like [file_name] does, and numbered if that makes two of them the same.
+pub fn entry_anchors(spec_prefix: &str, tables: &[Table]) -> Vec<Vec<EntryAnchor>> {
+    // The label of the table each get value first appeared in
+    let mut first_table: HashMap<&str, &str> = HashMap::new();
+    // The get value each anchor has been given to
+    let mut owners: HashMap<String, &str> = HashMap::new();
+    tables
+        .iter()
+        .map(|table| {
+            table
+                .entries
+                .iter()
+                .map(|entry| {
+                    let get_value = entry.get_value.as_deref()?;
+                    let first_table = *first_table.entry(get_value).or_insert(&table.label);
+                    let base = if first_table == table.label {
+                        format!("{}-{}", spec_prefix, file_name(get_value))
+                    } else {
+                        format!("{}-{}-{}", spec_prefix, table.label, file_name(get_value))
+                    };
+                    let mut anchor = base.clone();
+                    let mut number = 1;
+                    while owners.get(&anchor).is_some_and(|&owner| owner != get_value) {
+                        number += 1;
+                        anchor = format!("{}-{}", base, number);
+                    }
+                    let is_new = owners.insert(anchor.clone(), get_value).is_none();
+                    Some((anchor, is_new))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// An entry by its spec, table and entry indices.
+pub type EntryIndex = (usize, usize, usize);
+
+/// Find the entries in other specs that are for the same state variable as
+/// each entry, matching by both get value and alternative get value. `specs`
+/// is the tables of each spec.
+pub fn equivalent_entries(specs: &[&[Table]]) -> HashMap<EntryIndex, Vec<EntryIndex>> {
+    fn names<'a>(entry: &'a Entry) -> impl Iterator<Item = &'a str> {
+        entry
+            .get_value
+            .iter()
+            .chain(&entry.alt_get_value)
+            .map(|name| &**name)
+    }
+
+    let mut by_name: HashMap<&str, Vec<EntryIndex>> = HashMap::new();
+    for (spec_index, tables) in specs.iter().enumerate() {
+        for (table_index, table) in tables.iter().enumerate() {
+            for (entry_index, entry) in table.entries.iter().enumerate() {
+                for name in names(entry) {
+                    by_name
+                        .entry(name)
+                        .or_default()
+                        .push((spec_index, table_index, entry_index));
+                }
+            }
+        }
+    }
+
+    let mut equivalents = HashMap::new();
+    for (spec_index, tables) in specs.iter().enumerate() {
+        for (table_index, table) in tables.iter().enumerate() {
+            for (entry_index, entry) in table.entries.iter().enumerate() {
+                let mut matches: Vec<EntryIndex> = names(entry)
+                    .flat_map(|name| &by_name[name])
+                    .filter(|&&(other_spec, _, _)| other_spec != spec_index)
+                    .copied()
+                    .collect();
+                if matches.is_empty() {
+                    continue;
+                }
+                matches.sort();
+                matches.dedup();
+                equivalents.insert((spec_index, table_index, entry_index), matches);
+            }
+        }
+    }
+    equivalents
+}
+
+/// An entry for the same state variable in another spec.
+pub struct Equivalent<'a> {
+    pub spec_name: &'a str,
+    pub table_title: &'a str,
+    /// Link to the entry's row.
+    pub href: String,
+    pub entry: &'a Entry<'a>,
+}
+
+/// Where an entry row's links should point.
+pub struct EntryLinks<'a> {
+    /// The spec's anchor prefix, e.g. `gl`.
+    pub spec_prefix: &'a str,
+    /// The page the entry's table is on, for links to its footnotes and
+    /// permalink. This is empty if it's the current page.
+    pub table_page: &'a str,
+    pub anchor: &'a EntryAnchor,
+    /// The page for the entry's state variable, if there is one.
+    pub variable_page: Option<String>,
+    pub equivalents: Vec<Equivalent<'a>>,
+}
+
+/// The fields that are compared between specs, with the names used for them
+/// in the "also in" links.
+pub const COMPARED_FIELDS: [&str; 4] = ["type", "get command", "initial value", "attribute"];
+
+/// Which of [COMPARED_FIELDS] differ between two entries. Whitespace
+/// differences in the LaTeX source don't count.
+pub fn differing_fields(entry: &Entry, other: &Entry) -> [bool; 4] {
+    fn normalize(text: &Option<Cow<str>>) -> Option<String> {
+        text.as_deref()
+            .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "))
+    }
+    [
+        entry.type_ != other.type_ || entry.series.is_some() != other.series.is_some(),
+        entry.get_cmnd != other.get_cmnd,
+        normalize(&entry.initial_value) != normalize(&other.initial_value),
+        entry.attribute != other.attribute,
+    ]
+}
+
+pub fn write_table_columns(out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "<thead>")?;
+    writeln!(out, "<tr>")?;
+    writeln!(out, "<th>Get value</th>")?;
+    writeln!(out, "<th>Type</th>")?;
+    writeln!(out, "<th>Get command</th>")?;
+    writeln!(out, "<th>Initial value</th>")?;
+    writeln!(out, "<th>Description</th>")?;
+    writeln!(out, "<th>Attribute</th>")?;
+    writeln!(out, "</tr>")?;
+    writeln!(out, "</thead>")
+}
+
+/// Write the row with a table's title, caption and footnotes. `macros` are the
+/// ones defined by the table's spec, for rendering math.
+pub fn write_table_header(
+    out: &mut impl Write,
+    table: &Table,
+    label: &str,
+    macros: &Macros,
+) -> io::Result<()> {
+    // special classes for filtering only
+    let mut section_classes = String::from("section-header ");
+    for entry in &table.entries {
+        let class = class_for_condition(&entry.condition);
+        if section_classes.contains(class) {
+            continue;
+        }
+        section_classes.push(' ');
+        section_classes.push_str("has-");
+        section_classes.push_str(class);
+    }
+
+    writeln!(
+        out,
+        "<tr id=\"{}\" class=\"{}\">",
+        escape_html(label),
+        section_classes
+    )?;
+    writeln!(out, "<td colspan=6>")?;
+    writeln!(
+        out,
+        "<h3><a href=\"#{}\">§</a> {}</h3>",
+        escape_html(label),
+        escape_html(&table.title)
+    )?;
+    if let Some(ref caption) = table.caption {
+        writeln!(out, "<p>{}</p>", text_to_html(caption, macros))?;
+    }
+    if !table.footnotes.is_empty() {
+        writeln!(out, "<ol>")?;
+        for (index, footnote) in table.footnotes.iter().enumerate() {
+            writeln!(
+                out,
+                "<li id=\"{}\">{} {}</li>",
+                escape_html(&footnote_name(label, index)),
+                footnote_symbol(index),
+                text_to_html(footnote, macros)
+            )?;
+        }
+        writeln!(out, "</ol>")?;
+    }
+    writeln!(out, "</td>")?;
+    writeln!(out, "</tr>")
+}
+
+/// Write the row for an entry. `label` is the table's anchor, and `macros` are
+/// the ones defined by its spec.
+pub fn write_entry(
+    out: &mut impl Write,
+    entry: &Entry,
+    label: &str,
+    links: &EntryLinks,
+    macros: &Macros,
+) -> io::Result<()> {
+    let reference_footnotes = |out: &mut dyn Write, indices: &[usize]| -> io::Result<()> {
+        for &index in indices {
+            write!(
+                out,
+                "<sup><a href=\"{}#{}\">{}</a></sup>",
+                links.table_page,
+                escape_html(&footnote_name(label, index)),
+                footnote_symbol(index)
+            )?;
+        }
+        Ok(())
+    };
+
+    write!(out, "<tr")?;
+    if let Some((ref anchor, true)) = links.anchor {
+        write!(out, " id=\"{}\"", escape_html(anchor))?;
+    }
+    write!(out, " class={}", class_for_condition(&entry.condition))?;
+    // Series members like LIGHT3 don't get their own row, so the page's
+    // script uses this to send links for them to the series row instead.
+    // This is the anchor a member's link would have, minus its number, even if
+    // this row's own anchor had to be qualified or numbered.
+    if let (Some(get_value), Some(_)) = (entry.get_value.as_deref(), &entry.series) {
+        if let Some(prefix) = get_value.strip_suffix('0') {
+            write!(
+                out,
+                " data-series=\"{}-{}\"",
+                escape_html(links.spec_prefix),
+                escape_html(&file_name(prefix))
+            )?;
+        }
+    }
+    writeln!(out, ">")?;
+
+    write!(out, "<td>")?;
+    if let Some((ref anchor, _)) = links.anchor {
+        write!(
+            out,
+            "<a class=permalink href=\"{}#{}\">§</a> ",
+            links.table_page,
+            escape_html(anchor)
+        )?;
+    }
+    if let Some(ref get_value) = entry.get_value {
+        if let Some(ref variable_page) = links.variable_page {
+            write!(
+                out,
+                "<a href=\"{}\"><code>{}</code></a>",
+                variable_page,
+                escape_html(get_value)
+            )?;
+        } else {
+            write!(out, "<code>{}</code>", escape_html(get_value))?;
+        }
+    } else {
+        write!(out, "—")?;
+    }
+    reference_footnotes(out, &entry.get_value_footnotes)?;
+    if let Some(ref alt_get_value) = entry.alt_get_value {
+        write!(
+            out,
+            " <em>or</em><br> <code>{}</code>",
+            escape_html(alt_get_value)
+        )?;
+    }
+    if let Some(ref minimum) = entry.series {
+        let first_value = entry.get_value.as_deref().unwrap();
+        write!(
+            out,
+            " …<br><code>{}</code> + (<var>n</var>-1)<br>where <var>n</var> ≥ ",
+            escape_html(first_value)
+        )?;
+        write_quantity(out, minimum)?;
+    }
+    let mut differs = [false; 4];
+    if !links.equivalents.is_empty() {
+        write!(out, "<small class=equivalents>Also in: ")?;
+        for (index, equivalent) in links.equivalents.iter().enumerate() {
+            if index > 0 {
+                write!(out, ", ")?;
+            }
+            write!(
+                out,
+                "<a href=\"{}\" title=\"{}\">{}{}</a>",
+                escape_html(&equivalent.href),
+                escape_html(equivalent.table_title),
+                equivalent.spec_name,
+                match equivalent.entry.condition {
+                    Some(Condition::Core) => " core",
+                    Some(Condition::Compatibility) => " compatibility",
+                    Some(Condition::ImagingSubset) => " imaging subset",
+                    None => "",
+                }
+            )?;
+            let fields = differing_fields(entry, equivalent.entry);
+            let names: Vec<&str> = COMPARED_FIELDS
+                .iter()
+                .zip(fields)
+                .filter(|&(_, differs)| differs)
+                .map(|(&name, _)| name)
+                .collect();
+            if !names.is_empty() {
+                write!(out, " (different {})", names.join(", "))?;
+            }
+            for (differs, field) in differs.iter_mut().zip(fields) {
+                *differs |= field;
+            }
+        }
+        write!(out, "</small>")?;
+    }
+    writeln!(out, "</td>")?;
+
+    let open_cell = |out: &mut dyn Write, differs: bool| {
+        write!(
+            out,
+            "{}",
+            if differs {
+                "<td class=differs>"
+            } else {
+                "<td>"
+            }
+        )
+    };
+
+    open_cell(out, differs[0])?;
+    if entry.series.is_some() {
+        write!(out, "<var>n</var> × ")?;
+    }
+    if let Some(ref type_) = entry.type_ {
+        match type_ {
+            MaybeParsed::Parsed(t) => write_type(out, t)?,
+            MaybeParsed::Unparsed(s) => {
+                let mathml = s
+                    .strip_prefix('$')
+                    .and_then(|s| s.strip_suffix('$'))
+                    .and_then(|math| math_to_mathml(math, macros));
+                match mathml {
+                    Some(mathml) => write!(out, "{}", mathml)?,
+                    None => write!(out, "{}", escape_html(s))?,
+                }
+            }
+        }
+    } else if entry.type_footnotes.is_empty() {
+        write!(out, "—")?;
+    }
+    reference_footnotes(out, &entry.type_footnotes)?;
+    writeln!(out, "</td>")?;
+
+    open_cell(out, differs[1])?;
+    if let Some(ref get_cmnd) = entry.get_cmnd {
+        writeln!(out, "<code>{}</code></td>", escape_html(get_cmnd))?;
+    } else {
+        writeln!(out, "—</td>")?;
+    }
+
+    open_cell(out, differs[2])?;
+    if let Some(ref initial_value) = entry.initial_value {
+        write!(out, "{}", text_to_html(initial_value, macros))?;
+    } else if entry.initial_value_footnotes.is_empty() {
+        write!(out, "—")?;
+    }
+    reference_footnotes(out, &entry.initial_value_footnotes)?;
+    writeln!(out, "</td>")?;
+
+    write!(out, "<td>")?;
+    write!(out, "{}", text_to_html(&entry.description, macros))?;
+    reference_footnotes(out, &entry.description_footnotes)?;
+    writeln!(out, "</td>")?;
+
+    open_cell(out, differs[3])?;
+    if let Some(ref attribute) = entry.attribute {
+        writeln!(out, "{}</td>", escape_html(attribute))?;
+    } else {
+        writeln!(out, "—</td>")?;
+    }
+
+    writeln!(out, "</tr>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use OpenGL_state_table_parser::{parse_spec, read_spec};
+
+    #[test]
+    fn series_rows() {
+        let source = read_spec("gl");
+        let (_, tables) = parse_spec("gl", &source);
+        let macros = Macros::from_source(&source);
+        let entry = tables
+            .iter()
+            .flat_map(|table| &table.entries)
+            .find(|entry| entry.get_value.as_deref() == Some("LIGHT0"))
+            .unwrap();
+        let row = |anchor: &str| {
+            let anchor = Some((anchor.to_string(), true));
+            let links = EntryLinks {
+                spec_prefix: "gl",
+                table_page: "",
+                anchor: &anchor,
+                variable_page: None,
+                equivalents: Vec::new(),
+            };
+            let mut out = Vec::new();
+            write_entry(&mut out, entry, "gl-label", &links, &macros).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+        // Links to LIGHT3 etc. lose their number, so the series is named after
+        // the get value whatever the row's own anchor is.
+        for anchor in ["gl-LIGHT0", "gl-LIGHT0-2", "gl-label-LIGHT0"] {
+            let row = row(anchor);
+            assert!(
+                row.starts_with(&format!("<tr id=\"{}\" ", anchor)),
+                "{}",
+                row
+            );
+            assert!(row.contains(" data-series=\"gl-LIGHT\""), "{}", row);
+        }
+    }
+}
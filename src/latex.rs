@@ -0,0 +1,186 @@
+//! A tokenizer for the subset of LaTeX used by the state table sources.
+//!
+//! This doesn't try to expand macros or track category codes, it just splits
+//! the source into the pieces the parser cares about, so that e.g. `\fi` can
+//! be told apart from `\fill` and a `\{` isn't mistaken for a group.
+
+/// A byte range within the source text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// A backslash followed by one or more letters, e.g. `\doentry`
+    ControlWord,
+    /// A backslash followed by a single non-letter, e.g. `\_` or `\%`
+    ControlSymbol,
+    /// `{`
+    BeginGroup,
+    /// `}`
+    EndGroup,
+    /// `$`
+    MathShift,
+    /// `%` and the rest of the line, not including the newline
+    Comment,
+    /// A run of whitespace, including newlines
+    Whitespace,
+    /// A run of any other characters
+    Text,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span,
+}
+
+impl Token {
+    /// The text of this token within `source`.
+    pub fn text<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.span.start..self.span.end]
+    }
+
+    /// Whether this token is the control word `\name` in `source`.
+    pub fn is_control_word(&self, source: &str, name: &str) -> bool {
+        self.kind == TokenKind::ControlWord && self.text(source)[1..] == *name
+    }
+}
+
+fn is_special(c: char) -> bool {
+    matches!(c, '\\' | '{' | '}' | '$' | '%') || c.is_whitespace()
+}
+
+pub fn tokenize(source: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+    while let Some(c) = source[offset..].chars().next() {
+        let rest = &source[offset..];
+        let (kind, len) = match c {
+            '\\' => {
+                let name_len = rest[1..]
+                    .find(|c: char| !c.is_ascii_alphabetic())
+                    .unwrap_or(rest.len() - 1);
+                if name_len > 0 {
+                    (TokenKind::ControlWord, 1 + name_len)
+                } else {
+                    let symbol_len = rest[1..].chars().next().map_or(0, char::len_utf8);
+                    (TokenKind::ControlSymbol, 1 + symbol_len)
+                }
+            }
+            '{' => (TokenKind::BeginGroup, 1),
+            '}' => (TokenKind::EndGroup, 1),
+            '$' => (TokenKind::MathShift, 1),
+            '%' => (TokenKind::Comment, rest.find('\n').unwrap_or(rest.len())),
+            c if c.is_whitespace() => (
+                TokenKind::Whitespace,
+                rest.find(|c: char| !c.is_whitespace())
+                    .unwrap_or(rest.len()),
+            ),
+            _ => (TokenKind::Text, rest.find(is_special).unwrap_or(rest.len())),
+        };
+        tokens.push(Token {
+            kind,
+            span: Span {
+                start: offset,
+                end: offset + len,
+            },
+        });
+        offset += len;
+    }
+    tokens
+}
+
+/// Find the [TokenKind::EndGroup] matching the [TokenKind::BeginGroup] at
+/// `tokens[open]`, returning its index.
+pub fn matching_end_group(tokens: &[Token], open: usize) -> Option<usize> {
+    let mut depth: u32 = 0;
+    for (index, token) in tokens.iter().enumerate().skip(open) {
+        match token.kind {
+            TokenKind::BeginGroup => depth += 1,
+            TokenKind::EndGroup => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => (),
+        }
+    }
+    None
+}
+
+/// Skip over any whitespace and comments starting at `tokens[index]`,
+/// returning the index of the next token of substance.
+pub fn skip_whitespace(tokens: &[Token], mut index: usize) -> usize {
+    while tokens
+        .get(index)
+        .is_some_and(|token| matches!(token.kind, TokenKind::Whitespace | TokenKind::Comment))
+    {
+        index += 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds_and_texts(source: &str) -> Vec<(TokenKind, &str)> {
+        tokenize(source)
+            .into_iter()
+            .map(|token| (token.kind, token.text(source)))
+            .collect()
+    }
+
+    #[test]
+    fn tokenize_control_sequences() {
+        use TokenKind::*;
+        assert_eq!(
+            kinds_and_texts("\\fi\\fill \\_x\\fn1"),
+            [
+                (ControlWord, "\\fi"),
+                (ControlWord, "\\fill"),
+                (Whitespace, " "),
+                (ControlSymbol, "\\_"),
+                (Text, "x"),
+                (ControlWord, "\\fn"),
+                (Text, "1"),
+            ]
+        );
+        assert_eq!(kinds_and_texts("\\"), [(ControlSymbol, "\\")]);
+    }
+
+    #[test]
+    fn tokenize_groups_math_and_comments() {
+        use TokenKind::*;
+        assert_eq!(
+            kinds_and_texts("{$Z^{+}$} % comment\n\\%"),
+            [
+                (BeginGroup, "{"),
+                (MathShift, "$"),
+                (Text, "Z^"),
+                (BeginGroup, "{"),
+                (Text, "+"),
+                (EndGroup, "}"),
+                (MathShift, "$"),
+                (EndGroup, "}"),
+                (Whitespace, " "),
+                (Comment, "% comment"),
+                (Whitespace, "\n"),
+                (ControlSymbol, "\\%"),
+            ]
+        );
+    }
+
+    #[test]
+    fn matching_end_group_skips_escapes() {
+        let source = "{a\\{b}c}";
+        let tokens = tokenize(source);
+        let end = matching_end_group(&tokens, 0).unwrap();
+        assert_eq!(tokens[end].span.start, 5);
+        assert_eq!(matching_end_group(&tokenize("{{}"), 0), None);
+    }
+}
@@ -0,0 +1,1148 @@
+#![allow(non_snake_case)] // let me capitalize the crate name, Rust!
+
+pub mod latex;
+pub mod mathml;
+pub mod mock;
+#[cfg(test)]
+mod tests;
+pub mod types;
+use latex::{Span, Token, TokenKind};
+use std::borrow::Cow;
+use types::{parse_quantity, parse_type, MaybeParsed, Quantity, Type};
+
+/// Match a set of curly braces potentially containing nested curly braces.
+/// Returns the content of the outermost set of braces, and the remaining text.
+///
+/// Panics if the text doesn't start with a balanced set of braces (other than
+/// leading whitespace), which would mean we're looking in the wrong place.
+pub fn read_cell(text: &str) -> (&str, &str) {
+    try_read_cell(text).unwrap_or_else(|| panic!("Expected a braced group: {:?}", text))
+}
+
+/// Like [read_cell], but returns [None] rather than panicking.
+pub fn try_read_cell(text: &str) -> Option<(&str, &str)> {
+    let start = text.find(|c: char| !c.is_whitespace())?;
+    if !text[start..].starts_with('{') {
+        return None;
+    }
+    let mut offset = start;
+    let mut depth: u32 = 0;
+    loop {
+        offset += text[offset..].find(['{', '}'])?;
+        // A brace preceded by an odd number of backslashes is escaped, and an
+        // even number is just a series of escaped backslashes.
+        let backslashes = text[..offset].len() - text[..offset].trim_end_matches('\\').len();
+        if backslashes % 2 == 1 {
+            offset += 1;
+            continue;
+        }
+        if text[offset..].starts_with('{') {
+            offset += 1;
+            depth += 1;
+        } else {
+            offset += 1;
+            depth -= 1;
+            if depth == 0 {
+                break;
+            }
+        }
+    }
+    let (cell, remainder) = text.split_at(offset);
+    Some((&cell[start + 1..offset - 1], remainder))
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Condition {
+    /// This entry is only in the core profile. It might have a different
+    /// definition in the compatibility profile.
+    Core,
+    /// This entry is only in the compatibility profile. It might have a
+    /// different definition in the core profile.
+    Compatibility,
+    /// This entry is only in the Imaging Subset, which is only in the
+    /// compatibility profile.
+    ImagingSubset,
+}
+
+/// A state table
+///
+/// The text fields borrow from the LaTeX source (see [parse_spec]) wherever
+/// they didn't need rewriting.
+#[derive(Debug)]
+pub struct Table<'src> {
+    /// Where the table's header is in the LaTeX source
+    pub span: Span,
+    /// This is a "string used to describe the table in the index"
+    pub title: Cow<'src, str>,
+    /// Extra text that follows `title`
+    pub caption: Option<Cow<'src, str>>,
+    /// An internal label within the LaTeX source
+    pub label: Cow<'src, str>,
+    /// Footnotes that are referenced by entries
+    pub footnotes: Vec<Cow<'src, str>>,
+    /// If this is true, the entries' "Initial value" is really a minimum value,
+    /// as in the implementation-dependent tables.
+    pub minimum_values: bool,
+    /// The entries in (rows of) the state table
+    pub entries: Vec<Entry<'src>>,
+}
+
+/// An entry in one of the state tables, representing a state variable
+///
+/// The text fields borrow from the LaTeX source (see [parse_spec]) wherever
+/// they didn't need rewriting.
+#[derive(Debug)]
+pub struct Entry<'src> {
+    /// Where the row this entry came from is in the LaTeX source. Rows that are
+    /// expanded into several entries give them all the same span.
+    pub span: Span,
+    /// If this is [Some], the entry is only defined when this condition
+    /// applies.
+    pub condition: Option<Condition>,
+    /// "Get value" (symbolic constant to pass to "Get command")
+    pub get_value: Option<Cow<'src, str>>,
+    /// Indices of table footnotes referenced by the get value
+    pub get_value_footnotes: Vec<usize>,
+    /// Alternative "Get value", if any. This is not necessarily a synonym, e.g.
+    /// `TRANSPOSE_` versions of matrices.
+    ///
+    /// These alternative values only seem to appear in the GL compatibility
+    /// profile, so you can ignore them for core profile OpenGL and both
+    /// versions of OpenGL ES. They're also mutually exclusive with `series`.
+    pub alt_get_value: Option<Cow<'src, str>>,
+    /// If this is `Some(n)`, there is a series of at least `n` values, and the
+    /// symbolic constant named by `get_value` is just the first of them.
+    ///
+    /// Subsequent values are referred to by the numeric value of that constant
+    /// plus the index of that value from zero, or alternatively by a constant
+    /// whose name is formed by substituting the index for `0`. The index will
+    /// be referred to in `description` as `$i$`.
+    ///
+    /// One example of such a series is `GL_TEXTURE0`.
+    pub series: Option<Quantity>,
+    /// "Type"
+    ///
+    /// There's only one case that doesn't have a type: `GetUniform`.
+    pub type_: Option<MaybeParsed<Type>>,
+    /// Indices of table footnotes referenced by the type
+    pub type_footnotes: Vec<usize>,
+    /// "Get command" (function that can query this state variable)
+    ///
+    /// If this is [None], the variable is inaccessible.
+    pub get_cmnd: Option<Cow<'src, str>>,
+    /// "Initial value" (or "Minimum value", see [Table::minimum_values])
+    pub initial_value: Option<Cow<'src, str>>,
+    /// Indices of table footnotes referenced by the initial value
+    pub initial_value_footnotes: Vec<usize>,
+    /// "Description"
+    pub description: Cow<'src, str>,
+    /// Indices of table footnotes referenced by the description
+    pub description_footnotes: Vec<usize>,
+    /// "Attribute" (which attribute group to use with `PushAttrib`/`PopAttrib`
+    /// or `PushClientAttrib`/`PopClientAttrib` as applicable)
+    ///
+    /// Attribute groups are a legacy feature that only exists in the OpenGL
+    /// compatibility profile. Not even OpenGL ES 1.1 has them, though the state
+    /// tables nonetheless include attribute group information for some reason?
+    pub attribute: Option<Cow<'src, str>>,
+}
+
+impl Entry<'_> {
+    /// Whether this entry is in a profile, where [None] means every profile.
+    /// The Imaging Subset is part of the compatibility profile.
+    pub fn in_profile(&self, profile: Option<Condition>) -> bool {
+        match (profile, self.condition) {
+            (_, None) | (None, _) => true,
+            (Some(Condition::Core), Some(condition)) => condition == Condition::Core,
+            (Some(_), Some(condition)) => condition != Condition::Core,
+        }
+    }
+
+    /// Whether a get value names this entry, either as its get value or
+    /// alternative get value, or as a member of its series, like `LIGHT3`.
+    pub fn is_named(&self, name: &str) -> bool {
+        if self.alt_get_value.as_deref() == Some(name) {
+            return true;
+        }
+        let Some(get_value) = self.get_value.as_deref() else {
+            return false;
+        };
+        if get_value == name {
+            return true;
+        }
+        let Some(ref minimum) = self.series else {
+            return false;
+        };
+        let prefix = &get_value[..get_value.len() - 1];
+        let Some(index) = name.strip_prefix(prefix) else {
+            return false;
+        };
+        let Ok(index) = index.parse::<u32>() else {
+            return false;
+        };
+        // Leading zeros would make it a different name
+        index.to_string() == name[prefix.len()..]
+            && match minimum {
+                Quantity::Integer(minimum) => index < *minimum,
+                Quantity::Constant(_) => true,
+            }
+    }
+}
+
+/// Narrow `cell` down to `part`, which must be a slice of it, borrowing from
+/// the source if `cell` does.
+fn subslice<'src>(cell: &Cow<'src, str>, part: &str) -> Cow<'src, str> {
+    match cell {
+        Cow::Borrowed(cell) => {
+            let start = part.as_ptr() as usize - cell.as_ptr() as usize;
+            Cow::Borrowed(&cell[start..start + part.len()])
+        }
+        Cow::Owned(_) => Cow::Owned(part.to_string()),
+    }
+}
+
+/// Whether [unescape] would change `cell` at all.
+fn needs_unescaping(cell: &str) -> bool {
+    cell.contains('\\')
+        || cell.contains("``")
+        || cell.contains("''")
+        || cell.contains("  ")
+        || cell.contains(|c: char| c.is_whitespace() && c != ' ')
+        || cell.starts_with(' ')
+        || cell.ends_with(' ')
+}
+
+fn unescape(cell: Cow<str>) -> Cow<str> {
+    if needs_unescaping(&cell) {
+        Cow::Owned(unescape_to_string(&cell))
+    } else {
+        cell
+    }
+}
+
+fn unescape_to_string(cell: &str) -> String {
+    let tokens = latex::tokenize(cell);
+    let mut unescaped = String::with_capacity(cell.len());
+    // Whitespace is collapsed HTML-style, so it's only output once we know
+    // there's something after it
+    let mut seen_content = false;
+    let mut pending_space = false;
+    // Whether each currently open group's braces are being kept
+    let mut groups: Vec<bool> = Vec::new();
+
+    let mut index = 0;
+    while index < tokens.len() {
+        let token = tokens[index];
+        index += 1;
+        let is_change_marker = |token: &Token| {
+            token.is_control_word(cell, "change") || token.is_control_word(cell, "ochange")
+        };
+
+        let text = match token.kind {
+            TokenKind::Whitespace => {
+                pending_space = seen_content;
+                continue;
+            }
+            // Remove group around change marker
+            TokenKind::BeginGroup if tokens.get(index).is_some_and(is_change_marker) => {
+                groups.push(false);
+                continue;
+            }
+            TokenKind::BeginGroup => {
+                groups.push(true);
+                "{"
+            }
+            TokenKind::EndGroup if groups.pop() == Some(false) => continue,
+            // Remove change marker with accompanying issue number annotation
+            TokenKind::ControlWord
+                if token.is_control_word(cell, "change")
+                    && tokens
+                        .get(index)
+                        .is_some_and(|next| next.is_control_word(cell, "cbext"))
+                    && tokens.get(index + 1).map(|next| next.kind)
+                        == Some(TokenKind::BeginGroup) =>
+            {
+                index = latex::matching_end_group(&tokens, index + 1)
+                    .map_or(tokens.len(), |end| end + 1);
+                continue;
+            }
+            // Remove change markers (not a kind of escaping but annoying)
+            TokenKind::ControlWord if is_change_marker(&token) => continue,
+            // Remove small-font markup (the spec isn't consistent about using
+            // this and it's not semantically useful)
+            TokenKind::ControlWord
+                if token.is_control_word(cell, "small")
+                    && tokens.get(index).map(|next| next.kind) == Some(TokenKind::Whitespace)
+                    && tokens.get(index + 1).is_some() =>
+            {
+                index += 1;
+                continue;
+            }
+            // Unescape underscores
+            TokenKind::ControlSymbol if token.text(cell) == "\\_" => "_",
+            // Remove line-wrap hyphenation
+            TokenKind::ControlSymbol if token.text(cell) == "\\-" => "",
+            _ => token.text(cell),
+        };
+
+        if pending_space {
+            unescaped.push(' ');
+            pending_space = false;
+        }
+        seen_content = true;
+        if token.kind == TokenKind::Text && text.contains(['`', '\'']) {
+            // LaTeX quotes to curly quotes
+            unescaped.push_str(&text.replace("``", "“").replace("''", "”"));
+        } else {
+            unescaped.push_str(text);
+        }
+    }
+    unescaped
+}
+
+/// Remove an expected multiplication in a type, i.e. turn
+/// "A times B" into just "B".
+fn divide(type_: &str, by: usize) -> String {
+    type_.replace(&format!("{} \\times ", by), "")
+}
+
+/// The combination of the conditonal expansion and parameter expansion can
+/// result in entries that have identical core and compatibility variants.
+/// This function does a simple deduplication.
+fn push_entry<'src>(entries: &mut Vec<Entry<'src>>, new_entry: Entry<'src>) {
+    if new_entry.condition.is_some() {
+        for existing_entry in entries.iter_mut().rev() {
+            // These duplicates only occur within a single function. Don't waste
+            // time if we're no longer in the same section etc.
+            if existing_entry.condition.is_none()
+                || existing_entry.type_ != new_entry.type_
+                || existing_entry.get_cmnd != new_entry.get_cmnd
+                || existing_entry.initial_value != new_entry.initial_value
+                || existing_entry.description != new_entry.description
+                || existing_entry.attribute != new_entry.attribute
+            {
+                break;
+            }
+
+            if existing_entry.get_value == new_entry.get_value {
+                assert_ne!(existing_entry.condition, new_entry.condition);
+                existing_entry.condition = None;
+                return;
+            }
+        }
+    }
+
+    entries.push(new_entry);
+}
+
+/// Parse a footnote marker at the start of `text`, returning the index of the
+/// footnote it refers to and the remaining text.
+///
+/// The OpenGL and OpenGL ES specs define `\fn1` and `\fnb` for the first and
+/// second footnotes of a table; further footnotes are assumed to continue the
+/// same lettering (`\fnc`, `\fnd`, …). The OpenGL ES 1.1 spec instead uses
+/// `\footnotemark[n]`, which is numbered from one.
+fn parse_footnote_marker(text: &str) -> Option<(usize, &str)> {
+    if let Some(rest) = text.strip_prefix("\\footnotemark[") {
+        let (number, rest) = rest.split_once(']')?;
+        let index = number.parse::<usize>().ok()?.checked_sub(1)?;
+        return Some((index, rest));
+    }
+    let rest = text.strip_prefix("\\fn")?;
+    let index = match rest.bytes().next()? {
+        c @ b'1'..=b'9' => usize::from(c - b'1'),
+        c @ b'a'..=b'z' => usize::from(c - b'a'),
+        _ => return None,
+    };
+    let rest = &rest[1..];
+    // Don't mistake some other macro (e.g. \fnord) for a footnote marker
+    if rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    Some((index, rest))
+}
+
+/// Find the first footnote marker in `text`, returning the text before it, the
+/// footnote index, and the text after it.
+fn find_footnote_marker(text: &str) -> Option<(&str, usize, &str)> {
+    text.match_indices('\\').find_map(|(offset, _)| {
+        parse_footnote_marker(&text[offset..]).map(|(index, after)| (&text[..offset], index, after))
+    })
+}
+
+/// Strip any footnote references from the end of a cell. The references are
+/// returned in the order they appear, as indices that still need resolving
+/// with [resolve_footnote_refs].
+fn extract_footnote_refs(cell: Cow<str>) -> (Option<Cow<str>>, Vec<usize>) {
+    let mut remaining: &str = cell.trim_end();
+    let mut footnote_refs = Vec::new();
+    while let Some((before, index)) = remaining.match_indices('\\').rev().find_map(|(offset, _)| {
+        parse_footnote_marker(&remaining[offset..])
+            .filter(|(_, after)| after.trim().is_empty())
+            .map(|(index, _)| (&remaining[..offset], index))
+    }) {
+        footnote_refs.insert(0, index);
+        remaining = before.trim_end();
+    }
+
+    if footnote_refs.is_empty() {
+        return (Some(cell), footnote_refs);
+    }
+    let remaining = remaining.trim();
+    if remaining.is_empty() {
+        (None, footnote_refs)
+    } else {
+        (Some(subslice(&cell, remaining)), footnote_refs)
+    }
+}
+
+/// Footnote markers are local to wherever the footnote is defined. Markers
+/// defined within the current row are looked up in `row_footnotes` (pairs of
+/// marker index and table footnote index); any others refer to the footnotes
+/// in the table header.
+fn resolve_footnote_refs(
+    footnote_refs: Vec<usize>,
+    row_footnotes: &[(usize, usize)],
+    table: &Table<'_>,
+) -> Vec<usize> {
+    footnote_refs
+        .into_iter()
+        .map(|index| {
+            let index = row_footnotes
+                .iter()
+                .find(|&&(marker, _)| marker == index)
+                .map_or(index, |&(_, table_index)| table_index);
+            assert!(table.footnotes.get(index).is_some());
+            index
+        })
+        .collect()
+}
+
+fn process_row<'src>(
+    spec: &str,
+    condition: Option<Condition>,
+    span: Span,
+    cells: [Cow<'src, str>; 7],
+    constants: &[(&'src str, Cow<'src, str>)],
+    table: &mut Table<'src>,
+) {
+    let [get_value, type_, get_cmnd, initial_value, description, section, attribute] = cells;
+
+    // Process a variant of this row, with some of its cells rewritten
+    let mut process_variant = |condition: Option<Condition>,
+                               get_value: Cow<'src, str>,
+                               type_: Cow<'src, str>,
+                               description: Cow<'src, str>| {
+        process_row(
+            spec,
+            condition,
+            span,
+            [
+                get_value,
+                type_,
+                get_cmnd.clone(),
+                initial_value.clone(),
+                description,
+                section.clone(),
+                attribute.clone(),
+            ],
+            constants,
+            table,
+        )
+    };
+
+    // The description might contain a deprecation conditional. Expand both
+    // branches for machine-friendliness. This is also a prerequisite for
+    // expanding some parameterised get_value cases (see below).
+    if let Some(dep_offset) = description.find("\\dep{") {
+        let (before, after) = description.split_at(dep_offset);
+        let (conditional, after) = read_cell(&after[after.find('{').unwrap()..]);
+        let description_compatibility = Cow::Owned(format!("{}{}{}", before, conditional, after));
+        let description_core = Cow::Owned(format!("{}{}", before, after));
+        match condition {
+            Some(Condition::Compatibility) => {
+                process_variant(condition, get_value, type_, description_compatibility)
+            }
+            Some(Condition::Core) => process_variant(condition, get_value, type_, description_core),
+            Some(Condition::ImagingSubset) => unimplemented!(),
+            None => {
+                process_variant(
+                    Some(Condition::Compatibility),
+                    get_value.clone(),
+                    type_.clone(),
+                    description_compatibility,
+                );
+                process_variant(Some(Condition::Core), get_value, type_, description_core);
+            }
+        }
+        return;
+    }
+
+    let (get_value, get_value_footnotes) = extract_footnote_refs(unescape(get_value));
+    let get_value = get_value.unwrap_or_default();
+
+    // Some of these values are parameterised for compactness. We have to handle
+    // this in one way or another, let's expand them for machine-friendliness.
+
+    // These expansions for RGBA PixelMap values are listed in the section for
+    // "The Imaging Subset" in a table labelled "PixelMap parameters".
+    const PIXEL_MAP_RGBA_MODES: &[&str] = &[
+        "PIXEL_MAP_I_TO_R",
+        "PIXEL_MAP_I_TO_G",
+        "PIXEL_MAP_I_TO_B",
+        "PIXEL_MAP_I_TO_A",
+        "PIXEL_MAP_R_TO_R",
+        "PIXEL_MAP_G_TO_G",
+        "PIXEL_MAP_B_TO_B",
+        "PIXEL_MAP_A_TO_A",
+    ];
+    const PIXEL_MAP_INDEX_MODES: &[&str] = &["PIXEL_MAP_I_TO_I", "PIXEL_MAP_S_TO_S"];
+
+    // TEXTURE_1D, TEXTURE_2D, TEXTURE_3D, and related enums.
+    if get_value.contains("$x$D") {
+        let dimensions: &[&str] = if spec == "gl" {
+            &["1", "2", "3"]
+        } else {
+            &["2", "3"]
+        };
+        for dimension in dimensions {
+            let get_value = get_value.replace("$x$", dimension);
+            // Remove vectorness
+            let type_ = divide(&type_, dimensions.len());
+            // Remove list of dimensions ("x is 1, 2, or 3.") from description,
+            // then expand.
+            let description = description
+                .split_once("; $x$ is")
+                .map_or(&*description, |(before, _after)| before)
+                .replace("$x$", dimension);
+            process_variant(
+                condition,
+                get_value.into(),
+                type_.into(),
+                description.into(),
+            );
+        }
+        return;
+    // These expansions for the BIAS and SCALE values are listed in the section
+    // for "The Imaging Subset" in a table labelled "PixelTransfer parameters".
+    } else if section == "\\ref{pix:xfer}"
+        && (get_value.contains("$x$_BIAS") || get_value.contains("$x$_SCALE"))
+        && !description.contains(',')
+    {
+        for component in ["RED", "GREEN", "BLUE", "ALPHA"] {
+            let get_value = get_value.replace("$x$", component);
+            let description = description.replace("$x$", component);
+            process_variant(
+                condition,
+                get_value.into(),
+                type_.clone(),
+                description.into(),
+            );
+        }
+        return;
+    } else if section == "\\ref{pix:xfer}" && get_value == "$x$" && get_cmnd.contains("GetPixelMap")
+    {
+        let modes = if description.contains("RGBA") {
+            PIXEL_MAP_RGBA_MODES
+        } else {
+            assert!(description.contains("Index"));
+            PIXEL_MAP_INDEX_MODES
+        };
+        for mode in modes {
+            let get_value = get_value.replace("$x$", mode);
+            // Remove vectorness
+            let type_ = divide(&type_, modes.len());
+            // Remove plural and explanation of $x$.
+            let description =
+                subslice(&description, description.split_once("s; $x$ is").unwrap().0);
+            process_variant(condition, get_value.into(), type_.into(), description);
+        }
+        return;
+    } else if section == "\\ref{pix:xfer}"
+        && get_value == "$x$_SIZE"
+        && get_cmnd.contains("GetIntegerv")
+    {
+        for mode in PIXEL_MAP_RGBA_MODES
+            .iter()
+            .chain(PIXEL_MAP_INDEX_MODES.iter())
+        {
+            let get_value = get_value.replace("$x$", mode);
+            let description = description.replace("$x$", mode);
+            process_variant(
+                condition,
+                get_value.into(),
+                type_.clone(),
+                description.into(),
+            );
+        }
+        return;
+    // These expansions for Map1/Map2 values are listed in the "Evaluators"
+    // section table labelled "Values specified by the target to Map1".
+    } else if (get_value == "MAP1_$x$" || get_value == "MAP2_$x$") && get_cmnd == "\\glr{IsEnabled}"
+    {
+        let values = [
+            "VERTEX_3",
+            "VERTEX_4",
+            "INDEX",
+            "COLOR_4",
+            "NORMAL",
+            "TEXTURE_COORD_1",
+            "TEXTURE_COORD_2",
+            "TEXTURE_COORD_3",
+            "TEXTURE_COORD_4",
+        ];
+        for value in values {
+            let get_value = get_value.replace("$x$", value);
+            // Remove plural and explanation of $x$.
+            let description =
+                subslice(&description, description.split_once("s: $x$ is").unwrap().0);
+            // Remove vectorness.
+            let type_ = divide(&type_, values.len());
+            process_variant(condition, get_value.into(), type_.into(), description);
+        }
+        return;
+    } else if get_value.contains("$x$") {
+        // Some values conveniently list their expansions in their descriptions.
+        if let Some((description, expansions)) = description
+            .split_once("; $x$ is one of ")
+            .or_else(|| description.split_once(";\n$x$ is one of "))
+            .or_else(|| description.split_once(".    $x$ is one of "))
+            .or_else(|| description.split_once(". $x$ is one of "))
+            .or_else(|| description.split_once("; $x$ is "))
+            .or_else(|| description.split_once(" ($x$ is "))
+        {
+            if expansions.contains(',') {
+                let expansions = expansions.strip_suffix(')').unwrap_or(expansions);
+                let expansions: Vec<_> = expansions.split(',').collect();
+                for expansion in expansions.iter() {
+                    let expansion = expansion.trim();
+                    let expansion = expansion.strip_prefix("or ").unwrap_or(expansion);
+                    let expansion = expansion.strip_prefix("\\glc{").unwrap_or(expansion);
+                    let expansion = expansion.strip_suffix('}').unwrap_or(expansion);
+
+                    let get_value = get_value.replace("$x$", expansion);
+                    // Remove vectorness
+                    let type_ =
+                        if let Some(stripped) = type_.strip_prefix("$\\mtexbasefmt \\times ") {
+                            format!("${}", stripped)
+                        } else {
+                            divide(&type_, expansions.len())
+                        };
+                    let description = description.replace("$x$", expansion);
+                    process_variant(
+                        condition,
+                        get_value.into(),
+                        type_.into(),
+                        description.into(),
+                    );
+                }
+                return;
+            }
+        }
+    }
+
+    // In OpenGL ES 1.1's spec, the whole type is implicitly inline math
+    let type_ = if spec == "es11" {
+        Some(Cow::Owned(format!("${}$", type_)))
+    // Absent type (only example is GetUniform, which isn't in OpenGL ES 1.1)
+    } else if type_ == "$-$" {
+        None
+    } else {
+        Some(type_)
+    };
+
+    let (get_value, alt_get_value, series, type_) =
+        // Match absent get value. OpenGL ES 1.1's "buffer data" row leaves
+        // the cell empty rather than using a dash.
+        if get_value.is_empty() || get_value == "-" || get_value == "--" {
+            (None, None, None, type_)
+        // Match series like GL_TEXTUREn, GL_CLIP_PLANEn etc.
+        } else if let Some(prefix) = get_value.strip_suffix("$i$") {
+            let first_get_value = format!("{}0", prefix);
+            // Extract minimum count from type
+            let (count, type_) = type_.as_deref().unwrap().split_once(" \\times ").unwrap();
+            let count = count.strip_prefix('$').unwrap();
+            // Handle annoying exception
+            let count = count
+                .strip_prefix('{')
+                .and_then(|count| count.strip_suffix('}'))
+                .unwrap_or(count);
+            // "*" means "at least"
+            let count = count.strip_suffix('*').unwrap();
+            let count = parse_quantity(count).unwrap();
+            // Ensure LaTeX inline math characters are balanced in type
+            let type_ = Some(Cow::Owned(format!("${}", type_)));
+            (Some(Cow::Owned(first_get_value)), None, Some(count), type_)
+        // Match alternate name
+        } else if let Some((first_get_value, alt_get_value)) = get_value.split_once(" \\hbox{(") {
+            let alt_get_value = alt_get_value.strip_suffix(")}").unwrap();
+            (
+                Some(subslice(&get_value, first_get_value)),
+                Some(subslice(&get_value, alt_get_value)),
+                None,
+                type_,
+            )
+        } else {
+            (Some(get_value), None, None, type_)
+        };
+
+    let get_cmnd = if get_cmnd == "\\vbox{\\hbox{{\\bf GetIntegerv},}\\hbox{\\bf GetFloatv}}" {
+        // Weird outlier: CURRENT_COLOR has two commands listed in the
+        // OpenGL ES spec, unlike every other variable in these three specs.
+        // So far as I can tell there's no good reason for this, since even
+        // this old spec has a specific color type (C) and there doesn't seem to
+        // be any special handling for this variable. The OpenGL 4.6 spec says
+        // just GetFloatv, so let's normalise to that.
+        assert!(spec == "es11" && get_value.as_deref().unwrap() == "CURRENT_COLOR");
+        Some(Cow::Borrowed("GetFloatv"))
+    // Absent get command
+    } else if get_cmnd == "--" || get_cmnd == "-" {
+        None
+    // The old spec doesn't use \glr{}
+    } else if spec == "es11" {
+        Some(get_cmnd)
+    } else {
+        Some(subslice(
+            &get_cmnd,
+            get_cmnd
+                .strip_prefix("\\glr{")
+                .unwrap()
+                .strip_suffix('}')
+                .unwrap(),
+        ))
+    }
+    .map(unescape);
+
+    let initial_value = if initial_value == "--" || initial_value == "-" {
+        None
+    } else {
+        let mut initial_value = unescape(initial_value);
+
+        // Replace constants. These are used for things like MAX_DRAW_BUFFERS
+        // so that types can be parameterised by them. The "initial value"
+        // in this case is the spec's minimum for that constant, and we don't
+        // want an unhelpful recursive definition; a different approach is taken
+        // in the types code.
+        for (name, value) in constants {
+            if initial_value.contains(name) {
+                initial_value = Cow::Owned(initial_value.replace(name, value));
+            }
+        }
+
+        Some(initial_value)
+    };
+
+    // Extract footnote references for description first, to avoid confusing
+    // the code that extracts footnote definitions in the description.
+    let (description, description_footnotes) = extract_footnote_refs(unescape(description));
+    let description = description.unwrap();
+
+    // Extract footnote definitions from the description. These are moved to
+    // the table header so we don't need two footnote systems, which means
+    // their markers have to be renumbered to follow any footnotes that are
+    // already there.
+    let mut row_footnotes = Vec::new();
+    let description =
+        if let Some((before, mut marker, mut remaining)) = find_footnote_marker(&description) {
+            loop {
+                let (footnote, next) = match find_footnote_marker(remaining) {
+                    Some((footnote, next_marker, next_remaining)) => {
+                        (footnote, Some((next_marker, next_remaining)))
+                    }
+                    None => (remaining, None),
+                };
+                let footnote = footnote.trim();
+                // Conditional expansion can process the same row more than once
+                let index = table
+                    .footnotes
+                    .iter()
+                    .position(|existing| existing == footnote)
+                    .unwrap_or_else(|| {
+                        table.footnotes.push(subslice(&description, footnote));
+                        table.footnotes.len() - 1
+                    });
+                row_footnotes.push((marker, index));
+                let Some((next_marker, next_remaining)) = next else {
+                    break;
+                };
+                (marker, remaining) = (next_marker, next_remaining);
+            }
+            subslice(&description, before.trim())
+        } else {
+            description
+        };
+
+    let get_value_footnotes = resolve_footnote_refs(get_value_footnotes, &row_footnotes, table);
+    let description_footnotes = resolve_footnote_refs(description_footnotes, &row_footnotes, table);
+    let (initial_value, initial_value_footnotes) =
+        initial_value.map_or((None, Vec::new()), extract_footnote_refs);
+    let initial_value_footnotes =
+        resolve_footnote_refs(initial_value_footnotes, &row_footnotes, table);
+    let (type_, type_footnotes) = type_.map_or((None, Vec::new()), extract_footnote_refs);
+    let type_footnotes = resolve_footnote_refs(type_footnotes, &row_footnotes, table);
+
+    let attribute = if attribute == "--" || attribute == "-" {
+        None
+    } else {
+        Some(attribute)
+    };
+
+    let type_ = type_.map(|type_| {
+        if let Some(parsed_type) = parse_type(&type_) {
+            MaybeParsed::Parsed(parsed_type)
+        } else {
+            MaybeParsed::Unparsed(type_.into_owned())
+        }
+    });
+
+    // Note that the section is ignored because we don't have access to the
+    // LaTeX source of the full spec, so we can't resolve to a section number.
+
+    push_entry(
+        &mut table.entries,
+        Entry {
+            span,
+            condition,
+            get_value,
+            get_value_footnotes,
+            alt_get_value,
+            series,
+            type_,
+            type_footnotes,
+            get_cmnd,
+            initial_value,
+            initial_value_footnotes,
+            description,
+            description_footnotes,
+            attribute,
+        },
+    );
+}
+
+/// The text of a group in the LaTeX source, given the tokens between its
+/// braces. Comments are removed, and so is indentation and any blank lines, as
+/// the rest of the parser is easier to write without them.
+fn group_text<'src>(source: &'src str, tokens: &[Token], span: Span) -> Cow<'src, str> {
+    let raw = &source[span.start..span.end];
+    if !raw.contains('\n') && !tokens.iter().any(|token| token.kind == TokenKind::Comment) {
+        return Cow::Borrowed(raw);
+    }
+
+    let without_comments: String = tokens
+        .iter()
+        .filter(|token| token.kind != TokenKind::Comment)
+        .map(|token| token.text(source))
+        .collect();
+    let line_count = without_comments.split('\n').count();
+    let mut text = String::with_capacity(without_comments.len());
+    for (index, line) in without_comments.split('\n').enumerate() {
+        let is_first = index == 0;
+        let is_last = index == line_count - 1;
+        // The first and last lines are shared with the braces, so they're
+        // only trimmed on the side that isn't.
+        let line = if is_first { line } else { line.trim_start() };
+        let line = if is_last { line } else { line.trim_end() };
+        if line.is_empty() && !is_first && !is_last {
+            continue;
+        }
+        if !is_first {
+            text.push('\n');
+        }
+        text.push_str(line);
+    }
+    Cow::Owned(text)
+}
+
+/// Read the group starting at `tokens[index]` (after any whitespace and
+/// comments), returning its text and the index of the token following it.
+fn read_group<'src>(source: &'src str, tokens: &[Token], index: usize) -> (Cow<'src, str>, usize) {
+    let open = latex::skip_whitespace(tokens, index);
+    assert_eq!(
+        tokens.get(open).map(|token| token.kind),
+        Some(TokenKind::BeginGroup),
+        "Expected a group at byte {}",
+        tokens
+            .get(open)
+            .map_or(source.len(), |token| token.span.start)
+    );
+    let close = latex::matching_end_group(tokens, open).expect("Unbalanced braces");
+    let span = Span {
+        start: tokens[open].span.end,
+        end: tokens[close].span.start,
+    };
+    (
+        group_text(source, &tokens[open + 1..close], span),
+        close + 1,
+    )
+}
+
+/// Skip an optional argument (`[...]`) starting at `tokens[index]`, if there
+/// is one, returning the index of the token following it.
+fn skip_optional_argument(source: &str, tokens: &[Token], index: usize) -> usize {
+    let is_text_containing =
+        |token: &Token, c: char| token.kind == TokenKind::Text && token.text(source).contains(c);
+    match tokens.get(index) {
+        Some(token) if is_text_containing(token, '[') => tokens[index..]
+            .iter()
+            .position(|token| is_text_containing(token, ']'))
+            .map_or(tokens.len(), |offset| index + offset + 1),
+        _ => index,
+    }
+}
+
+/// Read the LaTeX source in `tables_src/gettables.{spec}.tex`, for use with
+/// [parse_spec].
+pub fn read_spec(spec: &str) -> String {
+    std::fs::read_to_string(format!("tables_src/gettables.{}.tex", spec)).expect("Can't open file")
+}
+
+/// Parse the state tables in the LaTeX `source` for `spec` (see [read_spec]).
+/// Returns the copyright notice from the top of the file, and the tables, which
+/// borrow from `source`.
+pub fn parse_spec<'src>(spec: &str, source: &'src str) -> (String, Vec<Table<'src>>) {
+    let mut copyright_text = String::new();
+    for line in source.lines().take(3) {
+        copyright_text.push_str(line.strip_prefix("% ").unwrap());
+        copyright_text.push('\n');
+    }
+
+    let tokens = latex::tokenize(source);
+
+    // Split the spec into macro definitions and entries sections using this
+    // divider
+    let divider = tokens
+        .iter()
+        .position(|token| {
+            token.kind == TokenKind::Comment
+                && token.text(source)
+                    == "%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%"
+        })
+        .expect("Can't find divider");
+
+    // Parse definitions of some special constants
+    let mut constants = Vec::new();
+    let mut index = 0;
+    while index < divider {
+        let token = tokens[index];
+        index += 1;
+        if token.kind != TokenKind::ControlWord {
+            continue;
+        }
+
+        // Constant definition
+        if token.is_control_word(source, "def")
+            && tokens[index].kind == TokenKind::ControlWord
+            && tokens[index].text(source).starts_with("\\m")
+        {
+            let def_name = tokens[index].text(source);
+            let (def_value, new_index) = read_group(source, &tokens, index + 1);
+            index = new_index;
+
+            constants.push((def_name, def_value));
+        // ugly hack: the only conditional constant definition (\mtexbasefmt) is
+        // one we don't need the value of, so we can stop at this point. this
+        // also avoids any confusion with the conditionals inside the table
+        // entry macro definitions :)
+        } else if token.text(source).starts_with("\\if") {
+            break;
+        }
+    }
+
+    // Parse table headers and entries
+    let mut tables = Vec::new();
+    let mut current_condition: Option<Condition> = None;
+    let mut index = divider + 1;
+
+    while index < tokens.len() {
+        let token = tokens[index];
+        index += 1;
+        if token.kind != TokenKind::ControlWord {
+            continue;
+        }
+
+        let condition = match &token.text(source)[1..] {
+            // Normal entry or entry marking a change from a previous version
+            "doentry" | "cbentry" | "ocbentry" => current_condition,
+            // Imaging subset (deprecated) entry
+            "graydepentry" => Some(Condition::ImagingSubset),
+            // Deprecated entry
+            "depentry" => Some(Condition::Compatibility),
+            // Probably the beginning of a table
+            "begin" => {
+                let start = token.span.start;
+                let (kind, new_index) = read_group(source, &tokens, index);
+                index = new_index;
+
+                if ![
+                    "statetable",
+                    "statetableindex",
+                    "statetabledifferentindexcaption",
+                ]
+                .contains(&&*kind)
+                {
+                    continue;
+                };
+
+                index = skip_optional_argument(source, &tokens, index);
+                let mut next_cell = || {
+                    let (cell, new_index) = read_group(source, &tokens, index);
+                    index = new_index;
+                    cell
+                };
+
+                // OpenGL-only macro
+                let (title, caption, label) = if kind == "statetableindex" {
+                    let title = next_cell();
+                    let caption = next_cell();
+                    let label = next_cell();
+                    (unescape(title), Some(unescape(caption)), label)
+                // OpenGL ES-only macro
+                } else if kind == "statetabledifferentindexcaption" {
+                    // TODO: remove title from caption
+                    let caption = next_cell();
+                    let label = next_cell();
+                    let title = next_cell();
+                    let title = unescape(title);
+                    let caption = unescape(caption);
+                    let caption = subslice(&caption, caption.strip_prefix(&*title).unwrap());
+                    (title, Some(caption), label)
+                // Common macro
+                } else {
+                    assert!(kind == "statetable");
+                    let title = next_cell();
+                    let label = next_cell();
+                    if let Some((title_part, caption)) = title.split_once('\n') {
+                        let caption = subslice(&title, caption);
+                        (
+                            unescape(subslice(&title, title_part)),
+                            Some(unescape(caption)),
+                            label,
+                        )
+                    // Special hack for “Lighting (see also …)” in ES 1.1 spec to
+                    // make it consistent with GL 4.6.
+                    } else if title.contains(" (see also") {
+                        let (title_part, caption) = title.split_once(' ').unwrap();
+                        let caption = subslice(&title, caption);
+                        (
+                            unescape(subslice(&title, title_part)),
+                            Some(unescape(caption)),
+                            label,
+                        )
+                    } else {
+                        (unescape(title), None, label)
+                    }
+                };
+
+                // Extract footnotes from the caption
+                let mut footnotes = Vec::new();
+                let caption = if caption
+                    .as_deref()
+                    .is_some_and(|caption| find_footnote_marker(caption).is_some())
+                {
+                    let caption = caption.as_ref().unwrap();
+                    let mut remaining: &str = caption;
+                    loop {
+                        remaining = remaining.trim_start();
+                        let (footnote, new_remaining) = if remaining.starts_with("{\\par") {
+                            read_cell(remaining)
+                        } else if remaining.starts_with("\\par") {
+                            let footnote_end = remaining[1..]
+                                .find("\\par")
+                                .map_or(remaining.len(), |i| i + 1);
+                            remaining.split_at(footnote_end)
+                        } else {
+                            assert!(remaining.is_empty());
+                            break;
+                        };
+                        remaining = new_remaining;
+
+                        let (index, footnote) = parse_footnote_marker(
+                            footnote.strip_prefix("\\par").unwrap().trim_start(),
+                        )
+                        .unwrap();
+                        // Footnotes are assumed to be defined in marker order
+                        assert_eq!(index, footnotes.len());
+                        footnotes.push(subslice(caption, footnote.trim_start()));
+                    }
+                    None
+                } else {
+                    caption
+                };
+
+                tables.push(Table {
+                    span: Span {
+                        start,
+                        end: tokens[index - 1].span.end,
+                    },
+                    title,
+                    caption,
+                    label,
+                    footnotes,
+                    minimum_values: false,
+                    entries: Vec::new(),
+                });
+                continue;
+            }
+            // Table header for implementation-dependent values (these are
+            // also \def'd before any table, which is ignored)
+            "newlimitstart" | "newshortlimitstart" => {
+                if let Some(table) = tables.last_mut() {
+                    table.minimum_values = true;
+                }
+                continue;
+            }
+            // Conditionals
+            "ifnum" => {
+                if tokens[index].is_control_word(source, "specdep")
+                    && tokens[index + 1].text(source).starts_with("=1")
+                {
+                    assert!(current_condition.is_none());
+                    current_condition = Some(Condition::Compatibility);
+                }
+                continue;
+            }
+            "else" => {
+                assert!(current_condition == Some(Condition::Compatibility));
+                current_condition = Some(Condition::Core);
+                continue;
+            }
+            "fi" => {
+                assert!(current_condition.is_some());
+                current_condition = None;
+                continue;
+            }
+            _ => continue,
+        };
+
+        let start = token.span.start;
+        let mut cells = Vec::new();
+        let column_count = if spec == "es11" { 8 } else { 7 };
+        for _ in 0..column_count {
+            let (cell, new_index) = read_group(source, &tokens, index);
+            cells.push(cell);
+            index = new_index;
+        }
+
+        let span = Span {
+            start,
+            end: tokens[index - 1].span.end,
+        };
+        let order = if spec == "es11" {
+            [4, 1, 3, 2, 5, 6, 7]
+        } else {
+            [0, 1, 2, 3, 4, 5, 6]
+        };
+        let cells = order.map(|column| std::mem::take(&mut cells[column]));
+
+        process_row(
+            spec,
+            condition,
+            span,
+            cells,
+            &constants,
+            tables.last_mut().unwrap(),
+        );
+    }
+
+    (copyright_text, tables)
+}
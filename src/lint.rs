@@ -0,0 +1,862 @@
+//! Linting C and C++ sources for state queries that don't match the state
+//! tables: calls like `glGetIntegerv(GL_X, ...)`, `glGetTexParameterfv(...,
+//! GL_Y, ...)` and `glIsEnabled(GL_Z)` where the pname isn't a state variable
+//! in the target spec or profile, the command isn't the one the spec lists (or
+//! may lose precision), or the output buffer is too small for the value (or is
+//! a single variable, for a value with a variable number of values).
+//!
+//! This only scans the tokens of each file, so it only checks pnames written as
+//! `GL_` constants, and only knows the size of a buffer if it's a local array
+//! or variable declared earlier in the same file.
+
+use crate::SpecTables;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use OpenGL_state_table_parser::types::{
+    basic_component_count, is_matrix, type_quantity, MaybeParsed, Quantity,
+};
+use OpenGL_state_table_parser::{Condition, Entry};
+
+/// What to check against: a spec, and for OpenGL, a profile.
+#[derive(Clone, Copy)]
+pub struct Target {
+    pub suffix: &'static str,
+    pub profile: Option<Condition>,
+}
+
+impl Target {
+    /// Parse a target from the command line: `es11`, `es`, `gl-core` or
+    /// `gl-compatibility`.
+    pub fn parse(target: &str) -> Option<Target> {
+        let (suffix, profile) = match target {
+            "es11" => ("es11", None),
+            "es" => ("es", None),
+            "gl-core" => ("gl", Some(Condition::Core)),
+            "gl-compatibility" => ("gl", Some(Condition::Compatibility)),
+            _ => return None,
+        };
+        Some(Target { suffix, profile })
+    }
+
+    /// The name of the target, given its spec's name, e.g. "the OpenGL 4.6
+    /// core profile".
+    pub fn name(&self, spec_name: &str) -> String {
+        match self.profile {
+            Some(Condition::Core) => format!("the {} core profile", spec_name),
+            Some(_) => format!("the {} compatibility profile", spec_name),
+            None => spec_name.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum TokenKind {
+    Identifier,
+    Number,
+    Punctuation,
+}
+
+struct Token<'a> {
+    kind: TokenKind,
+    text: &'a str,
+    line: usize,
+    column: usize,
+}
+
+/// Split C or C++ source into identifiers, numbers and punctuation, skipping
+/// whitespace, comments, and string and character literals.
+fn tokenize(source: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut line_start = 0;
+    let mut chars = source.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let column = source[line_start..start].chars().count() + 1;
+        let rest = &source[start..];
+        let skip_to = |end: usize, chars: &mut std::iter::Peekable<std::str::CharIndices>| {
+            while chars.peek().is_some_and(|&(index, _)| index < end) {
+                chars.next();
+            }
+        };
+        if c == '\n' {
+            line += 1;
+            line_start = start + 1;
+        } else if c.is_whitespace() {
+        } else if rest.starts_with("//") {
+            let end = rest.find('\n').map_or(source.len(), |end| start + end);
+            skip_to(end, &mut chars);
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            let end = comment
+                .find("*/")
+                .map_or(source.len(), |end| start + 2 + end + 2);
+            for (offset, c) in rest[..end - start].char_indices() {
+                if c == '\n' {
+                    line += 1;
+                    line_start = start + offset + 1;
+                }
+            }
+            skip_to(end, &mut chars);
+        } else if c == '"' || c == '\'' {
+            while let Some((_, next)) = chars.next() {
+                match next {
+                    '\\' => {
+                        chars.next();
+                    }
+                    '\n' => {
+                        // Unterminated, which can't be valid anyway
+                        line += 1;
+                        break;
+                    }
+                    next if next == c => break,
+                    _ => (),
+                }
+            }
+        } else if c.is_alphanumeric() || c == '_' {
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .map_or(source.len(), |end| start + end);
+            skip_to(end, &mut chars);
+            tokens.push(Token {
+                kind: if c.is_ascii_digit() {
+                    TokenKind::Number
+                } else {
+                    TokenKind::Identifier
+                },
+                text: &source[start..end],
+                line,
+                column,
+            });
+        } else {
+            tokens.push(Token {
+                kind: TokenKind::Punctuation,
+                text: &source[start..start + c.len_utf8()],
+                line,
+                column,
+            });
+        }
+    }
+    tokens
+}
+
+/// The families of query commands that the linter knows, with which argument
+/// is the pname and which (if any) is the output buffer.
+pub const COMMANDS: [(&str, usize, Option<usize>); 38] = [
+    ("GetBoolean", 0, Some(1)),
+    ("GetInteger", 0, Some(1)),
+    ("GetInteger64", 0, Some(1)),
+    ("GetFloat", 0, Some(1)),
+    ("GetDouble", 0, Some(1)),
+    ("IsEnabled", 0, None),
+    ("GetString", 0, None),
+    ("GetPointer", 0, Some(1)),
+    ("GetMultisample", 0, None),
+    ("GetTexParameter", 1, Some(2)),
+    ("GetTextureParameter", 1, Some(2)),
+    ("GetSamplerParameter", 1, Some(2)),
+    ("GetBufferParameter", 1, Some(2)),
+    ("GetNamedBufferParameter", 1, Some(2)),
+    ("GetRenderbufferParameter", 1, Some(2)),
+    ("GetFramebufferParameter", 1, Some(2)),
+    ("GetQuery", 1, Some(2)),
+    ("GetQueryObject", 1, Some(2)),
+    ("GetProgram", 1, Some(2)),
+    ("GetShader", 1, Some(2)),
+    ("GetProgramPipeline", 1, Some(2)),
+    ("GetVertexAttrib", 1, Some(2)),
+    ("GetSync", 1, None),
+    ("GetTexEnv", 1, Some(2)),
+    ("GetTexGen", 1, Some(2)),
+    ("GetLight", 1, Some(2)),
+    ("GetMaterial", 1, Some(2)),
+    ("GetColorTableParameter", 1, Some(2)),
+    ("GetConvolutionParameter", 1, Some(2)),
+    ("GetHistogramParameter", 1, Some(2)),
+    ("GetMinmaxParameter", 1, Some(2)),
+    ("GetTexLevelParameter", 2, Some(3)),
+    ("GetFramebufferAttachmentParameter", 2, Some(3)),
+    ("GetActiveUniformBlock", 2, Some(3)),
+    ("GetProgramInterface", 2, Some(3)),
+    ("GetProgramStage", 2, Some(3)),
+    ("GetActiveAtomicCounterBuffer", 2, Some(3)),
+    ("GetInternalformat", 2, None),
+];
+
+/// The queries that convert any state to the type they return.
+const GENERIC_QUERIES: [&str; 5] = [
+    "GetBoolean",
+    "GetInteger",
+    "GetInteger64",
+    "GetFloat",
+    "GetDouble",
+];
+
+/// The type suffixes query commands can have. Those with `i` make the query
+/// indexed, which adds an index argument before the output buffer.
+const SUFFIXES: [&str; 16] = [
+    "", "v", "bv", "iv", "uiv", "Iiv", "Iuiv", "i64v", "ui64v", "fv", "dv", "Ldv", "xv", "i",
+    "i_v", "i64_v",
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Scalar {
+    Boolean,
+    Integer,
+    Integer64,
+    Float,
+    Double,
+}
+
+/// A query command, split into its family (an index into [COMMANDS]) and
+/// suffix.
+pub struct Command<'a> {
+    pub family: usize,
+    suffix: &'a str,
+}
+
+impl Command<'_> {
+    pub fn parse(name: &str) -> Option<Command<'_>> {
+        // Longer families first, so GetInteger64v isn't GetInteger + 64v
+        COMMANDS
+            .iter()
+            .enumerate()
+            .filter(|(_, &(family, _, _))| name.starts_with(family))
+            .filter(|(_, &(family, _, _))| SUFFIXES.contains(&&name[family.len()..]))
+            .max_by_key(|(_, &(family, _, _))| family.len())
+            .map(|(family, &(prefix, _, _))| Command {
+                family,
+                suffix: &name[prefix.len()..],
+            })
+    }
+
+    fn family_name(&self) -> &'static str {
+        COMMANDS[self.family].0
+    }
+
+    pub fn indexed(&self) -> bool {
+        matches!(self.suffix, "i" | "i_v" | "i64_v")
+    }
+
+    fn is_generic(&self) -> bool {
+        GENERIC_QUERIES.contains(&self.family_name())
+    }
+
+    /// What the command returns, if it's known.
+    fn scalar(&self) -> Option<Scalar> {
+        Some(match (self.family_name(), self.suffix) {
+            ("GetBoolean" | "IsEnabled", _) => Scalar::Boolean,
+            ("GetInteger", _) => Scalar::Integer,
+            ("GetInteger64", _) => Scalar::Integer64,
+            ("GetFloat", _) => Scalar::Float,
+            ("GetDouble", _) => Scalar::Double,
+            (_, "bv") => Scalar::Boolean,
+            (_, "iv" | "uiv" | "Iiv" | "Iuiv") => Scalar::Integer,
+            (_, "i64v" | "ui64v") => Scalar::Integer64,
+            (_, "fv") => Scalar::Float,
+            (_, "dv" | "Ldv") => Scalar::Double,
+            _ => return None,
+        })
+    }
+}
+
+/// Whether querying state listed with a `listed` command as `called` loses
+/// precision.
+fn loses_precision(listed: Scalar, called: Scalar) -> bool {
+    matches!(
+        (listed, called),
+        (
+            Scalar::Float | Scalar::Double,
+            Scalar::Boolean | Scalar::Integer | Scalar::Integer64
+        ) | (Scalar::Double, Scalar::Float)
+            | (Scalar::Integer64, Scalar::Boolean | Scalar::Integer)
+    )
+}
+
+/// The families whose types don't count the objects they query, since the
+/// object is named by an argument, e.g. GetProgram's `3 × Z⁺` for
+/// COMPUTE_WORK_GROUP_SIZE is three values for one program.
+const NAMED_OBJECTS: [&str; 7] = [
+    "GetProgram",
+    "GetShader",
+    "GetProgramPipeline",
+    "GetSync",
+    "GetActiveUniformBlock",
+    "GetQueryObject",
+    "GetSamplerParameter",
+];
+
+/// How many values a query writes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Length {
+    Exactly(u32),
+    /// The length is a minimum or depends on the implementation or object, so
+    /// this is the least it could be, which can be 0.
+    AtLeast(u32),
+}
+
+impl Length {
+    fn minimum(self) -> u32 {
+        match self {
+            Length::Exactly(n) | Length::AtLeast(n) => n,
+        }
+    }
+}
+
+impl std::fmt::Display for Length {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Length::Exactly(n) => write!(f, "{}", n),
+            Length::AtLeast(n) => write!(f, "at least {}", n),
+        }
+    }
+}
+
+/// How many values a query of an entry with `command` writes, if its type is
+/// known. The first term of the quantity is skipped if it selects the unit,
+/// index or object the query is for: an index for an indexed query (or state
+/// listed with one), a texture unit or the like that's selected some other way,
+/// or an object for a per-object command. So is the depth of a matrix stack,
+/// since only the top is queried.
+fn values_written(entry: &Entry, command: &Command) -> Option<Length> {
+    let Some(MaybeParsed::Parsed(ref type_)) = entry.type_ else {
+        return None;
+    };
+    let mut count = basic_component_count(type_)?;
+    let terms = type_quantity(type_);
+    let listed_indexed = entry
+        .get_cmnd
+        .as_deref()
+        .and_then(Command::parse)
+        .is_some_and(|listed| listed.indexed());
+    let per_object = !command.is_generic()
+        && command.family_name() != "IsEnabled"
+        && !NAMED_OBJECTS.contains(&command.family_name());
+    let selector = match terms.first() {
+        Some(_) if command.indexed() || listed_indexed || per_object => 1,
+        Some(MaybeParsed::Parsed((Quantity::Constant(_), _))) => 1,
+        _ => 0,
+    };
+    let mut terms = terms[selector.min(terms.len())..].iter().peekable();
+    if is_matrix(type_) {
+        terms.next_if(|term| matches!(term, MaybeParsed::Parsed((_, true))));
+    }
+    let mut exact = true;
+    for term in terms {
+        match term {
+            MaybeParsed::Parsed((Quantity::Integer(n), minimum)) => {
+                count *= n;
+                exact &= !minimum;
+            }
+            _ => {
+                count = 0;
+                exact = false;
+            }
+        }
+    }
+    Some(if exact {
+        Length::Exactly(count)
+    } else {
+        Length::AtLeast(count)
+    })
+}
+
+/// Split the arguments of a call, given the tokens after its `(`. Returns the
+/// arguments and the number of tokens up to and including the `)`.
+fn arguments<'t, 'a>(tokens: &'t [Token<'a>]) -> Option<(Vec<&'t [Token<'a>]>, usize)> {
+    let mut arguments = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, token) in tokens.iter().enumerate() {
+        if token.kind != TokenKind::Punctuation {
+            continue;
+        }
+        match token.text {
+            "(" | "[" | "{" => depth += 1,
+            ")" if depth == 0 => {
+                arguments.push(&tokens[start..index]);
+                return Some((arguments, index + 1));
+            }
+            ")" | "]" | "}" => depth -= 1,
+            "," if depth == 0 => {
+                arguments.push(&tokens[start..index]);
+                start = index + 1;
+            }
+            _ => (),
+        }
+    }
+    None
+}
+
+/// The scalar types that buffers are declared with.
+fn is_scalar_type(name: &str) -> bool {
+    matches!(
+        name,
+        "GLboolean"
+            | "GLint"
+            | "GLuint"
+            | "GLint64"
+            | "GLuint64"
+            | "GLenum"
+            | "GLsizei"
+            | "GLfloat"
+            | "GLdouble"
+            | "GLfixed"
+            | "int"
+            | "unsigned"
+            | "float"
+            | "double"
+            | "bool"
+            | "int32_t"
+            | "uint32_t"
+            | "int64_t"
+            | "uint64_t"
+    )
+}
+
+pub struct Lint<'a> {
+    target: Target,
+    spec_name: &'a str,
+    entries: Vec<&'a Entry<'a>>,
+}
+
+impl<'a> Lint<'a> {
+    pub fn new(target: Target, specs: &'a [SpecTables<'a>]) -> Lint<'a> {
+        let &(_, spec_name, _, tables) = specs
+            .iter()
+            .find(|&&(suffix, _, _, _)| suffix == target.suffix)
+            .unwrap();
+        Lint {
+            target,
+            spec_name,
+            entries: tables.iter().flat_map(|table| &table.entries).collect(),
+        }
+    }
+
+    /// Check a call to `gl{name}` with the given arguments, returning the
+    /// problems with it.
+    fn check_call(
+        &self,
+        name: &str,
+        arguments: &[&[Token]],
+        buffers: &HashMap<&str, (u32, bool)>,
+    ) -> Vec<String> {
+        let Some(command) = Command::parse(name) else {
+            return Vec::new();
+        };
+        let (_, pname_argument, buffer_argument) = COMMANDS[command.family];
+        let (pname, spelled) = match arguments.get(pname_argument) {
+            Some(
+                &[Token {
+                    kind: TokenKind::Identifier,
+                    text,
+                    ..
+                }],
+            ) => match text.strip_prefix("GL_") {
+                Some(pname) => (pname, text),
+                None => return Vec::new(),
+            },
+            _ => return Vec::new(),
+        };
+        let buffer_argument = buffer_argument.map(|index| index + usize::from(command.indexed()));
+        let buffer = match buffer_argument.and_then(|index| arguments.get(index)) {
+            // An array, or the address of its first element
+            Some(&[Token { text, .. }])
+            | Some(
+                &[Token { text: "&", .. }, Token { text, .. }, Token { text: "[", .. }, Token { text: "0", .. }, Token { text: "]", .. }],
+            ) => buffers
+                .get(text)
+                .filter(|&&(_, array)| array)
+                .map(|&(size, array)| (*text, size, array)),
+            // The address of a variable
+            Some(&[Token { text: "&", .. }, Token { text, .. }]) => buffers
+                .get(text)
+                .filter(|&&(_, array)| !array)
+                .map(|&(size, array)| (*text, size, array)),
+            _ => None,
+        };
+        self.check_query(&format!("gl{}", name), &command, pname, spelled, buffer)
+    }
+
+    /// Check a query of `pname` (without `GL_`) with `command`, which are
+    /// written `spelled` and `called` in the source. If the size of the buffer
+    /// it writes to is known, `buffer` is a description of it, its size, and
+    /// whether it's an array rather than a single variable.
+    pub fn check_query(
+        &self,
+        called: &str,
+        command: &Command,
+        pname: &str,
+        spelled: &str,
+        buffer: Option<(&str, u32, bool)>,
+    ) -> Vec<String> {
+        let entries: Vec<&Entry> = self
+            .entries
+            .iter()
+            .copied()
+            .filter(|entry| entry.is_named(pname))
+            .collect();
+        if entries.is_empty() {
+            return vec![format!(
+                "{} isn't a state variable in {}",
+                spelled, self.spec_name
+            )];
+        }
+        let entries: Vec<&Entry> = entries
+            .into_iter()
+            .filter(|entry| entry.in_profile(self.target.profile))
+            .collect();
+        if entries.is_empty() {
+            let other = match self.target.profile {
+                Some(Condition::Core) => "compatibility",
+                _ => "core",
+            };
+            return vec![format!(
+                "{} is only in the {} profile, not {}",
+                spelled,
+                other,
+                self.target.name(self.spec_name)
+            )];
+        }
+
+        let mut problems = Vec::new();
+        let listed: Vec<(&str, Option<Command>)> = entries
+            .iter()
+            .filter_map(|entry| entry.get_cmnd.as_deref())
+            .map(|get_cmnd| (get_cmnd, Command::parse(get_cmnd)))
+            .collect();
+        // Generic queries work for anything that's listed with one, or with
+        // IsEnabled, but IsEnabled only works for capabilities.
+        let family_matches = |listed: &Command| {
+            listed.family == command.family
+                || (command.is_generic()
+                    && (listed.is_generic() || listed.family_name() == "IsEnabled"))
+        };
+        let matching: Vec<&Command> = listed
+            .iter()
+            .filter_map(|(_, listed)| listed.as_ref())
+            .filter(|listed| family_matches(listed))
+            .collect();
+        if !listed.is_empty() && matching.is_empty() {
+            let listed: Vec<String> = listed
+                .iter()
+                .map(|(get_cmnd, _)| format!("gl{}", get_cmnd))
+                .collect();
+            problems.push(format!(
+                "{} is queried with {}, not {}",
+                spelled,
+                listed.join(" or "),
+                called
+            ));
+        } else if let Some(scalar) = command.scalar() {
+            // Only if every way it's listed would be lossy
+            let lossy = matching.iter().all(|listed| {
+                listed
+                    .scalar()
+                    .is_some_and(|listed| loses_precision(listed, scalar))
+            });
+            if !matching.is_empty() && lossy {
+                let listed: Vec<String> = listed
+                    .iter()
+                    .filter(|(_, listed)| listed.as_ref().is_some_and(&family_matches))
+                    .map(|(get_cmnd, _)| format!("gl{}", get_cmnd))
+                    .collect();
+                problems.push(format!(
+                    "{} may lose precision for {}, which is queried with {}",
+                    called,
+                    spelled,
+                    listed.join(" or ")
+                ));
+            }
+        }
+
+        if let Some((buffer, size, array)) = buffer {
+            let written = entries
+                .iter()
+                .map(|entry| values_written(entry, command))
+                .collect::<Option<Vec<Length>>>()
+                .unwrap_or_default();
+            // Only if it's too small for every entry it could be
+            let needed = written
+                .iter()
+                .copied()
+                .min_by_key(|length| length.minimum());
+            if let Some(needed) = needed.filter(|needed| needed.minimum() > size) {
+                problems.push(format!(
+                    "{} has room for {} value{}, but {} has {}",
+                    buffer,
+                    size,
+                    if size == 1 { "" } else { "s" },
+                    spelled,
+                    needed
+                ));
+            } else if !array
+                && !written.is_empty()
+                && written
+                    .iter()
+                    .all(|length| matches!(length, Length::AtLeast(_)))
+            {
+                // A variable number of values might fit an array, but it's
+                // unlikely to be just one
+                problems.push(format!(
+                    "{} is a single value, but {} has a variable number of values",
+                    buffer, spelled
+                ));
+            }
+        }
+        problems
+    }
+
+    /// Check a file's source, writing a line for each problem to `out` in the
+    /// usual `file:line:column: message` form. Returns how many there were.
+    pub fn check_file(&self, out: &mut impl Write, path: &str, source: &str) -> io::Result<usize> {
+        let tokens = tokenize(source);
+        // The size of each array or variable declared so far, and whether it's
+        // an array
+        let mut buffers: HashMap<&str, (u32, bool)> = HashMap::new();
+        let mut problems = 0;
+        for index in 0..tokens.len() {
+            let token = &tokens[index];
+            if token.kind != TokenKind::Identifier {
+                continue;
+            }
+            let next = |offset: usize| tokens.get(index + offset).map(|token| token.text);
+            if is_scalar_type(token.text)
+                && tokens
+                    .get(index + 1)
+                    .is_some_and(|token| token.kind == TokenKind::Identifier)
+            {
+                let name = tokens[index + 1].text;
+                match (next(2), next(3), next(4)) {
+                    (Some("["), Some(size), Some("]")) => {
+                        if let Ok(size) = size.parse() {
+                            buffers.insert(name, (size, true));
+                        }
+                    }
+                    (Some(";" | "=" | "," | ")"), _, _) => {
+                        buffers.insert(name, (1, false));
+                    }
+                    _ => (),
+                }
+            }
+            let Some(name) = token.text.strip_prefix("gl") else {
+                continue;
+            };
+            if next(1) != Some("(") {
+                continue;
+            }
+            let Some((arguments, _)) = arguments(&tokens[index + 2..]) else {
+                continue;
+            };
+            for problem in self.check_call(name, &arguments, &buffers) {
+                writeln!(out, "{}:{}:{}: {}", path, token.line, token.column, problem)?;
+                problems += 1;
+            }
+        }
+        Ok(problems)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{tokenize, Command, Lint, Target, TokenKind, COMMANDS};
+
+    /// Some source, and its tokens' kinds, text, lines and columns.
+    type Tokens<'a> = (&'a str, &'a [(TokenKind, &'a str, usize, usize)]);
+
+    /// A target, the command called, the pname, the buffer, and the problems.
+    type Query<'a> = (
+        &'a str,
+        &'a str,
+        &'a str,
+        Option<(&'a str, u32, bool)>,
+        &'a [&'a str],
+    );
+
+    #[test]
+    fn tokens() {
+        use TokenKind::*;
+        let cases: [Tokens; 4] = [
+            (
+                "glGetIntegerv(GL_VIEWPORT, v);",
+                &[
+                    (Identifier, "glGetIntegerv", 1, 1),
+                    (Punctuation, "(", 1, 14),
+                    (Identifier, "GL_VIEWPORT", 1, 15),
+                    (Punctuation, ",", 1, 26),
+                    (Identifier, "v", 1, 28),
+                    (Punctuation, ")", 1, 29),
+                    (Punctuation, ";", 1, 30),
+                ],
+            ),
+            (
+                "GLint v[16];",
+                &[
+                    (Identifier, "GLint", 1, 1),
+                    (Identifier, "v", 1, 7),
+                    (Punctuation, "[", 1, 8),
+                    (Number, "16", 1, 9),
+                    (Punctuation, "]", 1, 11),
+                    (Punctuation, ";", 1, 12),
+                ],
+            ),
+            (
+                "a /* b\n c */ d // e(\n  \"f(\\\"\" 'g' h",
+                &[
+                    (Identifier, "a", 1, 1),
+                    (Identifier, "d", 2, 7),
+                    (Identifier, "h", 3, 14),
+                ],
+            ),
+            ("é\tx", &[(Identifier, "é", 1, 1), (Identifier, "x", 1, 3)]),
+        ];
+        for (source, expected) in cases {
+            let actual: Vec<(TokenKind, &str, usize, usize)> = tokenize(source)
+                .into_iter()
+                .map(|token| (token.kind, token.text, token.line, token.column))
+                .collect();
+            assert_eq!(actual, expected, "{:?}", source);
+        }
+    }
+
+    #[test]
+    fn commands() {
+        // The family, and whether it's indexed
+        let cases = [
+            ("GetIntegerv", Some(("GetInteger", false))),
+            ("GetInteger64v", Some(("GetInteger64", false))),
+            ("GetInteger64i_v", Some(("GetInteger64", true))),
+            ("GetBooleani_v", Some(("GetBoolean", true))),
+            ("IsEnabled", Some(("IsEnabled", false))),
+            ("IsEnabledi", Some(("IsEnabled", true))),
+            ("GetTexParameterIuiv", Some(("GetTexParameter", false))),
+            (
+                "GetTexLevelParameterfv",
+                Some(("GetTexLevelParameter", false)),
+            ),
+            (
+                "GetActiveUniformBlockiv",
+                Some(("GetActiveUniformBlock", false)),
+            ),
+            ("GetIntegerw", None),
+            ("GetTexImage", None),
+            ("Viewport", None),
+        ];
+        for (name, expected) in cases {
+            let actual =
+                Command::parse(name).map(|command| (COMMANDS[command.family].0, command.indexed()));
+            assert_eq!(actual, expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn queries() {
+        let cases: [Query; 18] = [
+            ("es", "glGetIntegerv", "VIEWPORT", Some(("v", 4, true)), &[]),
+            (
+                "es",
+                "glGetIntegerv",
+                "NOT_A_THING",
+                None,
+                &["GL_NOT_A_THING isn't a state variable in OpenGL ES 3.2"],
+            ),
+            (
+                "gl-core",
+                "glGetFloatv",
+                "CURRENT_COLOR",
+                None,
+                &["GL_CURRENT_COLOR is only in the compatibility profile, not the OpenGL 4.6 core profile"],
+            ),
+            ("gl-compatibility", "glGetFloatv", "CURRENT_COLOR", None, &[]),
+            (
+                "es",
+                "glGetIntegerv",
+                "DEPTH_CLEAR_VALUE",
+                None,
+                &["glGetIntegerv may lose precision for GL_DEPTH_CLEAR_VALUE, which is queried with glGetFloatv"],
+            ),
+            (
+                "es",
+                "glGetTexParameteriv",
+                "VIEWPORT",
+                None,
+                &["GL_VIEWPORT is queried with glGetIntegerv, not glGetTexParameteriv"],
+            ),
+            (
+                "es",
+                "glGetIntegerv",
+                "VIEWPORT",
+                Some(("v", 2, true)),
+                &["v has room for 2 values, but GL_VIEWPORT has 4"],
+            ),
+            // A variable-length value's length, whether it's exact, a minimum,
+            // or unknown
+            (
+                "es11",
+                "glGetIntegerv",
+                "COMPRESSED_TEXTURE_FORMATS",
+                Some(("x", 1, false)),
+                &["x has room for 1 value, but GL_COMPRESSED_TEXTURE_FORMATS has 10"],
+            ),
+            (
+                "es",
+                "glGetIntegerv",
+                "COMPRESSED_TEXTURE_FORMATS",
+                Some(("x", 1, false)),
+                &["x has room for 1 value, but GL_COMPRESSED_TEXTURE_FORMATS has at least 10"],
+            ),
+            (
+                "gl-core",
+                "glGetIntegerv",
+                "COMPRESSED_TEXTURE_FORMATS",
+                Some(("x", 1, false)),
+                &["x has room for 1 value, but GL_COMPRESSED_TEXTURE_FORMATS has at least 18"],
+            ),
+            (
+                "es",
+                "glGetIntegerv",
+                "COMPRESSED_TEXTURE_FORMATS",
+                Some(("formats", 64, true)),
+                &[],
+            ),
+            (
+                "es",
+                "glGetIntegerv",
+                "PROGRAM_BINARY_FORMATS",
+                Some(("x", 1, false)),
+                &["x is a single value, but GL_PROGRAM_BINARY_FORMATS has a variable number of values"],
+            ),
+            (
+                "es",
+                "glGetProgramiv",
+                "COMPUTE_WORK_GROUP_SIZE",
+                Some(("x", 1, false)),
+                &["x has room for 1 value, but GL_COMPUTE_WORK_GROUP_SIZE has 3"],
+            ),
+            // Terms that select a unit, index, object or the top of a stack
+            ("es", "glGetIntegerv", "TEXTURE_BINDING_2D", Some(("x", 1, false)), &[]),
+            ("es", "glGetIntegerv", "UNIFORM_BUFFER_BINDING", Some(("x", 1, false)), &[]),
+            ("es", "glGetBooleanv", "COLOR_WRITEMASK", Some(("b", 4, true)), &[]),
+            ("es11", "glGetMaterialfv", "AMBIENT", Some(("c", 4, true)), &[]),
+            (
+                "gl-compatibility",
+                "glGetFloatv",
+                "MODELVIEW_MATRIX",
+                Some(("m", 16, true)),
+                &[],
+            ),
+        ];
+        crate::with_specs(|specs| {
+            for (target, called, pname, buffer, expected) in cases {
+                let lint = Lint::new(Target::parse(target).unwrap(), specs);
+                let command = Command::parse(called.strip_prefix("gl").unwrap()).unwrap();
+                assert_eq!(
+                    lint.check_query(called, &command, pname, &format!("GL_{}", pname), buffer),
+                    expected,
+                    "{} {} {}",
+                    target,
+                    called,
+                    pname
+                );
+            }
+        });
+    }
+}
@@ -0,0 +1,444 @@
+//! Linting Rust sources for state queries that don't match the state tables,
+//! like [lint](crate::lint) does for C, using `syn`. It knows the raw bindings
+//! of the `gl` crate and its relatives (`gl::GetIntegerv(gl::VIEWPORT, ...)`,
+//! or `gl.GetIntegerv(...)` for struct bindings) and `glow`'s methods
+//! (`gl.get_parameter_i32(glow::VIEWPORT)`).
+//!
+//! Pnames are only checked if they're paths like `gl::X` or `glow::X`, and
+//! buffer sizes are only known for `let` bindings of arrays and scalars in the
+//! same file.
+
+use crate::lint::{Command, Lint, COMMANDS};
+use std::collections::HashMap;
+use std::io::{self, Write};
+use syn::visit::{self, Visit};
+use syn::{Expr, ExprCall, ExprMethodCall, Ident, Lit, Local, Pat, Type};
+
+/// What a `glow` method does with the values it gets.
+#[derive(Clone, Copy)]
+enum Output {
+    /// It returns one value.
+    Returned,
+    /// It writes them to the slice that's this argument.
+    Slice(usize),
+    /// It returns something else, like a string or a fixed-size array.
+    Other,
+}
+
+/// The `glow` query methods, with the GL command each calls, which argument is
+/// the pname, and what they do with the result.
+const GLOW_METHODS: [(&str, &str, usize, Output); 24] = [
+    ("get_parameter_bool", "GetBooleanv", 0, Output::Returned),
+    ("get_parameter_bool_array", "GetBooleanv", 0, Output::Other),
+    ("get_parameter_i32", "GetIntegerv", 0, Output::Returned),
+    (
+        "get_parameter_i32_slice",
+        "GetIntegerv",
+        0,
+        Output::Slice(1),
+    ),
+    ("get_parameter_i64", "GetInteger64v", 0, Output::Returned),
+    (
+        "get_parameter_i64_slice",
+        "GetInteger64v",
+        0,
+        Output::Slice(1),
+    ),
+    ("get_parameter_f32", "GetFloatv", 0, Output::Returned),
+    ("get_parameter_f32_slice", "GetFloatv", 0, Output::Slice(1)),
+    (
+        "get_parameter_indexed_i32",
+        "GetIntegeri_v",
+        0,
+        Output::Returned,
+    ),
+    (
+        "get_parameter_indexed_string",
+        "GetStringi",
+        0,
+        Output::Other,
+    ),
+    ("get_parameter_string", "GetString", 0, Output::Other),
+    ("is_enabled", "IsEnabled", 0, Output::Other),
+    ("is_enabled_indexed", "IsEnabledi", 0, Output::Other),
+    (
+        "get_tex_parameter_i32",
+        "GetTexParameteriv",
+        1,
+        Output::Returned,
+    ),
+    (
+        "get_tex_parameter_f32",
+        "GetTexParameterfv",
+        1,
+        Output::Returned,
+    ),
+    (
+        "get_tex_level_parameter_i32",
+        "GetTexLevelParameteriv",
+        2,
+        Output::Returned,
+    ),
+    (
+        "get_buffer_parameter_i32",
+        "GetBufferParameteriv",
+        1,
+        Output::Returned,
+    ),
+    (
+        "get_framebuffer_parameter_i32",
+        "GetFramebufferParameteriv",
+        1,
+        Output::Returned,
+    ),
+    (
+        "get_framebuffer_attachment_parameter_i32",
+        "GetFramebufferAttachmentParameteriv",
+        2,
+        Output::Returned,
+    ),
+    (
+        "get_program_parameter_i32",
+        "GetProgramiv",
+        1,
+        Output::Returned,
+    ),
+    (
+        "get_sampler_parameter_i32",
+        "GetSamplerParameteriv",
+        1,
+        Output::Returned,
+    ),
+    (
+        "get_sampler_parameter_f32",
+        "GetSamplerParameterfv",
+        1,
+        Output::Returned,
+    ),
+    (
+        "get_query_parameter_u32",
+        "GetQueryObjectuiv",
+        1,
+        Output::Returned,
+    ),
+    ("get_sync_parameter_i32", "GetSynciv", 1, Output::Returned),
+];
+
+/// The scalar types that buffers are declared with.
+fn is_scalar_type(name: &str) -> bool {
+    matches!(
+        name,
+        "bool" | "i32" | "u32" | "i64" | "u64" | "f32" | "f64" | "u8"
+    ) || name.starts_with("GL")
+}
+
+/// The value of an integer literal.
+fn integer(expr: &Expr) -> Option<u32> {
+    match expr {
+        Expr::Lit(expr) => match &expr.lit {
+            Lit::Int(int) => int.base10_parse().ok(),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The name a path expression ends with and the whole path, if it has more
+/// than one segment, so `gl::VIEWPORT` is `VIEWPORT` but a local `VIEWPORT`
+/// isn't anything.
+fn qualified_name(expr: &Expr) -> Option<(String, String)> {
+    match expr {
+        Expr::Path(expr) if expr.path.segments.len() > 1 => {
+            let segments: Vec<String> = expr
+                .path
+                .segments
+                .iter()
+                .map(|segment| segment.ident.to_string())
+                .collect();
+            Some((segments.last()?.clone(), segments.join("::")))
+        }
+        _ => None,
+    }
+}
+
+/// The name of a local variable.
+fn local_name(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Path(expr) => expr.path.get_ident().map(Ident::to_string),
+        _ => None,
+    }
+}
+
+struct Visitor<'l, 'a> {
+    lint: &'l Lint<'a>,
+    /// The size of each array or variable bound so far, and whether it's an
+    /// array
+    buffers: HashMap<String, (u32, bool)>,
+    /// Each problem's line, column and message
+    problems: Vec<(usize, usize, String)>,
+}
+
+impl Visitor<'_, '_> {
+    /// What to call the buffer an argument points to, its size and whether
+    /// it's an array, if they're known.
+    fn buffer(&self, expr: &Expr) -> Option<(String, u32, bool)> {
+        let array = |name: String| {
+            let &(size, array) = self.buffers.get(&name)?;
+            array.then_some((name, size, true))
+        };
+        match expr {
+            Expr::Cast(expr) => self.buffer(&expr.expr),
+            Expr::Paren(expr) => self.buffer(&expr.expr),
+            Expr::MethodCall(call) if call.method == "as_mut_ptr" => {
+                array(local_name(&call.receiver)?)
+            }
+            Expr::Reference(reference) => match &*reference.expr {
+                Expr::Index(index)
+                    if integer(&index.index) == Some(0)
+                        || matches!(&*index.index, Expr::Range(range)
+                            if range.start.is_none() && range.end.is_none()) =>
+                {
+                    array(local_name(&index.expr)?)
+                }
+                Expr::Repeat(repeat) => {
+                    Some(("the array".to_string(), integer(&repeat.len)?, true))
+                }
+                Expr::Array(elements) => {
+                    Some(("the array".to_string(), elements.elems.len() as u32, true))
+                }
+                expr => {
+                    let name = local_name(expr)?;
+                    let &(size, array) = self.buffers.get(&name)?;
+                    Some((name, size, array))
+                }
+            },
+            _ => None,
+        }
+    }
+
+    fn check(
+        &mut self,
+        ident: &Ident,
+        called: &str,
+        command: &Command,
+        pname: &Expr,
+        buffer: Option<(String, u32, bool)>,
+    ) {
+        let Some((pname, spelled)) = qualified_name(pname) else {
+            return;
+        };
+        let start = ident.span().start();
+        for problem in self.lint.check_query(
+            called,
+            command,
+            &pname,
+            &spelled,
+            buffer
+                .as_ref()
+                .map(|(name, size, array)| (&name[..], *size, *array)),
+        ) {
+            self.problems.push((start.line, start.column + 1, problem));
+        }
+    }
+
+    /// Check a call to a raw binding like `gl::GetIntegerv`.
+    fn check_binding<'e>(
+        &mut self,
+        ident: &Ident,
+        called: &str,
+        arguments: impl Iterator<Item = &'e Expr>,
+    ) {
+        let name = ident.to_string();
+        let Some(command) = Command::parse(&name) else {
+            return;
+        };
+        let arguments: Vec<&Expr> = arguments.collect();
+        let (_, pname_argument, buffer_argument) = COMMANDS[command.family];
+        let Some(pname) = arguments.get(pname_argument) else {
+            return;
+        };
+        let buffer = buffer_argument
+            .map(|index| index + usize::from(command.indexed()))
+            .and_then(|index| arguments.get(index))
+            .and_then(|buffer| self.buffer(buffer));
+        self.check(ident, called, &command, pname, buffer);
+    }
+}
+
+impl<'ast> Visit<'ast> for Visitor<'_, '_> {
+    fn visit_local(&mut self, local: &'ast Local) {
+        visit::visit_local(self, local);
+        let (name, type_) = match &local.pat {
+            Pat::Ident(pat) => (&pat.ident, None),
+            Pat::Type(pat) => match &*pat.pat {
+                Pat::Ident(inner) => (&inner.ident, Some(&*pat.ty)),
+                _ => return,
+            },
+            _ => return,
+        };
+        let init = local.init.as_ref().map(|init| &*init.expr);
+        let buffer = match (type_, init) {
+            (Some(Type::Array(array)), _) => integer(&array.len).map(|size| (size, true)),
+            (Some(Type::Path(path)), _) => path
+                .path
+                .segments
+                .last()
+                .filter(|segment| is_scalar_type(&segment.ident.to_string()))
+                .map(|_| (1, false)),
+            (None, Some(Expr::Repeat(repeat))) => integer(&repeat.len).map(|size| (size, true)),
+            (None, Some(Expr::Array(array))) => Some((array.elems.len() as u32, true)),
+            (None, Some(Expr::Lit(_))) => Some((1, false)),
+            _ => None,
+        };
+        // Unknown bindings shadow earlier ones too
+        match buffer {
+            Some(buffer) => self.buffers.insert(name.to_string(), buffer),
+            None => self.buffers.remove(&name.to_string()),
+        };
+    }
+
+    fn visit_expr_call(&mut self, call: &'ast ExprCall) {
+        visit::visit_expr_call(self, call);
+        let Expr::Path(ref func) = *call.func else {
+            return;
+        };
+        if func.path.segments.len() < 2 {
+            return;
+        }
+        let segments: Vec<String> = func
+            .path
+            .segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect();
+        let ident = &func.path.segments.last().unwrap().ident;
+        self.check_binding(ident, &segments.join("::"), call.args.iter());
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast ExprMethodCall) {
+        visit::visit_expr_method_call(self, call);
+        let method = call.method.to_string();
+        let Some(&(_, get_cmnd, pname_argument, output)) =
+            GLOW_METHODS.iter().find(|&&(name, _, _, _)| name == method)
+        else {
+            self.check_binding(&call.method, &method, call.args.iter());
+            return;
+        };
+        let command = Command::parse(get_cmnd).unwrap();
+        let Some(pname) = call.args.iter().nth(pname_argument) else {
+            return;
+        };
+        let buffer = match output {
+            Output::Returned => Some((format!("{}'s result", method), 1, false)),
+            Output::Slice(index) => call.args.iter().nth(index).and_then(|arg| self.buffer(arg)),
+            Output::Other => None,
+        };
+        self.check(&call.method, &method, &command, pname, buffer);
+    }
+}
+
+/// Check a Rust file's source, writing a line for each problem to `out` like
+/// [Lint::check_file]. Returns how many there were.
+pub fn check_file(
+    lint: &Lint,
+    out: &mut impl Write,
+    path: &str,
+    source: &str,
+) -> io::Result<usize> {
+    let file = match syn::parse_file(source) {
+        Ok(file) => file,
+        Err(err) => {
+            let start = err.span().start();
+            writeln!(
+                out,
+                "{}:{}:{}: couldn't parse: {}",
+                path,
+                start.line,
+                start.column + 1,
+                err
+            )?;
+            return Ok(1);
+        }
+    };
+    let mut visitor = Visitor {
+        lint,
+        buffers: HashMap::new(),
+        problems: Vec::new(),
+    };
+    visitor.visit_file(&file);
+    for (line, column, problem) in &visitor.problems {
+        writeln!(out, "{}:{}:{}: {}", path, line, column, problem)?;
+    }
+    Ok(visitor.problems.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_file;
+    use crate::lint::{Lint, Target};
+
+    /// The problems with some source, linted for OpenGL ES 3.2.
+    fn problems(source: &str) -> String {
+        crate::with_specs(|specs| {
+            let lint = Lint::new(Target::parse("es").unwrap(), specs);
+            let mut out = Vec::new();
+            check_file(&lint, &mut out, "test.rs", source).unwrap();
+            String::from_utf8(out).unwrap()
+        })
+    }
+
+    #[test]
+    fn shadowing() {
+        let source = "unsafe fn f() {
+    let mut v = [0i32; 2];
+    gl::GetIntegerv(gl::VIEWPORT, v.as_mut_ptr());
+    let mut v = [0i32; 4];
+    gl::GetIntegerv(gl::VIEWPORT, v.as_mut_ptr());
+    let mut v = vec![0; 2];
+    gl::GetIntegerv(gl::VIEWPORT, v.as_mut_ptr());
+    let mut n: i32 = 0;
+    gl::GetIntegerv(gl::COMPRESSED_TEXTURE_FORMATS, &mut n);
+}";
+        assert_eq!(
+            problems(source),
+            "test.rs:3:9: v has room for 2 values, but gl::VIEWPORT has 4\n\
+             test.rs:9:9: n has room for 1 value, but gl::COMPRESSED_TEXTURE_FORMATS has at least 10\n"
+        );
+    }
+
+    #[test]
+    fn slices() {
+        let source = "fn f(gl: &glow::Context) {
+    let mut w: [f32; 2] = [0.0; 2];
+    gl.get_parameter_f32_slice(glow::VIEWPORT, &mut w[..]);
+    gl.get_parameter_f32_slice(glow::DEPTH_RANGE, &mut w[..]);
+    gl.GetFloatv(gl::VIEWPORT, &mut w[0]);
+    gl.get_parameter_f32_slice(glow::VIEWPORT, &mut [0.0; 4]);
+}";
+        assert_eq!(
+            problems(source),
+            "test.rs:3:8: w has room for 2 values, but glow::VIEWPORT has 4\n\
+             test.rs:5:8: w has room for 2 values, but gl::VIEWPORT has 4\n"
+        );
+    }
+
+    #[test]
+    fn glow_methods() {
+        let source = "fn f(gl: &glow::Context) {
+    let a = gl.get_parameter_i32(glow::COLOR_WRITEMASK);
+    let b = gl.get_parameter_i32(glow::NOT_A_THING);
+    let c = gl.get_parameter_i32(glow::DEPTH_CLEAR_VALUE);
+    let d = gl.get_tex_parameter_i32(glow::TEXTURE_2D, glow::VIEWPORT);
+    let e = gl.get_tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER);
+    let VIEWPORT = 0;
+    let f = gl.get_parameter_i32(VIEWPORT);
+}";
+        assert_eq!(
+            problems(source),
+            "test.rs:2:16: get_parameter_i32's result has room for 1 value, but glow::COLOR_WRITEMASK has 4\n\
+             test.rs:3:16: glow::NOT_A_THING isn't a state variable in OpenGL ES 3.2\n\
+             test.rs:4:16: get_parameter_i32 may lose precision for glow::DEPTH_CLEAR_VALUE, which is queried with glGetFloatv\n\
+             test.rs:5:16: glow::VIEWPORT is queried with glGetIntegerv, not get_tex_parameter_i32\n"
+        );
+    }
+}
@@ -0,0 +1,346 @@
+//! Man pages: one per state variable, showing it across all the specs, an
+//! index page per spec, and a `gl-state` page pointing to those. They go in
+//! section 7. A variable's page is named like `gl-state-TEXTURE_MAG_FILTER`,
+//! which man-db also finds for `man gl-state TEXTURE_MAG_FILTER`, the way
+//! `man git commit` finds `git-commit`. There's also a page under the bare get
+//! value that sources it. So with the output directory `out/`, any of
+//! `man -M out/ gl-state TEXTURE_MAG_FILTER`, `man -M out/ TEXTURE_MAG_FILTER`
+//! or `man -M out/ gl-state-es` works.
+
+use crate::html::{footnote_symbol, DISCLAIMER};
+use crate::site::file_name;
+use crate::terminal::with_footnotes;
+use crate::SpecTables;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use OpenGL_state_table_parser::types::{type_to_unicode, MaybeParsed};
+use OpenGL_state_table_parser::{Condition, Entry, Table};
+
+/// Make text safe to use in roff, on a line of its own or as an argument.
+fn roff(text: &str) -> String {
+    let text = text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('\\', "\\e")
+        .replace('-', "\\-")
+        .replace('"', "\\(dq");
+    if text.starts_with(['.', '\'']) {
+        format!("\\&{}", text)
+    } else {
+        text
+    }
+}
+
+/// The HTML disclaimer as plain text.
+fn disclaimer() -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in DISCLAIMER.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+    text
+}
+
+/// The overview page, which is also what `man gl-state NAME` is relative to.
+const OVERVIEW_PAGE_NAME: &str = "gl-state";
+
+fn index_page_name(suffix: &str) -> String {
+    format!("{}-{}", OVERVIEW_PAGE_NAME, suffix)
+}
+
+fn variable_page_name(get_value: &str) -> String {
+    format!("{}-{}", OVERVIEW_PAGE_NAME, file_name(get_value))
+}
+
+fn write_header(out: &mut impl Write, name: &str, summary: &str) -> io::Result<()> {
+    writeln!(
+        out,
+        ".TH \"{}\" 7 \"\" \"OpenGL-state-table-parser\" \"OpenGL State Tables\"",
+        roff(name)
+    )?;
+    writeln!(out, ".SH NAME")?;
+    writeln!(out, "{} \\- {}", roff(name), roff(summary))
+}
+
+fn write_footer(out: &mut impl Write, specs: &[SpecTables]) -> io::Result<()> {
+    writeln!(out, ".SH SEE ALSO")?;
+    for (index, &(suffix, _, _, _)) in specs.iter().enumerate() {
+        let separator = if index + 1 == specs.len() { "" } else { "," };
+        writeln!(
+            out,
+            ".BR {} (7){}",
+            roff(&index_page_name(suffix)),
+            separator
+        )?;
+    }
+    writeln!(out, ".SH NOTES")?;
+    writeln!(out, "{}", roff(&disclaimer()))
+}
+
+fn write_field(out: &mut impl Write, name: &str, value: &str) -> io::Result<()> {
+    writeln!(out, ".TP")?;
+    writeln!(out, ".B {}", name)?;
+    writeln!(out, "{}", roff(value))
+}
+
+fn write_entry(out: &mut impl Write, table: &Table, entry: &Entry) -> io::Result<()> {
+    if let Some(ref alt_get_value) = entry.alt_get_value {
+        write_field(out, "Alternative get value", alt_get_value)?;
+    }
+    if let Some(ref minimum) = entry.series {
+        let get_value = entry.get_value.as_deref().unwrap();
+        write_field(
+            out,
+            "Series",
+            &format!(
+                "{} is the first of n values, {} + (n-1), where n ≥ {}",
+                get_value, get_value, minimum
+            ),
+        )?;
+    }
+    let type_ = match entry.type_ {
+        Some(MaybeParsed::Parsed(ref type_)) => Some(type_to_unicode(type_)),
+        Some(MaybeParsed::Unparsed(ref type_)) => Some(type_.clone()),
+        None => None,
+    };
+    if type_.is_some() || !entry.type_footnotes.is_empty() {
+        let type_ = type_.unwrap_or_default();
+        write_field(out, "Type", &with_footnotes(type_, &entry.type_footnotes))?;
+    }
+    if let Some(ref get_cmnd) = entry.get_cmnd {
+        write_field(out, "Get command", get_cmnd)?;
+    }
+    if entry.initial_value.is_some() || !entry.initial_value_footnotes.is_empty() {
+        let name = if table.minimum_values {
+            "Minimum value"
+        } else {
+            "Initial value"
+        };
+        let initial_value = entry.initial_value.as_deref().unwrap_or("").to_string();
+        write_field(
+            out,
+            name,
+            &with_footnotes(initial_value, &entry.initial_value_footnotes),
+        )?;
+    }
+    write_field(
+        out,
+        "Description",
+        &with_footnotes(entry.description.to_string(), &entry.description_footnotes),
+    )?;
+    if let Some(ref attribute) = entry.attribute {
+        write_field(out, "Attribute group", attribute)?;
+    }
+    if let Some(condition) = entry.condition {
+        let profile = match condition {
+            Condition::Core => "Core profile only",
+            Condition::Compatibility => "Compatibility profile only",
+            Condition::ImagingSubset => "Imaging Subset (compatibility profile only)",
+        };
+        write_field(out, "Profile", profile)?;
+    }
+
+    let mut footnotes: Vec<usize> = [
+        &entry.get_value_footnotes,
+        &entry.type_footnotes,
+        &entry.initial_value_footnotes,
+        &entry.description_footnotes,
+    ]
+    .into_iter()
+    .flatten()
+    .copied()
+    .collect();
+    footnotes.sort();
+    footnotes.dedup();
+    for index in footnotes {
+        write_field(out, &footnote_symbol(index), &table.footnotes[index])?;
+    }
+    Ok(())
+}
+
+/// Write the man pages to `out_dir/man7/`.
+pub fn write_man_pages(out_dir: &Path, specs: &[SpecTables]) -> io::Result<()> {
+    let man_dir = out_dir.join("man7");
+    fs::create_dir_all(&man_dir)?;
+    let create = |page_name: &str| -> io::Result<BufWriter<File>> {
+        Ok(BufWriter::new(File::create(
+            man_dir.join(format!("{}.7", page_name)),
+        )?))
+    };
+    // A page that just sources another one
+    let create_link = |page_name: &str, target: &str| -> io::Result<()> {
+        let mut out = create(page_name)?;
+        writeln!(out, ".so man7/{}.7", target)?;
+        out.flush()
+    };
+
+    // Every (spec, table, entry) for each get value
+    let mut variables: BTreeMap<&str, Vec<(usize, usize, usize)>> = BTreeMap::new();
+    // Alternative names, and the get value they are an alternative for
+    let mut alt_names: BTreeMap<&str, &str> = BTreeMap::new();
+    for (spec_index, &(_, _, _, tables)) in specs.iter().enumerate() {
+        for (table_index, table) in tables.iter().enumerate() {
+            for (entry_index, entry) in table.entries.iter().enumerate() {
+                let Some(ref get_value) = entry.get_value else {
+                    continue;
+                };
+                variables.entry(get_value).or_default().push((
+                    spec_index,
+                    table_index,
+                    entry_index,
+                ));
+                if let Some(ref alt_get_value) = entry.alt_get_value {
+                    alt_names.insert(alt_get_value, get_value);
+                }
+            }
+        }
+    }
+
+    for (&get_value, occurrences) in &variables {
+        let page_name = variable_page_name(get_value);
+        create_link(&file_name(get_value), &page_name)?;
+        let mut out = create(&page_name)?;
+        let (spec_index, table_index, entry_index) = occurrences[0];
+        let first = &specs[spec_index].3[table_index].entries[entry_index];
+        write_header(&mut out, get_value, &first.description)?;
+        writeln!(out, ".SH DESCRIPTION")?;
+        for &(spec_index, table_index, entry_index) in occurrences {
+            let (_, spec_name, _, tables) = specs[spec_index];
+            let table = &tables[table_index];
+            writeln!(out, ".SS \"{}: {}\"", spec_name, roff(&table.title))?;
+            write_entry(&mut out, table, &table.entries[entry_index])?;
+        }
+        for (spec_index, &(_, spec_name, _, _)) in specs.iter().enumerate() {
+            if !occurrences.iter().any(|&(s, _, _)| s == spec_index) {
+                writeln!(out, ".PP")?;
+                writeln!(out, "Not in the {} state tables.", spec_name)?;
+            }
+        }
+        write_footer(&mut out, specs)?;
+        out.flush()?;
+    }
+
+    for (alt_get_value, get_value) in alt_names {
+        // There may already be a page if the name is also a get value itself
+        if variables.contains_key(alt_get_value) {
+            continue;
+        }
+        let target = variable_page_name(get_value);
+        create_link(&variable_page_name(alt_get_value), &target)?;
+        create_link(&file_name(alt_get_value), &target)?;
+    }
+
+    let mut out = create(OVERVIEW_PAGE_NAME)?;
+    write_header(&mut out, OVERVIEW_PAGE_NAME, "OpenGL state variables")?;
+    writeln!(out, ".SH SYNOPSIS")?;
+    writeln!(out, ".B man gl\\-state")?;
+    writeln!(out, ".I name")?;
+    writeln!(out, ".SH DESCRIPTION")?;
+    writeln!(
+        out,
+        "{}",
+        roff(
+            "Each state variable in the OpenGL and OpenGL ES state tables has a page \
+             named after its get value without the GL_ prefix, e.g. \
+             gl-state-TEXTURE_MAG_FILTER, which man-db also finds as \
+             \"man gl-state TEXTURE_MAG_FILTER\" or \"man TEXTURE_MAG_FILTER\". \
+             Each page shows the variable in every spec. The pages listed below \
+             index each spec's state tables."
+        )
+    )?;
+    write_footer(&mut out, specs)?;
+    out.flush()?;
+
+    for &(suffix, spec_name, copyright, tables) in specs {
+        let mut out = create(&index_page_name(suffix))?;
+        write_header(
+            &mut out,
+            &index_page_name(suffix),
+            &format!("{} state tables", spec_name),
+        )?;
+        writeln!(out, ".SH DESCRIPTION")?;
+        for table in tables {
+            writeln!(out, ".SS \"{}\"", roff(&table.title))?;
+            if let Some(ref caption) = table.caption {
+                writeln!(out, "{}", roff(caption))?;
+            }
+            for entry in &table.entries {
+                let Some(ref get_value) = entry.get_value else {
+                    continue;
+                };
+                writeln!(out, ".TP")?;
+                writeln!(out, ".BR \"{}\" (7)", roff(&variable_page_name(get_value)))?;
+                writeln!(out, "{}", roff(&entry.description))?;
+            }
+        }
+        writeln!(out, ".SH COPYRIGHT")?;
+        writeln!(out, ".nf")?;
+        for line in copyright.lines() {
+            writeln!(out, "{}", roff(line))?;
+        }
+        writeln!(out, ".fi")?;
+        write_footer(&mut out, specs)?;
+        out.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{roff, write_man_pages};
+
+    #[test]
+    fn roff_escaping() {
+        assert_eq!(roff("a-b \"c\"  \\d"), "a\\-b \\(dqc\\(dq \\ed");
+        assert_eq!(roff(".5 or more"), "\\&.5 or more");
+    }
+
+    #[test]
+    fn pages() {
+        let out_dir = std::env::temp_dir().join(format!("gl-state-man-{}", std::process::id()));
+        crate::with_specs(|specs| write_man_pages(&out_dir, specs)).unwrap();
+        let read = |name: &str| std::fs::read_to_string(out_dir.join("man7").join(name)).unwrap();
+
+        let page = read("gl-state-MAX_TEXTURE_SIZE.7");
+        assert!(page.starts_with(".TH \"MAX_TEXTURE_SIZE\" 7 "));
+        // It's implementation-dependent, so it has a minimum, not an initial
+        // value
+        assert!(page.contains(".B Minimum value\n2048\n"));
+        assert!(!page.contains(".B Initial value"));
+        assert_eq!(
+            read("MAX_TEXTURE_SIZE.7"),
+            ".so man7/gl-state-MAX_TEXTURE_SIZE.7\n"
+        );
+        assert_eq!(
+            read("gl-state-TRANSPOSE_MODELVIEW_MATRIX.7"),
+            ".so man7/gl-state-MODELVIEW_MATRIX.7\n"
+        );
+        assert!(read("gl-state.7").contains(".BR gl\\-state\\-es11 (7),\n"));
+
+        // Every page an index refers to exists, including for ES 1.1's get
+        // values with spaces in them
+        for suffix in ["es11", "es", "gl"] {
+            let index = read(&format!("gl-state-{}.7", suffix));
+            let references: Vec<&str> = index
+                .lines()
+                .filter_map(|line| line.strip_prefix(".BR \""))
+                .filter_map(|line| line.strip_suffix("\" (7)"))
+                .collect();
+            assert!(references.contains(&"gl\\-state\\-MAX_TEXTURE_SIZE"));
+            for reference in references {
+                let page = format!("{}.7", reference.replace("\\-", "-"));
+                assert!(out_dir.join("man7").join(&page).exists(), "{}", page);
+            }
+        }
+
+        std::fs::remove_dir_all(&out_dir).unwrap();
+    }
+}
@@ -0,0 +1,222 @@
+//! GitHub-flavoured Markdown output, for wikis and READMEs. Each state table
+//! gets a section with its title, caption and footnotes, then a pipe table of
+//! its entries. Math is left as `$...$`, which GitHub renders itself.
+
+use crate::html::{footnote_symbol, DISCLAIMER, LICENSE};
+use crate::SpecTables;
+use std::io::{self, Write};
+use OpenGL_state_table_parser::types::{type_to_unicode, MaybeParsed};
+use OpenGL_state_table_parser::{Condition, Entry, Table};
+
+/// Make text safe to put in a table cell. `|` would end the cell even in a
+/// code span, so it's always escaped, but `<` is only escaped outside code
+/// spans, because entities aren't decoded inside them.
+fn cell(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut cell = String::new();
+    let mut rest = &text[..];
+    while let Some(c) = rest.chars().next() {
+        if c == '`' {
+            // A code span starts with a run of backticks and ends at the next
+            // run of the same length, if there is one
+            let run = rest.len() - rest.trim_start_matches('`').len();
+            let after = &rest[run..];
+            let mut end = None;
+            let mut search = 0;
+            while let Some(start) = after[search..].find('`').map(|i| i + search) {
+                let length = after[start..].len() - after[start..].trim_start_matches('`').len();
+                if length == run {
+                    end = Some(start);
+                    break;
+                }
+                search = start + length;
+            }
+            match end {
+                Some(end) => {
+                    cell.push_str(&rest[..run]);
+                    cell.push_str(&after[..end].replace('|', "\\|"));
+                    cell.push_str(&rest[..run]);
+                    rest = &after[end + run..];
+                }
+                None => {
+                    cell.push_str(&rest[..run]);
+                    rest = after;
+                }
+            }
+            continue;
+        }
+        match c {
+            '|' => cell.push_str("\\|"),
+            '<' => cell.push_str("&lt;"),
+            c => cell.push(c),
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    cell
+}
+
+/// Make text into a code span that's safe to put in a table cell.
+fn code(text: &str) -> String {
+    cell(&format!("`{}`", text))
+}
+
+fn footnote_references(indices: &[usize]) -> String {
+    indices
+        .iter()
+        .map(|&index| footnote_symbol(index))
+        .collect()
+}
+
+fn write_entry(out: &mut impl Write, entry: &Entry) -> io::Result<()> {
+    write!(out, "|")?;
+    match entry.condition {
+        Some(Condition::Core) => write!(out, "<kbd>core only</kbd> ")?,
+        Some(Condition::Compatibility) => write!(out, "<kbd>compatibility only</kbd> ")?,
+        Some(Condition::ImagingSubset) => write!(out, "<kbd>Imaging Subset</kbd> ")?,
+        None => (),
+    }
+    match entry.get_value {
+        Some(ref get_value) => write!(out, "{}", code(get_value))?,
+        None => write!(out, "—")?,
+    }
+    write!(out, "{}", footnote_references(&entry.get_value_footnotes))?;
+    if let Some(ref alt_get_value) = entry.alt_get_value {
+        write!(out, " *or* {}", code(alt_get_value))?;
+    }
+    if let Some(ref minimum) = entry.series {
+        write!(
+            out,
+            " … {} + (*n*-1) where *n* ≥ {}",
+            code(entry.get_value.as_deref().unwrap()),
+            minimum
+        )?;
+    }
+
+    write!(out, "|")?;
+    if entry.series.is_some() {
+        write!(out, "*n* × ")?;
+    }
+    match entry.type_ {
+        Some(MaybeParsed::Parsed(ref type_)) => write!(out, "{}", type_to_unicode(type_))?,
+        Some(MaybeParsed::Unparsed(ref type_)) => write!(out, "{}", cell(type_))?,
+        None if entry.type_footnotes.is_empty() => write!(out, "—")?,
+        None => (),
+    }
+    write!(out, "{}", footnote_references(&entry.type_footnotes))?;
+
+    write!(out, "|")?;
+    match entry.get_cmnd {
+        Some(ref get_cmnd) => write!(out, "{}", code(get_cmnd))?,
+        None => write!(out, "—")?,
+    }
+
+    write!(out, "|")?;
+    match entry.initial_value {
+        Some(ref initial_value) => write!(out, "{}", cell(initial_value))?,
+        None if entry.initial_value_footnotes.is_empty() => write!(out, "—")?,
+        None => (),
+    }
+    write!(
+        out,
+        "{}",
+        footnote_references(&entry.initial_value_footnotes)
+    )?;
+
+    write!(
+        out,
+        "|{}{}",
+        cell(&entry.description),
+        footnote_references(&entry.description_footnotes)
+    )?;
+
+    writeln!(
+        out,
+        "|{}|",
+        entry
+            .attribute
+            .as_deref()
+            .map(cell)
+            .as_deref()
+            .unwrap_or("—")
+    )
+}
+
+/// Write a section for a table.
+pub fn write_table(out: &mut impl Write, table: &Table) -> io::Result<()> {
+    writeln!(out, "## {}", cell(&table.title))?;
+    writeln!(out)?;
+    if let Some(ref caption) = table.caption {
+        writeln!(out, "{}", cell(caption))?;
+        writeln!(out)?;
+    }
+    writeln!(
+        out,
+        "|Get value|Type|Get command|Initial value|Description|Attribute|"
+    )?;
+    writeln!(out, "|---|---|---|---|---|---|")?;
+    for entry in &table.entries {
+        write_entry(out, entry)?;
+    }
+    writeln!(out)?;
+    if !table.footnotes.is_empty() {
+        for (index, footnote) in table.footnotes.iter().enumerate() {
+            writeln!(out, "- {} {}", footnote_symbol(index), cell(footnote))?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Write all the tables of each spec.
+pub fn write_markdown(out: &mut impl Write, specs: &[SpecTables]) -> io::Result<()> {
+    for &(_, name, _, tables) in specs {
+        writeln!(out, "# {} state tables", name)?;
+        writeln!(out)?;
+        for table in tables {
+            write_table(out, table)?;
+        }
+    }
+    writeln!(out, "---")?;
+    writeln!(out)?;
+    writeln!(out, "{}", DISCLAIMER)?;
+    writeln!(out)?;
+    for &(_, name, copyright, _) in specs {
+        writeln!(out, "{} specification acknowledgments:", name)?;
+        writeln!(out)?;
+        writeln!(out, "```")?;
+        writeln!(out, "{}", copyright)?;
+        writeln!(out, "```")?;
+        writeln!(out)?;
+    }
+    writeln!(out, "{}", LICENSE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cell, write_markdown};
+
+    #[test]
+    fn cell_escaping() {
+        assert_eq!(cell("a < b | c"), "a &lt; b \\| c");
+        assert_eq!(cell("`a < b | c` < d"), "`a < b \\| c` &lt; d");
+        assert_eq!(cell("``a ` < b`` < c"), "``a ` < b`` &lt; c");
+        // An unclosed backtick doesn't start a code span
+        assert_eq!(cell("` < a"), "` &lt; a");
+        assert_eq!(cell(" a\n  b "), "a b");
+    }
+
+    #[test]
+    fn document() {
+        let mut out = Vec::new();
+        crate::with_specs(|specs| write_markdown(&mut out, specs)).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("# OpenGL ES 1.1 state tables\n"));
+        assert!(out.contains("\n|`CURRENT_COLOR`|C|`GetFloatv`|1,1,1,1|Current color|current|\n"));
+        // Every row of every table has the six columns, so nothing in the
+        // cells was taken for a column separator
+        for line in out.lines().filter(|line| line.starts_with('|')) {
+            let separators = line.matches('|').count() - line.matches("\\|").count();
+            assert_eq!(separators, 7, "{}", line);
+        }
+    }
+}
@@ -0,0 +1,436 @@
+//! Conversion of the LaTeX math used in the state tables to MathML Core, so
+//! that browsers can render it natively without any JavaScript.
+//!
+//! Only the small subset of math the tables actually use is supported:
+//! identifiers, numbers, operators, groups, superscripts and subscripts,
+//! `\bf`, a few symbols like `\times` and `\pm`, Greek letters, and macros
+//! defined in the spec's source. Anything else is left as LaTeX.
+
+use crate::latex::{self, TokenKind};
+use std::borrow::Cow;
+
+/// Macros the tables use that are defined elsewhere in the full specs.
+const SPEC_MACROS: &[(&str, &str)] = &[
+    ("\\lodobjbias", "bias_{texobj}"),
+    ("\\lodunitbias", "bias_{texunit}"),
+];
+
+/// Control words for single symbols, and whether they are operators.
+const SYMBOLS: &[(&str, &str, bool)] = &[
+    ("\\times", "×", true),
+    ("\\pm", "±", true),
+    ("\\mp", "∓", true),
+    ("\\neq", "≠", true),
+    ("\\ne", "≠", true),
+    ("\\leq", "≤", true),
+    ("\\le", "≤", true),
+    ("\\geq", "≥", true),
+    ("\\ge", "≥", true),
+    ("\\cdot", "⋅", true),
+    ("\\dots", "…", true),
+    ("\\ldots", "…", true),
+    ("\\cdots", "⋯", true),
+    ("\\{", "{", true),
+    ("\\}", "}", true),
+    ("\\infty", "∞", false),
+    ("\\alpha", "α", false),
+    ("\\beta", "β", false),
+    ("\\gamma", "γ", false),
+    ("\\delta", "δ", false),
+    ("\\epsilon", "ϵ", false),
+    ("\\varepsilon", "ε", false),
+    ("\\zeta", "ζ", false),
+    ("\\eta", "η", false),
+    ("\\theta", "θ", false),
+    ("\\iota", "ι", false),
+    ("\\kappa", "κ", false),
+    ("\\lambda", "λ", false),
+    ("\\mu", "μ", false),
+    ("\\nu", "ν", false),
+    ("\\xi", "ξ", false),
+    ("\\pi", "π", false),
+    ("\\rho", "ρ", false),
+    ("\\sigma", "σ", false),
+    ("\\tau", "τ", false),
+    ("\\upsilon", "υ", false),
+    ("\\phi", "ϕ", false),
+    ("\\varphi", "φ", false),
+    ("\\chi", "χ", false),
+    ("\\psi", "ψ", false),
+    ("\\omega", "ω", false),
+    ("\\Gamma", "Γ", false),
+    ("\\Delta", "Δ", false),
+    ("\\Theta", "Θ", false),
+    ("\\Lambda", "Λ", false),
+    ("\\Xi", "Ξ", false),
+    ("\\Pi", "Π", false),
+    ("\\Sigma", "Σ", false),
+    ("\\Phi", "Φ", false),
+    ("\\Psi", "Ψ", false),
+    ("\\Omega", "Ω", false),
+];
+
+/// Macros without parameters that a spec's LaTeX source defines with `\def`,
+/// e.g. `\Zplus`.
+pub struct Macros<'src>(Vec<(&'src str, &'src str)>);
+
+impl<'src> Macros<'src> {
+    pub fn from_source(source: &'src str) -> Macros<'src> {
+        let tokens = latex::tokenize(source);
+        let mut definitions: Vec<(&str, &str)> = Vec::new();
+        for (index, token) in tokens.iter().enumerate() {
+            if !token.is_control_word(source, "def") {
+                continue;
+            }
+            let (Some(name), Some(open)) = (tokens.get(index + 1), tokens.get(index + 2)) else {
+                continue;
+            };
+            if name.kind != TokenKind::ControlWord || open.kind != TokenKind::BeginGroup {
+                continue;
+            }
+            let Some(close) = latex::matching_end_group(&tokens, index + 2) else {
+                continue;
+            };
+            let name = name.text(source);
+            // Conditional definitions have more than one, take the first
+            if definitions.iter().any(|&(existing, _)| existing == name) {
+                continue;
+            }
+            definitions.push((name, &source[open.span.end..tokens[close].span.start]));
+        }
+        Macros(definitions)
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        if let Some(&(_, body)) = self.0.iter().find(|&&(defined, _)| defined == name) {
+            Some(body)
+        } else {
+            SPEC_MACROS
+                .iter()
+                .find(|&&(defined, _)| defined == name)
+                .map(|&(_, body)| body)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Item<'a> {
+    Char(char),
+    Control(&'a str),
+    BeginGroup,
+    EndGroup,
+}
+
+/// Split math into characters, control sequences and groups, expanding
+/// macros. Whitespace is insignificant in math so it's dropped.
+fn items<'a>(latex: &'a str, macros: &'a Macros, depth: u32) -> Option<Vec<Item<'a>>> {
+    // Guard against recursive macros
+    if depth > 8 {
+        return None;
+    }
+    let mut items = Vec::new();
+    for token in latex::tokenize(latex) {
+        let text = token.text(latex);
+        match token.kind {
+            TokenKind::ControlWord | TokenKind::ControlSymbol => {
+                if let Some(body) = macros.get(text) {
+                    items.push(Item::BeginGroup);
+                    items.extend(self::items(body, macros, depth + 1)?);
+                    items.push(Item::EndGroup);
+                } else {
+                    items.push(Item::Control(text));
+                }
+            }
+            TokenKind::BeginGroup => items.push(Item::BeginGroup),
+            TokenKind::EndGroup => items.push(Item::EndGroup),
+            TokenKind::Text => items.extend(text.chars().map(Item::Char)),
+            TokenKind::Whitespace | TokenKind::Comment => (),
+            TokenKind::MathShift => return None,
+        }
+    }
+    Some(items)
+}
+
+/// The Mathematical Alphanumeric Symbols version of a bold character.
+fn bold(c: char) -> char {
+    let offset = match c {
+        'A'..='Z' => 0x1D400 - 'A' as u32,
+        'a'..='z' => 0x1D41A - 'a' as u32,
+        '0'..='9' => 0x1D7CE - '0' as u32,
+        _ => return c,
+    };
+    char::from_u32(c as u32 + offset).unwrap()
+}
+
+fn escape(c: char) -> Cow<'static, str> {
+    match c {
+        '<' => "&lt;".into(),
+        '>' => "&gt;".into(),
+        '&' => "&amp;".into(),
+        // TeX renders a hyphen in math as a minus sign
+        '-' => "−".into(),
+        c => c.to_string().into(),
+    }
+}
+
+struct Parser<'a> {
+    items: Vec<Item<'a>>,
+    index: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Item<'a>> {
+        self.items.get(self.index).copied()
+    }
+
+    /// Parse items until the end of the current group.
+    fn row(&mut self, mut is_bold: bool) -> Option<String> {
+        let mut mathml = String::new();
+        loop {
+            match self.peek() {
+                None | Some(Item::EndGroup) => return Some(mathml),
+                Some(Item::Control("\\bf")) => {
+                    self.index += 1;
+                    is_bold = true;
+                }
+                // Spacing isn't worth reproducing
+                Some(Item::Control("\\," | "\\;" | "\\:" | "\\!" | "\\ ")) => self.index += 1,
+                _ => mathml.push_str(&self.scripted_atom(is_bold)?),
+            }
+        }
+    }
+
+    /// Parse an atom followed by any superscript or subscript.
+    fn scripted_atom(&mut self, is_bold: bool) -> Option<String> {
+        let base = self.atom(is_bold, false)?;
+        let (mut superscript, mut subscript) = (None, None);
+        loop {
+            let script = match self.peek() {
+                Some(Item::Char('^')) if superscript.is_none() => &mut superscript,
+                Some(Item::Char('_')) if subscript.is_none() => &mut subscript,
+                _ => break,
+            };
+            self.index += 1;
+            *script = Some(self.atom(is_bold, true)?);
+        }
+        Some(match (superscript, subscript) {
+            (None, None) => base,
+            (Some(sup), None) => format!("<msup>{}{}</msup>", base, sup),
+            (None, Some(sub)) => format!("<msub>{}{}</msub>", base, sub),
+            (Some(sup), Some(sub)) => format!("<msubsup>{}{}{}</msubsup>", base, sub, sup),
+        })
+    }
+
+    /// Parse a single atom. In a script, a bare number is only one digit,
+    /// like in TeX.
+    fn atom(&mut self, is_bold: bool, in_script: bool) -> Option<String> {
+        let item = self.peek()?;
+        self.index += 1;
+        match item {
+            Item::BeginGroup => {
+                let row = self.row(is_bold)?;
+                if self.peek() != Some(Item::EndGroup) {
+                    return None;
+                }
+                self.index += 1;
+                Some(format!("<mrow>{}</mrow>", row))
+            }
+            Item::EndGroup => None,
+            Item::Char(c) if c.is_ascii_digit() => {
+                let mut number = String::from(c);
+                while let (false, Some(Item::Char(next))) = (in_script, self.peek()) {
+                    match (next, self.items.get(self.index + 1)) {
+                        (c, _) if c.is_ascii_digit() => number.push(c),
+                        ('.', Some(Item::Char(after))) if after.is_ascii_digit() => {
+                            number.push('.')
+                        }
+                        _ => break,
+                    }
+                    self.index += 1;
+                }
+                let number: String = number
+                    .chars()
+                    .map(|c| if is_bold { bold(c) } else { c })
+                    .collect();
+                Some(format!("<mn>{}</mn>", number))
+            }
+            Item::Char(c) if c.is_alphabetic() => {
+                if is_bold {
+                    Some(format!("<mi>{}</mi>", bold(c)))
+                } else {
+                    Some(format!("<mi>{}</mi>", c))
+                }
+            }
+            Item::Char(c) if "+-=,()[]*/<>|!:;.'".contains(c) => {
+                Some(format!("<mo>{}</mo>", escape(c)))
+            }
+            Item::Char(_) => None,
+            // Type names like \glt{char}
+            Item::Control("\\glt") => {
+                let mut name = String::new();
+                if self.peek() != Some(Item::BeginGroup) {
+                    return None;
+                }
+                self.index += 1;
+                while let Some(Item::Char(c)) = self.peek() {
+                    name.push(c);
+                    self.index += 1;
+                }
+                if self.peek() != Some(Item::EndGroup) {
+                    return None;
+                }
+                self.index += 1;
+                Some(format!("<mtext>{}</mtext>", name))
+            }
+            Item::Control(name) => {
+                let &(_, symbol, is_operator) =
+                    SYMBOLS.iter().find(|&&(control, _, _)| control == name)?;
+                Some(if is_operator {
+                    format!("<mo>{}</mo>", symbol)
+                } else if symbol.chars().all(char::is_uppercase) {
+                    // Capital Greek letters are upright in TeX
+                    format!("<mi mathvariant=normal>{}</mi>", symbol)
+                } else {
+                    format!("<mi>{}</mi>", symbol)
+                })
+            }
+        }
+    }
+}
+
+/// Convert LaTeX math, without the surrounding `$`s, to a MathML Core
+/// `<math>` element. Returns [None] if it uses anything unsupported.
+pub fn math_to_mathml(latex: &str, macros: &Macros) -> Option<String> {
+    let mut parser = Parser {
+        items: items(latex, macros, 0)?,
+        index: 0,
+    };
+    let row = parser.row(false)?;
+    // Unbalanced closing brace
+    if parser.index != parser.items.len() {
+        return None;
+    }
+    Some(format!("<math>{}</math>", row))
+}
+
+/// Escape text for use in HTML, including in double-quoted attribute values.
+pub fn escape_html(text: &str) -> Cow<'_, str> {
+    if !text.contains(['<', '>', '&', '"']) {
+        return Cow::Borrowed(text);
+    }
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+/// Convert text to HTML, turning each `$...$` inline math span into MathML and
+/// escaping everything else. Math that can't be converted is left as LaTeX.
+pub fn text_to_html<'a>(text: &'a str, macros: &Macros) -> Cow<'a, str> {
+    let shifts: Vec<_> = latex::tokenize(text)
+        .into_iter()
+        .filter(|token| token.kind == TokenKind::MathShift)
+        .collect();
+    if shifts.len() < 2 {
+        return escape_html(text);
+    }
+
+    let mut converted = String::new();
+    let mut offset = 0;
+    for pair in shifts.chunks_exact(2) {
+        let (open, close) = (pair[0].span, pair[1].span);
+        let Some(mathml) = math_to_mathml(&text[open.end..close.start], macros) else {
+            continue;
+        };
+        converted.push_str(&escape_html(&text[offset..open.start]));
+        converted.push_str(&mathml);
+        offset = close.end;
+    }
+    converted.push_str(&escape_html(&text[offset..]));
+    Cow::Owned(converted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(latex: &str) -> Option<String> {
+        math_to_mathml(latex, &Macros::from_source("\\def\\Zplus{Z^{+}}"))
+    }
+
+    #[test]
+    fn scripts() {
+        assert_eq!(
+            convert("2^{31}-1").unwrap(),
+            "<math><msup><mn>2</mn><mrow><mn>31</mn></mrow></msup><mo>−</mo><mn>1</mn></math>"
+        );
+        assert_eq!(
+            convert("Z^9").unwrap(),
+            "<math><msup><mi>Z</mi><mn>9</mn></msup></math>"
+        );
+        assert_eq!(
+            convert("x_w").unwrap(),
+            "<math><msub><mi>x</mi><mi>w</mi></msub></math>"
+        );
+    }
+
+    #[test]
+    fn symbols_and_macros() {
+        assert_eq!(
+            convert("n \\times \\Zplus").unwrap(),
+            "<math><mi>n</mi><mo>×</mo><mrow><msup><mi>Z</mi><mrow><mo>+</mo></mrow></msup></mrow></math>"
+        );
+        assert_eq!(
+            convert("\\pm\\alpha\\Omega").unwrap(),
+            "<math><mo>±</mo><mi>α</mi><mi mathvariant=normal>Ω</mi></math>"
+        );
+        assert_eq!(
+            convert("\\lodobjbias").unwrap(),
+            "<math><mrow><mi>b</mi><mi>i</mi><mi>a</mi><msub><mi>s</mi><mrow><mi>t</mi><mi>e</mi><mi>x</mi><mi>o</mi><mi>b</mi><mi>j</mi></mrow></msub></mrow></math>"
+        );
+        assert_eq!(
+            convert("{\\bf a}_{cm}").unwrap(),
+            "<math><msub><mrow><mi>𝐚</mi></mrow><mrow><mi>c</mi><mi>m</mi></mrow></msub></math>"
+        );
+    }
+
+    #[test]
+    fn unsupported() {
+        assert_eq!(convert("\\frac{1}{2}"), None);
+        assert_eq!(convert("{x"), None);
+        assert_eq!(convert("x}"), None);
+        assert_eq!(
+            math_to_mathml("\\a", &Macros::from_source("\\def\\a{\\a}")),
+            None
+        );
+    }
+
+    #[test]
+    fn inline_math() {
+        let macros = Macros::from_source("");
+        assert_eq!(
+            text_to_html("When $\\neq 0$, or \\$5", &macros),
+            "When <math><mo>≠</mo><mn>0</mn></math>, or \\$5"
+        );
+        assert_eq!(
+            text_to_html("$\\frac12$ and $1.0$", &macros),
+            "$\\frac12$ and <math><mn>1.0</mn></math>"
+        );
+        assert!(matches!(text_to_html("no math", &macros), Cow::Borrowed(_)));
+        assert_eq!(
+            text_to_html("a < b & $x<y$", &macros),
+            "a &lt; b &amp; <math><mi>x</mi><mo>&lt;</mo><mi>y</mi></math>"
+        );
+        assert_eq!(
+            text_to_html("<b>$\\frac12$", &macros),
+            "&lt;b&gt;$\\frac12$"
+        );
+    }
+}
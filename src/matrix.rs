@@ -0,0 +1,216 @@
+//! The availability matrix: every distinct get value from all the specs, and
+//! which get command it uses in each API version and profile, if it's there at
+//! all. This is useful for porting between them.
+
+use crate::csv::csv_field;
+use crate::search_index::json_string;
+use crate::SpecTables;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use OpenGL_state_table_parser::mathml::escape_html;
+use OpenGL_state_table_parser::Condition;
+
+/// The columns of the matrix. The GL 4.6 Imaging Subset is only in the
+/// compatibility profile, but it's optional, so it gets its own column.
+pub const COLUMNS: [&str; 5] = [
+    "OpenGL ES 1.1",
+    "OpenGL ES 3.2",
+    "OpenGL 4.6 core",
+    "OpenGL 4.6 compatibility",
+    "OpenGL 4.6 Imaging Subset",
+];
+
+/// Styles for the matrix, which are also in the site's stylesheet.
+const STYLE: &str =
+    ".matrix .present { background-color: lightgreen; } .matrix .absent { color: gray; }";
+
+/// The get commands for a variable in one column. An empty list means it's
+/// there, but the table doesn't say how to get it.
+type Cell = Option<Vec<String>>;
+
+pub struct Matrix {
+    rows: BTreeMap<String, [Cell; 5]>,
+}
+
+impl Matrix {
+    /// Build the matrix from the tables of each spec.
+    pub fn new(specs: &[SpecTables]) -> Matrix {
+        let mut rows: BTreeMap<String, [Cell; 5]> = BTreeMap::new();
+        for &(suffix, _, _, tables) in specs {
+            for entry in tables.iter().flat_map(|table| &table.entries) {
+                let Some(ref get_value) = entry.get_value else {
+                    continue;
+                };
+                let columns: &[usize] = match (suffix, entry.condition) {
+                    ("es11", _) => &[0],
+                    ("es", _) => &[1],
+                    ("gl", None) => &[2, 3],
+                    ("gl", Some(Condition::Core)) => &[2],
+                    ("gl", Some(Condition::Compatibility)) => &[3],
+                    ("gl", Some(Condition::ImagingSubset)) => &[4],
+                    _ => panic!("Unknown spec {}", suffix),
+                };
+                let row = rows.entry(get_value.to_string()).or_default();
+                for &column in columns {
+                    let commands = row[column].get_or_insert_with(Vec::new);
+                    if let Some(ref get_cmnd) = entry.get_cmnd {
+                        if !commands.iter().any(|command| command == get_cmnd) {
+                            commands.push(get_cmnd.to_string());
+                        }
+                    }
+                }
+            }
+        }
+        Matrix { rows }
+    }
+
+    /// Write the matrix as an HTML table. `variable_page` gives the link for a
+    /// get value, if there is one.
+    pub fn write_html_table(
+        &self,
+        out: &mut impl Write,
+        variable_page: impl Fn(&str) -> Option<String>,
+    ) -> io::Result<()> {
+        writeln!(out, "<table class=matrix>")?;
+        writeln!(out, "<thead>")?;
+        write!(out, "<tr><th>Get value</th>")?;
+        for column in COLUMNS {
+            write!(out, "<th>{}</th>", column)?;
+        }
+        writeln!(out, "</tr>")?;
+        writeln!(out, "</thead>")?;
+        writeln!(out, "<tbody>")?;
+        for (get_value, cells) in &self.rows {
+            write!(out, "<tr><td>")?;
+            if let Some(page) = variable_page(get_value) {
+                write!(
+                    out,
+                    "<a href=\"{}\"><code>{}</code></a>",
+                    escape_html(&page),
+                    escape_html(get_value)
+                )?;
+            } else {
+                write!(out, "<code>{}</code>", escape_html(get_value))?;
+            }
+            write!(out, "</td>")?;
+            for cell in cells {
+                match cell {
+                    None => write!(out, "<td class=absent>—</td>")?,
+                    Some(commands) if commands.is_empty() => {
+                        write!(out, "<td class=present>✓</td>")?
+                    }
+                    Some(commands) => write!(
+                        out,
+                        "<td class=present><code>{}</code></td>",
+                        escape_html(&commands.join(", "))
+                    )?,
+                }
+            }
+            writeln!(out, "</tr>")?;
+        }
+        writeln!(out, "</tbody>")?;
+        writeln!(out, "</table>")
+    }
+
+    /// Write the matrix as a standalone HTML page.
+    pub fn write_html(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(out, "<!doctype html>")?;
+        writeln!(out, "<meta charset=utf-8>")?;
+        writeln!(
+            out,
+            "<title>OpenGL and OpenGL ES state availability</title>"
+        )?;
+        writeln!(out, "<style>{}</style>", STYLE)?;
+        writeln!(out, "<h1>OpenGL and OpenGL ES state availability</h1>")?;
+        self.write_html_table(out, |_| None)
+    }
+
+    /// Write the matrix as CSV, with a header row. Cells are empty if the
+    /// variable isn't there, `-` if there's no get command, and otherwise the
+    /// get commands separated by spaces.
+    pub fn write_csv(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "Get value")?;
+        for column in COLUMNS {
+            write!(out, ",{}", column)?;
+        }
+        write!(out, "\r\n")?;
+        for (get_value, cells) in &self.rows {
+            write!(out, "{}", csv_field(get_value))?;
+            for cell in cells {
+                match cell {
+                    None => write!(out, ",")?,
+                    Some(commands) if commands.is_empty() => write!(out, ",-")?,
+                    Some(commands) => write!(out, ",{}", commands.join(" "))?,
+                }
+            }
+            write!(out, "\r\n")?;
+        }
+        Ok(())
+    }
+
+    /// Write the matrix as JSON of this shape:
+    ///
+    /// ```json
+    /// {
+    ///     "columns": ["OpenGL ES 1.1", ...],
+    ///     "rows": {"ACTIVE_TEXTURE": [["GetIntegerv"], ...], ...}
+    /// }
+    /// ```
+    ///
+    /// where each row has the get commands for each column, or `null` if the
+    /// variable isn't there.
+    pub fn write_json(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "{{\"columns\":[")?;
+        for (index, column) in COLUMNS.iter().enumerate() {
+            if index != 0 {
+                write!(out, ",")?;
+            }
+            write!(out, "{}", json_string(column))?;
+        }
+        writeln!(out, "],")?;
+        write!(out, "\"rows\":{{")?;
+        for (index, (get_value, cells)) in self.rows.iter().enumerate() {
+            if index != 0 {
+                writeln!(out, ",")?;
+            }
+            write!(out, "{}:[", json_string(get_value))?;
+            for (index, cell) in cells.iter().enumerate() {
+                if index != 0 {
+                    write!(out, ",")?;
+                }
+                match cell {
+                    None => write!(out, "null")?,
+                    Some(commands) => {
+                        let commands: Vec<String> = commands
+                            .iter()
+                            .map(|command| json_string(command))
+                            .collect();
+                        write!(out, "[{}]", commands.join(","))?
+                    }
+                }
+            }
+            write!(out, "]")?;
+        }
+        writeln!(out, "}}}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Matrix;
+
+    #[test]
+    fn commands_per_column() {
+        let matrix = crate::with_specs(Matrix::new);
+        let mut csv = Vec::new();
+        matrix.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        for row in [
+            "LIGHTING,IsEnabled,,,IsEnabled,",
+            "VIEWPORT,GetIntegerv,GetIntegerv,GetFloati_v,GetFloati_v,",
+            "HISTOGRAM,,,,,IsEnabled GetHistogram",
+        ] {
+            assert!(csv.lines().any(|line| line == row), "no row {}", row);
+        }
+    }
+}
@@ -0,0 +1,1133 @@
+//! A simulated GL context, seeded with the initial values from the state
+//! tables, for testing code that makes GL calls without a GPU. It answers the
+//! generic queries (`GetIntegerv`, `GetFloati_v`, `IsEnabled` and so on) for
+//! whatever the tables say is queried with them, converting values the way
+//! the spec says to, and has hand-written versions of a core set of setters
+//! like `Enable`, `BlendFunc`, `Viewport` and `BindTexture`, which return
+//! whether they changed anything.
+//!
+//! ```no_run
+//! use OpenGL_state_table_parser::mock::{MockContext, Value};
+//! use OpenGL_state_table_parser::{parse_spec, read_spec};
+//!
+//! let source = read_spec("es");
+//! let (_, tables) = parse_spec("es", &source);
+//! let mut gl = MockContext::new(&tables, None, 640, 480);
+//! gl.enable("BLEND").unwrap();
+//! gl.blend_func("SRC_ALPHA", "ONE_MINUS_SRC_ALPHA").unwrap();
+//! assert_eq!(
+//!     gl.get("GetIntegerv", "BLEND_DST_RGB").unwrap(),
+//!     [Value::Enum("ONE_MINUS_SRC_ALPHA".to_string())]
+//! );
+//! ```
+//!
+//! The tables don't have the numeric values of enums, so those stay
+//! symbolic. Implementation-dependent values are the spec's minimums. State
+//! that can't be modelled, like per-object state or state with no usable
+//! initial value in the tables, is listed by [MockContext::unmodelled], and
+//! queries of it fail with [Error::Unmodelled].
+
+use crate::types::{
+    basic_component_count, is_color, is_matrix, scalar_kind, type_quantity, MaybeParsed, Quantity,
+    ScalarKind,
+};
+use crate::{Condition, Entry, Table};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+/// A scalar in a state variable's value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Boolean(bool),
+    Integer(i64),
+    Float(f64),
+    /// An enum, by name without the `GL_` prefix
+    Enum(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// There's no state variable with this name in the tables.
+    UnknownName(String),
+    /// The state variable is in the tables, but can't be modelled, for the
+    /// given reason.
+    Unmodelled(String, String),
+    /// A real context would generate `INVALID_ENUM`, e.g. for `IsEnabled` on
+    /// state that isn't a capability.
+    InvalidEnum(String),
+    /// A real context would generate `INVALID_VALUE`, e.g. for an index past
+    /// the end.
+    InvalidValue(String),
+    /// The command isn't a modelled query, or was given an index when it
+    /// doesn't take one or not given one when it does.
+    InvalidCommand(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::UnknownName(name) => write!(f, "GL_{} isn't a state variable", name),
+            Error::Unmodelled(name, reason) => write!(f, "GL_{} isn't modelled: {}", name, reason),
+            Error::InvalidEnum(message) => write!(f, "INVALID_ENUM: {}", message),
+            Error::InvalidValue(message) => write!(f, "INVALID_VALUE: {}", message),
+            Error::InvalidCommand(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Boolean(true) => f.write_str("TRUE"),
+            Value::Boolean(false) => f.write_str("FALSE"),
+            Value::Integer(i) => write!(f, "{}", i),
+            Value::Float(x) => write!(f, "{}", x),
+            Value::Enum(name) => write!(f, "GL_{}", name),
+        }
+    }
+}
+
+/// The queries that are modelled.
+#[derive(Clone, Copy, PartialEq)]
+enum Query {
+    Boolean,
+    Integer,
+    Integer64,
+    Float,
+    Double,
+    IsEnabled,
+}
+
+/// The query a command makes, and whether it's indexed, if it's modelled.
+fn parse_query(command: &str) -> Option<(Query, bool)> {
+    let command = command.strip_prefix("gl").unwrap_or(command);
+    Some(match command {
+        "GetBooleanv" => (Query::Boolean, false),
+        "GetBooleani_v" => (Query::Boolean, true),
+        "GetIntegerv" => (Query::Integer, false),
+        "GetIntegeri_v" => (Query::Integer, true),
+        "GetInteger64v" => (Query::Integer64, false),
+        "GetInteger64i_v" => (Query::Integer64, true),
+        "GetFloatv" => (Query::Float, false),
+        "GetFloati_v" => (Query::Float, true),
+        "GetDoublev" => (Query::Double, false),
+        "GetDoublei_v" => (Query::Double, true),
+        "IsEnabled" => (Query::IsEnabled, false),
+        "IsEnabledi" => (Query::IsEnabled, true),
+        _ => return None,
+    })
+}
+
+/// The limits whose quantities count texture units, which are selected with
+/// `ActiveTexture` rather than an index.
+const TEXTURE_UNIT_LIMITS: [&str; 3] = [
+    "MAX_TEXTURE_UNITS",
+    "MAX_COMBINED_TEXTURE_IMAGE_UNITS",
+    "MAX_TEXTURE_COORDS",
+];
+
+/// How the copy of a variable that a query gets is chosen.
+#[derive(Clone, Copy, PartialEq)]
+enum Selector {
+    /// There's only one.
+    None,
+    /// One per texture unit, chosen by `ACTIVE_TEXTURE`.
+    ActiveTexture,
+    /// One per index, chosen by the index of an indexed query, or 0.
+    Index,
+}
+
+#[derive(Clone)]
+struct Variable {
+    kind: ScalarKind,
+    /// Colors and some depth values convert to integers as normalized values
+    normalized: bool,
+    capability: bool,
+    selector: Selector,
+    /// How many copies there are, if that's known
+    copies: Option<u32>,
+    /// The value of every copy that hasn't been set on its own
+    value: Vec<Value>,
+    /// Copies that have been set on their own
+    values: BTreeMap<u32, Vec<Value>>,
+}
+
+/// Parse one scalar of an initial value, e.g. `\glc{FALSE}` or `1.0`.
+fn parse_scalar(item: &str) -> Option<Value> {
+    let item = item.trim();
+    let item = item
+        .strip_prefix("\\glc{")
+        .and_then(|item| item.strip_suffix('}'))
+        .unwrap_or(item);
+    // A minimum that's derived from other minimums, like 14*
+    let item = item
+        .strip_suffix('*')
+        .filter(|item| !item.is_empty())
+        .unwrap_or(item);
+    if matches!(item, "TRUE" | "True") {
+        Some(Value::Boolean(true))
+    } else if matches!(item, "FALSE" | "False") {
+        Some(Value::Boolean(false))
+    } else if let Ok(integer) = item.parse() {
+        Some(Value::Integer(integer))
+    } else if item.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '+') {
+        item.parse().ok().map(Value::Float)
+    } else if item.starts_with(|c: char| c.is_ascii_uppercase())
+        && item
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+    {
+        Some(Value::Enum(item.to_string()))
+    } else {
+        None
+    }
+}
+
+/// Parse an initial value from the tables, e.g. `(0.0,0.0,0.0,1.0)`, `0's` or
+/// `{\em True}`.
+fn parse_initial(text: &str) -> Option<Vec<Value>> {
+    let text = text.replace('$', "");
+    let mut text = text.trim();
+    if let Some(inner) = text.strip_prefix('{').and_then(|t| t.strip_suffix('}')) {
+        text = inner;
+    }
+    for prefix in ["\\em ", "\\tt "] {
+        text = text.strip_prefix(prefix).unwrap_or(text);
+    }
+    if let Some(inner) = text.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+        text = inner;
+    }
+    if text == "Identity" {
+        return Some(
+            (0..16)
+                .map(|i| Value::Float(if i % 5 == 0 { 1.0 } else { 0.0 }))
+                .collect(),
+        );
+    }
+    // "0's" is all zeros, which is just 0 broadcast
+    let text = text.strip_suffix("'s").unwrap_or(text);
+    text.split(',').map(parse_scalar).collect()
+}
+
+/// Convert a value to the representation used for a kind of state, e.g. the
+/// 0 in a boolean's initial value to [Value::Boolean].
+fn coerce(value: Value, kind: ScalarKind) -> Value {
+    match (value, kind) {
+        (Value::Integer(i), ScalarKind::Boolean) => Value::Boolean(i != 0),
+        (Value::Integer(i), ScalarKind::Float) => Value::Float(i as f64),
+        (Value::Boolean(b), ScalarKind::Integer) => Value::Integer(b.into()),
+        (Value::Boolean(b), ScalarKind::Float) => Value::Float(f64::from(u8::from(b))),
+        (value, _) => value,
+    }
+}
+
+/// Convert a value for a query, following the spec's rules for converting
+/// state to the type a query returns. Enums stay as they are, since their
+/// numeric values aren't known.
+fn convert(value: &Value, query: Query, normalized: bool) -> Value {
+    let float_to_integer = |f: f64, bits: u32| {
+        let max = (1u64 << (bits - 1)) as f64 - 1.0;
+        if normalized {
+            (f.clamp(-1.0, 1.0) * max).round() as i64
+        } else {
+            f.round().clamp(-max - 1.0, max) as i64
+        }
+    };
+    match (value, query) {
+        (Value::Enum(name), Query::Boolean) => Value::Boolean(!matches!(
+            &name[..],
+            "NONE" | "ZERO" | "FALSE" | "NO_ERROR" | "POINTS"
+        )),
+        (Value::Enum(_), _) => value.clone(),
+        (Value::Boolean(b), Query::Boolean | Query::IsEnabled) => Value::Boolean(*b),
+        (Value::Integer(i), Query::Boolean | Query::IsEnabled) => Value::Boolean(*i != 0),
+        (Value::Float(f), Query::Boolean | Query::IsEnabled) => Value::Boolean(*f != 0.0),
+        (Value::Boolean(b), Query::Integer | Query::Integer64) => Value::Integer((*b).into()),
+        (Value::Integer(i), Query::Integer) => {
+            Value::Integer((*i).clamp(i32::MIN.into(), i32::MAX.into()))
+        }
+        (Value::Integer(i), Query::Integer64) => Value::Integer(*i),
+        (Value::Float(f), Query::Integer) => Value::Integer(float_to_integer(*f, 32)),
+        (Value::Float(f), Query::Integer64) => Value::Integer(float_to_integer(*f, 64)),
+        (Value::Boolean(b), Query::Float | Query::Double) => Value::Float(f64::from(u8::from(*b))),
+        (Value::Integer(i), Query::Float) => Value::Float(*i as f32 as f64),
+        (Value::Integer(i), Query::Double) => Value::Float(*i as f64),
+        (Value::Float(f), Query::Float) => Value::Float(*f as f32 as f64),
+        (Value::Float(f), Query::Double) => Value::Float(*f),
+    }
+}
+
+/// Model an entry, or say why it can't be. `limits` are the integer values of
+/// other state, for quantities like `MAX_DRAW_BUFFERS`.
+fn model_entry(
+    name: &str,
+    entry: &Entry,
+    limits: &HashMap<&str, u32>,
+    (width, height): (u32, u32),
+) -> Result<Variable, String> {
+    let get_cmnd = entry
+        .get_cmnd
+        .as_deref()
+        .ok_or_else(|| "it has no get command".to_string())?;
+    let (query, indexed) =
+        parse_query(get_cmnd).ok_or_else(|| format!("it's queried with {}", get_cmnd))?;
+    let type_ = match entry.type_ {
+        Some(MaybeParsed::Parsed(ref type_)) => type_,
+        Some(MaybeParsed::Unparsed(ref type_)) => {
+            return Err(format!("its type {} couldn't be parsed", type_))
+        }
+        None => return Err("it has no type".to_string()),
+    };
+    let kind = scalar_kind(type_);
+    if !matches!(
+        kind,
+        ScalarKind::Boolean | ScalarKind::Integer | ScalarKind::Enum | ScalarKind::Float
+    ) {
+        return Err(format!("it's {} state", kind));
+    }
+
+    let mut terms = type_quantity(type_).iter().peekable();
+    let (selector, copies) = match terms.peek() {
+        Some(MaybeParsed::Parsed((Quantity::Constant(limit), _))) => {
+            terms.next();
+            let selector = if TEXTURE_UNIT_LIMITS.contains(limit) {
+                Selector::ActiveTexture
+            } else {
+                Selector::Index
+            };
+            (selector, limits.get(limit).copied())
+        }
+        Some(MaybeParsed::Parsed((Quantity::Integer(n), true))) if indexed => {
+            terms.next();
+            (Selector::Index, Some(*n))
+        }
+        Some(MaybeParsed::Unparsed(_)) if indexed => {
+            terms.next();
+            (Selector::Index, None)
+        }
+        _ if indexed => (Selector::Index, None),
+        _ => (Selector::None, None),
+    };
+    // Matrix stacks, where only the top can be queried
+    if is_matrix(type_) && matches!(terms.peek(), Some(MaybeParsed::Parsed((_, true)))) {
+        terms.next();
+    }
+    let mut count = basic_component_count(type_).unwrap();
+    for term in terms {
+        match term {
+            MaybeParsed::Parsed((Quantity::Integer(n), false)) => count *= n,
+            _ => return Err("it has a variable number of values".to_string()),
+        }
+    }
+
+    let initial = entry.initial_value.as_deref().unwrap_or_default();
+    if initial.is_empty() && !matches!(name, "VIEWPORT" | "SCISSOR_BOX") {
+        return Err("it has no initial value".to_string());
+    }
+    let value = match name {
+        // These depend on the window, so the tables just refer to the spec
+        "VIEWPORT" | "SCISSOR_BOX" => vec![
+            Value::Integer(0),
+            Value::Integer(0),
+            Value::Integer(width.into()),
+            Value::Integer(height.into()),
+        ],
+        _ => parse_initial(initial).ok_or_else(|| format!("its initial value is {:?}", initial))?,
+    };
+    let mut value: Vec<Value> = value.into_iter().map(|v| coerce(v, kind)).collect();
+    if value.len() == 1 && count > 1 {
+        value = vec![value[0].clone(); count as usize];
+    }
+    if value.len() != count as usize {
+        return Err(format!(
+            "its initial value {:?} has {} values, not {}",
+            initial,
+            value.len(),
+            count
+        ));
+    }
+    Ok(Variable {
+        kind,
+        normalized: is_color(type_) || matches!(name, "DEPTH_RANGE" | "DEPTH_CLEAR_VALUE"),
+        capability: query == Query::IsEnabled && kind == ScalarKind::Boolean,
+        selector,
+        copies,
+        value,
+        values: BTreeMap::new(),
+    })
+}
+
+fn strip_gl(name: &str) -> &str {
+    name.strip_prefix("GL_").unwrap_or(name)
+}
+
+#[derive(Clone)]
+pub struct MockContext {
+    variables: HashMap<String, Variable>,
+    /// Alternative names, the variable they're for, and whether they're
+    /// transposed
+    aliases: HashMap<String, (String, bool)>,
+    unmodelled: Vec<(String, String)>,
+}
+
+impl MockContext {
+    /// Make a context with the state in `tables`, for `profile` if they're
+    /// OpenGL's. The window is `width` by `height`.
+    pub fn new(tables: &[Table], profile: Option<Condition>, width: u32, height: u32) -> Self {
+        let entries: Vec<(&str, &Entry)> = tables
+            .iter()
+            .flat_map(|table| &table.entries)
+            .filter(|entry| entry.in_profile(profile))
+            .filter_map(|entry| Some((entry.get_value.as_deref()?, entry)))
+            .collect();
+        let mut limits = HashMap::new();
+        for &(name, entry) in &entries {
+            if let Some([Value::Integer(value)]) = entry
+                .initial_value
+                .as_deref()
+                .and_then(parse_initial)
+                .as_deref()
+            {
+                limits.entry(name).or_insert(*value as u32);
+            }
+        }
+
+        let mut context = MockContext {
+            variables: HashMap::new(),
+            aliases: HashMap::new(),
+            unmodelled: Vec::new(),
+        };
+        for (name, entry) in entries {
+            // Some names are used for more than one thing, like TEXTURE_2D
+            if context.variables.contains_key(name) {
+                continue;
+            }
+            let names: Vec<String> = match entry.series {
+                None => vec![name.to_string()],
+                Some(ref minimum) => {
+                    let count = match minimum {
+                        Quantity::Integer(n) => Some(*n),
+                        Quantity::Constant(limit) => limits.get(limit).copied(),
+                    };
+                    let Some(count) = count else {
+                        context.unmodelled.push((
+                            name.to_string(),
+                            "the size of its series isn't known".to_string(),
+                        ));
+                        continue;
+                    };
+                    let prefix = &name[..name.len() - 1];
+                    (0..count).map(|i| format!("{}{}", prefix, i)).collect()
+                }
+            };
+            match model_entry(name, entry, &limits, (width, height)) {
+                Ok(variable) => {
+                    for name in names {
+                        context.variables.insert(name, variable.clone());
+                    }
+                    if let Some(ref alt) = entry.alt_get_value {
+                        context.aliases.insert(
+                            alt.to_string(),
+                            (name.to_string(), alt.starts_with("TRANSPOSE_")),
+                        );
+                    }
+                }
+                Err(reason) => context.unmodelled.push((name.to_string(), reason)),
+            }
+        }
+        context
+    }
+
+    /// The state that isn't modelled, by name, and why.
+    pub fn unmodelled(&self) -> &[(String, String)] {
+        &self.unmodelled
+    }
+
+    /// The state that's different here than in `other`, by name, with its
+    /// value here. Copies of per-unit or indexed state that were set on their
+    /// own are named like `TEXTURE_BINDING_2D[1]`.
+    pub fn differences(&self, other: &MockContext) -> Vec<(String, Vec<Value>)> {
+        let mut names: Vec<&String> = self.variables.keys().collect();
+        names.sort();
+        let mut differences = Vec::new();
+        for name in names {
+            let variable = &self.variables[name];
+            let Some(other) = other.variables.get(name) else {
+                continue;
+            };
+            if variable.value != other.value {
+                differences.push((name.clone(), variable.value.clone()));
+            }
+            let copies: BTreeSet<u32> = variable
+                .values
+                .keys()
+                .chain(other.values.keys())
+                .copied()
+                .collect();
+            for copy in copies {
+                let value = variable.values.get(&copy).unwrap_or(&variable.value);
+                if value != other.values.get(&copy).unwrap_or(&other.value) {
+                    differences.push((format!("{}[{}]", name, copy), value.clone()));
+                }
+            }
+        }
+        differences
+    }
+
+    /// Find a variable by name, and whether it's transposed.
+    fn variable(&self, name: &str) -> Result<(&str, &Variable, bool), Error> {
+        let name = strip_gl(name);
+        let (name, transposed) = match self.aliases.get(name) {
+            Some((name, transposed)) => (&name[..], *transposed),
+            None => (name, false),
+        };
+        if let Some((name, variable)) = self.variables.get_key_value(name) {
+            return Ok((name, variable, transposed));
+        }
+        Err(
+            match self.unmodelled.iter().find(|(other, _)| other == name) {
+                Some((name, reason)) => Error::Unmodelled(name.clone(), reason.clone()),
+                None => Error::UnknownName(name.to_string()),
+            },
+        )
+    }
+
+    /// The current texture unit.
+    fn active_unit(&self) -> u32 {
+        match self.variables.get("ACTIVE_TEXTURE").map(|v| &v.value[..]) {
+            Some([Value::Enum(unit)]) => unit
+                .strip_prefix("TEXTURE")
+                .and_then(|unit| unit.parse().ok())
+                .unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    /// Which copy of a variable an access with `index` is for.
+    fn copy(&self, name: &str, variable: &Variable, index: Option<u32>) -> Result<u32, Error> {
+        let copy = match (variable.selector, index) {
+            (Selector::None, Some(_)) => {
+                return Err(Error::InvalidEnum(format!("GL_{} isn't indexed", name)))
+            }
+            (Selector::None, None) => 0,
+            (Selector::ActiveTexture, None) => self.active_unit(),
+            (_, index) => index.unwrap_or(0),
+        };
+        match variable.copies {
+            Some(copies) if copy >= copies => Err(Error::InvalidValue(format!(
+                "GL_{} only has {} values",
+                name, copies
+            ))),
+            _ => Ok(copy),
+        }
+    }
+
+    fn query(&self, command: &str, pname: &str, index: Option<u32>) -> Result<Vec<Value>, Error> {
+        let Some((query, indexed)) = parse_query(command) else {
+            return Err(Error::InvalidCommand(format!(
+                "{} isn't a modelled query",
+                command
+            )));
+        };
+        if indexed != index.is_some() {
+            return Err(Error::InvalidCommand(format!(
+                "{} {} an index",
+                command,
+                if indexed { "needs" } else { "doesn't take" }
+            )));
+        }
+        let (name, variable, transposed) = self.variable(pname)?;
+        if query == Query::IsEnabled && !variable.capability {
+            return Err(Error::InvalidEnum(format!(
+                "GL_{} isn't a capability",
+                name
+            )));
+        }
+        let copy = self.copy(name, variable, index)?;
+        let mut value = variable
+            .values
+            .get(&copy)
+            .unwrap_or(&variable.value)
+            .clone();
+        if transposed {
+            value = (0..16).map(|i| value[i % 4 * 4 + i / 4].clone()).collect();
+        }
+        Ok(value
+            .iter()
+            .map(|value| convert(value, query, variable.normalized))
+            .collect())
+    }
+
+    /// Query state with a generic, non-indexed command like `GetIntegerv`.
+    /// Names can have the `GL_` and `gl` prefixes or not.
+    pub fn get(&self, command: &str, pname: &str) -> Result<Vec<Value>, Error> {
+        self.query(command, pname, None)
+    }
+
+    /// Query state with an indexed command like `GetIntegeri_v`.
+    pub fn get_indexed(&self, command: &str, pname: &str, index: u32) -> Result<Vec<Value>, Error> {
+        self.query(command, pname, Some(index))
+    }
+
+    pub fn is_enabled(&self, cap: &str) -> Result<bool, Error> {
+        Ok(self.get("IsEnabled", cap)? == [Value::Boolean(true)])
+    }
+
+    pub fn is_enabledi(&self, cap: &str, index: u32) -> Result<bool, Error> {
+        Ok(self.get_indexed("IsEnabledi", cap, index)? == [Value::Boolean(true)])
+    }
+
+    /// Set a variable, returning whether its value changed. Setting indexed
+    /// state without an index sets every index, like `Viewport` does for
+    /// every viewport.
+    fn set(&mut self, pname: &str, index: Option<u32>, value: Vec<Value>) -> Result<bool, Error> {
+        let (name, variable, _) = self.variable(pname)?;
+        let copy = self.copy(name, variable, index)?;
+        let name = name.to_string();
+        let variable = self.variables.get_mut(&name).unwrap();
+        if value.len() != variable.value.len() {
+            return Err(Error::Unmodelled(
+                name,
+                format!(
+                    "it has {} values, not {}",
+                    variable.value.len(),
+                    value.len()
+                ),
+            ));
+        }
+        let value: Vec<Value> = value
+            .into_iter()
+            .map(|v| coerce(v, variable.kind))
+            .collect();
+        let changed = match (variable.selector, index) {
+            (Selector::None, _) | (Selector::Index, None) => {
+                let changed =
+                    variable.value != value || variable.values.values().any(|copy| *copy != value);
+                variable.value = value;
+                variable.values.clear();
+                changed
+            }
+            _ => {
+                let changed = *variable.values.get(&copy).unwrap_or(&variable.value) != value;
+                variable.values.insert(copy, value);
+                changed
+            }
+        };
+        Ok(changed)
+    }
+
+    fn set_capability(
+        &mut self,
+        cap: &str,
+        index: Option<u32>,
+        enabled: bool,
+    ) -> Result<bool, Error> {
+        let (name, variable, _) = self.variable(cap)?;
+        if !variable.capability {
+            return Err(Error::InvalidEnum(format!(
+                "GL_{} isn't a capability",
+                name
+            )));
+        }
+        self.set(cap, index, vec![Value::Boolean(enabled)])
+    }
+
+    fn set_enum(&mut self, pname: &str, value: &str) -> Result<bool, Error> {
+        self.set(pname, None, vec![Value::Enum(strip_gl(value).to_string())])
+    }
+
+    pub fn enable(&mut self, cap: &str) -> Result<bool, Error> {
+        self.set_capability(cap, None, true)
+    }
+
+    pub fn disable(&mut self, cap: &str) -> Result<bool, Error> {
+        self.set_capability(cap, None, false)
+    }
+
+    pub fn enablei(&mut self, cap: &str, index: u32) -> Result<bool, Error> {
+        self.set_capability(cap, Some(index), true)
+    }
+
+    pub fn disablei(&mut self, cap: &str, index: u32) -> Result<bool, Error> {
+        self.set_capability(cap, Some(index), false)
+    }
+
+    pub fn blend_func(&mut self, sfactor: &str, dfactor: &str) -> Result<bool, Error> {
+        // OpenGL ES 1.1 doesn't have separate alpha factors
+        if self.variables.contains_key("BLEND_SRC") {
+            Ok(self.set_enum("BLEND_SRC", sfactor)? | self.set_enum("BLEND_DST", dfactor)?)
+        } else {
+            self.blend_func_separate(sfactor, dfactor, sfactor, dfactor)
+        }
+    }
+
+    pub fn blend_func_separate(
+        &mut self,
+        src_rgb: &str,
+        dst_rgb: &str,
+        src_alpha: &str,
+        dst_alpha: &str,
+    ) -> Result<bool, Error> {
+        Ok(self.set_enum("BLEND_SRC_RGB", src_rgb)?
+            | self.set_enum("BLEND_DST_RGB", dst_rgb)?
+            | self.set_enum("BLEND_SRC_ALPHA", src_alpha)?
+            | self.set_enum("BLEND_DST_ALPHA", dst_alpha)?)
+    }
+
+    pub fn blend_equation(&mut self, mode: &str) -> Result<bool, Error> {
+        Ok(self.set_enum("BLEND_EQUATION_RGB", mode)?
+            | self.set_enum("BLEND_EQUATION_ALPHA", mode)?)
+    }
+
+    fn set_rectangle(
+        &mut self,
+        pname: &str,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+    ) -> Result<bool, Error> {
+        if width < 0 || height < 0 {
+            return Err(Error::InvalidValue(format!(
+                "GL_{} with a negative size",
+                pname
+            )));
+        }
+        let value = [x, y, width, height].map(|v| Value::Integer(v.into()));
+        self.set(pname, None, value.to_vec())
+    }
+
+    pub fn viewport(&mut self, x: i32, y: i32, width: i32, height: i32) -> Result<bool, Error> {
+        self.set_rectangle("VIEWPORT", x, y, width, height)
+    }
+
+    pub fn scissor(&mut self, x: i32, y: i32, width: i32, height: i32) -> Result<bool, Error> {
+        self.set_rectangle("SCISSOR_BOX", x, y, width, height)
+    }
+
+    pub fn clear_color(
+        &mut self,
+        red: f32,
+        green: f32,
+        blue: f32,
+        alpha: f32,
+    ) -> Result<bool, Error> {
+        let value = [red, green, blue, alpha].map(|v| Value::Float(v.into()));
+        self.set("COLOR_CLEAR_VALUE", None, value.to_vec())
+    }
+
+    pub fn clear_depth(&mut self, depth: f64) -> Result<bool, Error> {
+        self.set(
+            "DEPTH_CLEAR_VALUE",
+            None,
+            vec![Value::Float(depth.clamp(0.0, 1.0))],
+        )
+    }
+
+    pub fn clear_stencil(&mut self, stencil: i32) -> Result<bool, Error> {
+        self.set(
+            "STENCIL_CLEAR_VALUE",
+            None,
+            vec![Value::Integer(stencil.into())],
+        )
+    }
+
+    pub fn color_mask(
+        &mut self,
+        red: bool,
+        green: bool,
+        blue: bool,
+        alpha: bool,
+    ) -> Result<bool, Error> {
+        let value = [red, green, blue, alpha].map(Value::Boolean);
+        self.set("COLOR_WRITEMASK", None, value.to_vec())
+    }
+
+    pub fn depth_mask(&mut self, flag: bool) -> Result<bool, Error> {
+        self.set("DEPTH_WRITEMASK", None, vec![Value::Boolean(flag)])
+    }
+
+    pub fn depth_func(&mut self, func: &str) -> Result<bool, Error> {
+        self.set_enum("DEPTH_FUNC", func)
+    }
+
+    pub fn cull_face(&mut self, mode: &str) -> Result<bool, Error> {
+        self.set_enum("CULL_FACE_MODE", mode)
+    }
+
+    pub fn front_face(&mut self, mode: &str) -> Result<bool, Error> {
+        self.set_enum("FRONT_FACE", mode)
+    }
+
+    pub fn line_width(&mut self, width: f32) -> Result<bool, Error> {
+        if width <= 0.0 {
+            return Err(Error::InvalidValue(
+                "LineWidth that isn't positive".to_string(),
+            ));
+        }
+        self.set("LINE_WIDTH", None, vec![Value::Float(width.into())])
+    }
+
+    /// Select a texture unit, e.g. `TEXTURE1`.
+    pub fn active_texture(&mut self, texture: &str) -> Result<bool, Error> {
+        let texture = strip_gl(texture);
+        let units = TEXTURE_UNIT_LIMITS
+            .iter()
+            .filter_map(|limit| self.variables.get(*limit))
+            .filter_map(|limit| match limit.value[..] {
+                [Value::Integer(units)] => Some(units),
+                _ => None,
+            })
+            .max();
+        // Only plain digits, so the name is one GL actually has
+        match texture
+            .strip_prefix("TEXTURE")
+            .filter(|unit| !unit.is_empty() && unit.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|unit| unit.parse::<u32>().ok())
+        {
+            Some(unit) if units.is_none_or(|units| i64::from(unit) < units) => {
+                self.set_enum("ACTIVE_TEXTURE", texture)
+            }
+            _ => Err(Error::InvalidEnum(format!(
+                "{} isn't a texture unit",
+                texture
+            ))),
+        }
+    }
+
+    /// Bind a texture to a target like `TEXTURE_2D` of the active texture
+    /// unit.
+    pub fn bind_texture(&mut self, target: &str, texture: u32) -> Result<bool, Error> {
+        let binding = strip_gl(target).replacen("TEXTURE_", "TEXTURE_BINDING_", 1);
+        if !self.variables.contains_key(&binding) {
+            return Err(Error::InvalidEnum(format!(
+                "{} isn't a texture target",
+                target
+            )));
+        }
+        self.set(&binding, None, vec![Value::Integer(texture.into())])
+    }
+
+    /// Bind a buffer to a target like `ARRAY_BUFFER`.
+    pub fn bind_buffer(&mut self, target: &str, buffer: u32) -> Result<bool, Error> {
+        let binding = format!("{}_BINDING", strip_gl(target));
+        if !self.variables.contains_key(&binding) {
+            return Err(Error::InvalidEnum(format!(
+                "{} isn't a buffer target",
+                target
+            )));
+        }
+        self.set(&binding, None, vec![Value::Integer(buffer.into())])
+    }
+
+    /// Bind a framebuffer to `FRAMEBUFFER` (both the draw and read
+    /// framebuffers), `DRAW_FRAMEBUFFER` or `READ_FRAMEBUFFER`.
+    pub fn bind_framebuffer(&mut self, target: &str, framebuffer: u32) -> Result<bool, Error> {
+        let bindings: &[&str] = match strip_gl(target) {
+            "FRAMEBUFFER" => &["DRAW_FRAMEBUFFER_BINDING", "READ_FRAMEBUFFER_BINDING"],
+            "DRAW_FRAMEBUFFER" => &["DRAW_FRAMEBUFFER_BINDING"],
+            "READ_FRAMEBUFFER" => &["READ_FRAMEBUFFER_BINDING"],
+            _ => {
+                return Err(Error::InvalidEnum(format!(
+                    "{} isn't a framebuffer target",
+                    target
+                )))
+            }
+        };
+        let mut changed = false;
+        for binding in bindings {
+            changed |= self.set(binding, None, vec![Value::Integer(framebuffer.into())])?;
+        }
+        Ok(changed)
+    }
+
+    pub fn bind_vertex_array(&mut self, array: u32) -> Result<bool, Error> {
+        self.set(
+            "VERTEX_ARRAY_BINDING",
+            None,
+            vec![Value::Integer(array.into())],
+        )
+    }
+
+    pub fn use_program(&mut self, program: u32) -> Result<bool, Error> {
+        self.set(
+            "CURRENT_PROGRAM",
+            None,
+            vec![Value::Integer(program.into())],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_spec, read_spec};
+
+    fn with_context(spec: &str, profile: Option<Condition>, f: impl FnOnce(MockContext)) {
+        let source = read_spec(spec);
+        let (_, tables) = parse_spec(spec, &source);
+        f(MockContext::new(&tables, profile, 640, 480));
+    }
+
+    fn enum_(name: &str) -> Value {
+        Value::Enum(name.to_string())
+    }
+
+    #[test]
+    fn parse_initial_values() {
+        assert_eq!(
+            parse_initial("\\glc{FALSE}"),
+            Some(vec![Value::Boolean(false)])
+        );
+        assert_eq!(
+            parse_initial("{\\em True}"),
+            Some(vec![Value::Boolean(true)])
+        );
+        assert_eq!(parse_initial("$8$"), Some(vec![Value::Integer(8)]));
+        assert_eq!(parse_initial("$14*$"), Some(vec![Value::Integer(14)]));
+        assert_eq!(parse_initial("0's"), Some(vec![Value::Integer(0)]));
+        assert_eq!(
+            parse_initial("(0.0,0.0,-1.0)"),
+            Some(vec![
+                Value::Float(0.0),
+                Value::Float(0.0),
+                Value::Float(-1.0)
+            ])
+        );
+        assert_eq!(
+            parse_initial("\\tt MODELVIEW"),
+            Some(vec![enum_("MODELVIEW")])
+        );
+        assert_eq!(parse_initial("Identity").unwrap().len(), 16);
+        assert_eq!(parse_initial("See sec.~\\ref{view:port}"), None);
+        assert_eq!(parse_initial("$2^{27}$"), None);
+    }
+
+    #[test]
+    fn initial_state() {
+        with_context("es", None, |gl| {
+            assert_eq!(
+                gl.get("GetIntegerv", "GL_BLEND_SRC_RGB").unwrap(),
+                [enum_("ONE")]
+            );
+            assert_eq!(gl.is_enabled("DEPTH_TEST"), Ok(false));
+            assert_eq!(
+                gl.get("glGetIntegerv", "VIEWPORT").unwrap(),
+                [0, 0, 640, 480].map(Value::Integer)
+            );
+            assert_eq!(
+                gl.get("GetFloatv", "LINE_WIDTH").unwrap(),
+                [Value::Float(1.0)]
+            );
+        });
+    }
+
+    #[test]
+    fn conversions() {
+        with_context("es", None, |mut gl| {
+            gl.clear_color(1.0, 0.0, -1.0, 0.5).unwrap();
+            assert_eq!(
+                gl.get("GetIntegerv", "COLOR_CLEAR_VALUE").unwrap(),
+                [i32::MAX as i64, 0, -(i32::MAX as i64), 1073741824].map(Value::Integer)
+            );
+            assert_eq!(
+                gl.get("GetBooleanv", "COLOR_CLEAR_VALUE").unwrap(),
+                [true, false, true, true].map(Value::Boolean)
+            );
+            gl.line_width(2.6).unwrap();
+            assert_eq!(
+                gl.get("GetIntegerv", "LINE_WIDTH").unwrap(),
+                [Value::Integer(3)]
+            );
+            assert_eq!(
+                gl.get("GetFloatv", "DEPTH_WRITEMASK").unwrap(),
+                [Value::Float(1.0)]
+            );
+            assert_eq!(
+                gl.get("GetBooleanv", "CULL_FACE_MODE").unwrap(),
+                [Value::Boolean(true)]
+            );
+        });
+    }
+
+    #[test]
+    fn series_and_profiles() {
+        with_context("gl", Some(Condition::Compatibility), |mut gl| {
+            gl.enable("LIGHT3").unwrap();
+            assert_eq!(gl.is_enabled("LIGHT3"), Ok(true));
+            assert_eq!(gl.is_enabled("LIGHT2"), Ok(false));
+            assert_eq!(
+                gl.is_enabled("LIGHT8"),
+                Err(Error::UnknownName("LIGHT8".to_string()))
+            );
+        });
+        with_context("gl", Some(Condition::Core), |gl| {
+            assert_eq!(
+                gl.is_enabled("LIGHT0"),
+                Err(Error::UnknownName("LIGHT0".to_string()))
+            );
+        });
+    }
+
+    #[test]
+    fn indexed_state() {
+        with_context("gl", Some(Condition::Core), |mut gl| {
+            gl.enablei("BLEND", 2).unwrap();
+            assert_eq!(gl.is_enabledi("BLEND", 2), Ok(true));
+            assert_eq!(gl.is_enabled("BLEND"), Ok(false));
+            gl.enable("BLEND").unwrap();
+            assert_eq!(gl.is_enabledi("BLEND", 5), Ok(true));
+
+            gl.viewport(1, 2, 3, 4).unwrap();
+            assert_eq!(
+                gl.get_indexed("GetFloati_v", "VIEWPORT", 15).unwrap(),
+                [1.0, 2.0, 3.0, 4.0].map(Value::Float)
+            );
+            assert!(matches!(
+                gl.get_indexed("GetFloati_v", "VIEWPORT", 16),
+                Err(Error::InvalidValue(_))
+            ));
+            assert!(matches!(
+                gl.get_indexed("GetIntegeri_v", "DEPTH_FUNC", 0),
+                Err(Error::InvalidEnum(_))
+            ));
+        });
+    }
+
+    #[test]
+    fn texture_units() {
+        with_context("es", None, |mut gl| {
+            gl.active_texture("GL_TEXTURE1").unwrap();
+            gl.bind_texture("TEXTURE_2D", 5).unwrap();
+            assert_eq!(
+                gl.get("GetIntegerv", "TEXTURE_BINDING_2D").unwrap(),
+                [Value::Integer(5)]
+            );
+            gl.active_texture("TEXTURE0").unwrap();
+            assert_eq!(
+                gl.get("GetIntegerv", "TEXTURE_BINDING_2D").unwrap(),
+                [Value::Integer(0)]
+            );
+            assert!(gl.active_texture("TEXTURE1000").is_err());
+            assert!(gl.bind_texture("TEXTURE_4D", 1).is_err());
+            for texture in ["TEXTURE-1", "TEXTURE+1", "TEXTURE", "TEXTURE4294967296"] {
+                assert!(
+                    matches!(gl.active_texture(texture), Err(Error::InvalidEnum(_))),
+                    "{}",
+                    texture
+                );
+            }
+            assert_eq!(
+                gl.get("GetIntegerv", "ACTIVE_TEXTURE").unwrap(),
+                [Value::Enum("TEXTURE0".to_string())]
+            );
+            // A unit that doesn't parse is treated as unit 0 rather than
+            // panicking
+            gl.set(
+                "ACTIVE_TEXTURE",
+                None,
+                vec![Value::Enum("TEXTURE_2D".to_string())],
+            )
+            .unwrap();
+            gl.bind_texture("TEXTURE_2D", 3).unwrap();
+            gl.active_texture("TEXTURE0").unwrap();
+            assert_eq!(
+                gl.get("GetIntegerv", "TEXTURE_BINDING_2D").unwrap(),
+                [Value::Integer(3)]
+            );
+        });
+    }
+
+    #[test]
+    fn differences() {
+        with_context("es", None, |mut gl| {
+            let initial = gl.clone();
+            gl.enable("BLEND").unwrap();
+            gl.active_texture("TEXTURE2").unwrap();
+            gl.bind_texture("TEXTURE_2D", 7).unwrap();
+            gl.viewport(0, 0, 640, 480).unwrap();
+            assert_eq!(
+                gl.differences(&initial),
+                [
+                    ("ACTIVE_TEXTURE".to_string(), vec![enum_("TEXTURE2")]),
+                    ("BLEND".to_string(), vec![Value::Boolean(true)]),
+                    ("TEXTURE_BINDING_2D[2]".to_string(), vec![Value::Integer(7)]),
+                ]
+            );
+            assert_eq!(initial.differences(&initial), []);
+        });
+    }
+
+    #[test]
+    fn errors_and_unmodelled_state() {
+        with_context("es", None, |mut gl| {
+            assert!(matches!(
+                gl.is_enabled("VIEWPORT"),
+                Err(Error::InvalidEnum(_))
+            ));
+            assert!(matches!(
+                gl.enable("DEPTH_FUNC"),
+                Err(Error::InvalidEnum(_))
+            ));
+            assert!(matches!(
+                gl.get("GetIntegerv", "TEXTURE_MAG_FILTER"),
+                Err(Error::Unmodelled(_, _))
+            ));
+            assert!(gl
+                .unmodelled()
+                .iter()
+                .any(|(name, reason)| name == "TEXTURE_MAG_FILTER"
+                    && reason.contains("GetTexParameter")));
+        });
+    }
+
+    #[test]
+    fn setters_report_changes() {
+        with_context("es", None, |mut gl| {
+            assert_eq!(gl.enable("BLEND"), Ok(true));
+            assert_eq!(gl.enable("BLEND"), Ok(false));
+            assert_eq!(gl.enablei("BLEND", 2), Ok(false));
+            assert_eq!(gl.disablei("BLEND", 2), Ok(true));
+            // Every index, including the one that was set on its own
+            assert_eq!(gl.enable("BLEND"), Ok(true));
+            assert_eq!(gl.viewport(0, 0, 640, 480), Ok(false));
+            assert_eq!(gl.blend_func("ONE", "ZERO"), Ok(false));
+            assert_eq!(gl.blend_func("ONE", "ONE"), Ok(true));
+            assert_eq!(gl.bind_framebuffer("FRAMEBUFFER", 0), Ok(false));
+            assert_eq!(gl.bind_framebuffer("READ_FRAMEBUFFER", 1), Ok(true));
+        });
+    }
+
+    #[test]
+    fn misused_commands() {
+        with_context("es", None, |mut gl| {
+            assert_eq!(
+                gl.get("GetTexParameteriv", "TEXTURE_MAG_FILTER"),
+                Err(Error::InvalidCommand(
+                    "GetTexParameteriv isn't a modelled query".to_string()
+                ))
+            );
+            assert_eq!(
+                gl.get("GetIntegeri_v", "BLEND_SRC_RGB"),
+                Err(Error::InvalidCommand(
+                    "GetIntegeri_v needs an index".to_string()
+                ))
+            );
+            assert_eq!(
+                gl.get_indexed("GetIntegerv", "BLEND_SRC_RGB", 0),
+                Err(Error::InvalidCommand(
+                    "GetIntegerv doesn't take an index".to_string()
+                ))
+            );
+            assert_eq!(
+                gl.set("VIEWPORT", None, vec![Value::Integer(0)]),
+                Err(Error::Unmodelled(
+                    "VIEWPORT".to_string(),
+                    "it has 4 values, not 1".to_string()
+                ))
+            );
+        });
+    }
+}
@@ -0,0 +1,525 @@
+//! A self-contained Python package, `gl_state_tables`, with the tables as JSON
+//! and a loader that turns them into dataclasses mirroring [Table], [Entry],
+//! [Type](OpenGL_state_table_parser::types::Type) and [Quantity]:
+//!
+//! ```python
+//! import gl_state_tables as gst
+//! for spec, table, entry in gst.lookup("GL_LIGHT3"):
+//!     print(spec.name, entry.get_value, entry.series_names())
+//! core = [entry for _, _, entry in gst.entries("gl") if entry.available_in(gst.Condition.CORE)]
+//! ```
+//!
+//! The dataclasses' fields and the JSON's keys both come from the field lists
+//! here, so they can't drift apart. `VERSION` records which spec files the
+//! data came from.
+
+use crate::search_index::{json_option, json_string};
+use crate::SpecTables;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use OpenGL_state_table_parser::types::{
+    component_count, scalar_kind, type_code, type_quantity, type_to_latex, MaybeParsed, Quantity,
+};
+use OpenGL_state_table_parser::{read_spec, Condition, Entry, Table};
+
+/// A field of one of the dataclasses.
+struct Field {
+    name: &'static str,
+    /// The Python type annotation
+    annotation: &'static str,
+    /// A Python function to convert the JSON value, or empty if it's used as
+    /// it is
+    from_json: &'static str,
+}
+
+const fn field(name: &'static str, annotation: &'static str, from_json: &'static str) -> Field {
+    Field {
+        name,
+        annotation,
+        from_json,
+    }
+}
+
+/// The dataclasses, with their docstrings and fields, in dependency order.
+const CLASSES: [(&str, &str, &[Field]); 6] = [
+    (
+        "Quantity",
+        "A number of things: exactly one of an integer or the name of an implementation-dependent maximum, e.g. MAX_DRAW_BUFFERS.",
+        &[
+            field("integer", "Optional[int]", ""),
+            field("constant", "Optional[str]", ""),
+        ],
+    ),
+    (
+        "Term",
+        "One of the terms of \"a × b × … × type\". If it couldn't be parsed, quantity is None and unparsed is the LaTeX for it. minimum says whether it's a minimum (\"n*\").",
+        &[
+            field("quantity", "Optional[Quantity]", "_optional(Quantity.from_json)"),
+            field("minimum", "bool", ""),
+            field("unparsed", "Optional[str]", ""),
+        ],
+    ),
+    (
+        "Type",
+        "A type. latex is in the spec's notation, written canonically if the type could be parsed. The other fields are None (or empty) if it couldn't be.",
+        &[
+            field("latex", "str", ""),
+            field("parsed", "bool", ""),
+            field("type_code", "Optional[str]", ""),
+            field("scalar_kind", "Optional[str]", ""),
+            field("component_count", "Optional[int]", ""),
+            field("quantity", "List[Term]", "_list(Term.from_json)"),
+        ],
+    ),
+    (
+        "Entry",
+        "An entry in a state table. Text is as it was in the spec's LaTeX source, and footnotes are indices into the table's footnotes.",
+        &[
+            field("condition", "Optional[Condition]", "_optional(Condition)"),
+            field("get_value", "Optional[str]", ""),
+            field("get_value_footnotes", "List[int]", ""),
+            field("alt_get_value", "Optional[str]", ""),
+            field("series", "Optional[Quantity]", "_optional(Quantity.from_json)"),
+            field("type_", "Optional[Type]", "_optional(Type.from_json)"),
+            field("type_footnotes", "List[int]", ""),
+            field("get_cmnd", "Optional[str]", ""),
+            field("initial_value", "Optional[str]", ""),
+            field("initial_value_footnotes", "List[int]", ""),
+            field("description", "str", ""),
+            field("description_footnotes", "List[int]", ""),
+            field("attribute", "Optional[str]", ""),
+        ],
+    ),
+    (
+        "Table",
+        "A state table. If minimum_values is true, the entries' initial values are really minimum values.",
+        &[
+            field("label", "str", ""),
+            field("title", "str", ""),
+            field("caption", "Optional[str]", ""),
+            field("footnotes", "List[str]", ""),
+            field("minimum_values", "bool", ""),
+            field("entries", "List[Entry]", "_list(Entry.from_json)"),
+        ],
+    ),
+    (
+        "Spec",
+        "The state tables of one spec. suffix is es11, es or gl.",
+        &[
+            field("suffix", "str", ""),
+            field("name", "str", ""),
+            field("copyright", "str", ""),
+            field("tables", "List[Table]", "_list(Table.from_json)"),
+        ],
+    ),
+];
+
+/// The loader, before the dataclasses.
+const LOADER_START: &str = r#"from __future__ import annotations
+
+import enum
+import json
+import os
+from dataclasses import dataclass
+from typing import Callable, Iterator, List, Optional, Tuple, TypeVar
+
+T = TypeVar("T")
+
+
+def _optional(from_json: Callable[..., T]) -> Callable[..., Optional[T]]:
+    return lambda value: None if value is None else from_json(value)
+
+
+def _list(from_json: Callable[..., T]) -> Callable[..., List[T]]:
+    return lambda values: [from_json(value) for value in values]
+
+
+class Condition(enum.Enum):
+    """A profile that an entry is limited to."""
+
+    CORE = "core"
+    COMPATIBILITY = "compatibility"
+    IMAGING_SUBSET = "imaging subset"
+"#;
+
+/// Methods for the dataclasses, by class.
+const METHODS: [(&str, &str); 2] = [
+    (
+        "Quantity",
+        r#"
+    def __str__(self) -> str:
+        return str(self.integer) if self.integer is not None else str(self.constant)
+"#,
+    ),
+    (
+        "Entry",
+        r#"
+    def series_names(self, count: Optional[int] = None) -> List[str]:
+        """The get values of a series like LIGHT0, LIGHT1, ..., or just the get
+        value if this isn't a series. There are count of them, which is the
+        series' minimum by default; it must be given if the minimum is
+        implementation-dependent. The nth value is also the first's enum value
+        plus n."""
+        if self.series is None or self.get_value is None:
+            return [] if self.get_value is None else [self.get_value]
+        if count is None:
+            if self.series.integer is None:
+                raise ValueError(
+                    f"{self.get_value}'s series has at least {self.series} values, so give a count"
+                )
+            count = self.series.integer
+        prefix = self.get_value[:-1]
+        return [f"{prefix}{index}" for index in range(count)]
+
+    def available_in(self, profile: Condition) -> bool:
+        """Whether this is in the given OpenGL profile. The Imaging Subset
+        counts as part of the compatibility profile."""
+        if self.condition is None:
+            return True
+        if self.condition == Condition.IMAGING_SUBSET:
+            return profile != Condition.CORE
+        return self.condition == profile
+"#,
+    ),
+];
+
+const LOADER_END: &str = r#"
+
+_specs: Optional[List[Spec]] = None
+
+
+def load() -> List[Spec]:
+    """All the specs, loaded from data.json the first time."""
+    global _specs
+    if _specs is None:
+        with open(os.path.join(os.path.dirname(__file__), "data.json"), encoding="utf-8") as file:
+            _specs = [Spec.from_json(spec) for spec in json.load(file)]
+    return _specs
+
+
+def entries(spec: Optional[str] = None) -> Iterator[Tuple[Spec, Table, Entry]]:
+    """Every entry, or just those of the spec with the given suffix."""
+    for spec_ in load():
+        if spec is None or spec_.suffix == spec:
+            for table in spec_.tables:
+                for entry in table.entries:
+                    yield spec_, table, entry
+
+
+def with_condition(
+    condition: Optional[Condition], spec: Optional[str] = None
+) -> Iterator[Tuple[Spec, Table, Entry]]:
+    """The entries with exactly the given condition, which is None for those
+    that aren't conditional."""
+    return (item for item in entries(spec) if item[2].condition == condition)
+
+
+def lookup(name: str, spec: Optional[str] = None) -> List[Tuple[Spec, Table, Entry]]:
+    """The entries for a get value or alternative get value, including the
+    members of series (so LIGHT3 finds LIGHT0). The GL_ prefix is optional."""
+    if name.startswith("GL_"):
+        name = name[3:]
+    found = []
+    for item in entries(spec):
+        entry = item[2]
+        if name in (entry.get_value, entry.alt_get_value):
+            found.append(item)
+        elif entry.series is not None and entry.get_value is not None:
+            prefix = entry.get_value[:-1]
+            index = name[len(prefix):]
+            if name.startswith(prefix) and index.isdigit() and str(int(index)) == index:
+                if entry.series.integer is None or int(index) < entry.series.integer:
+                    found.append(item)
+    return found
+"#;
+
+/// A 64-bit FNV-1a hash, to identify the spec files without any dependencies.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn json_list(values: impl IntoIterator<Item = String>) -> String {
+    format!("[{}]", values.into_iter().collect::<Vec<_>>().join(","))
+}
+
+/// A JSON object for one of the [CLASSES], checking that the keys are its
+/// fields.
+fn json_object(class: &str, values: &[(&str, String)]) -> String {
+    let &(_, _, fields) = CLASSES.iter().find(|&&(name, _, _)| name == class).unwrap();
+    assert_eq!(
+        fields.iter().map(|field| field.name).collect::<Vec<_>>(),
+        values.iter().map(|&(name, _)| name).collect::<Vec<_>>(),
+        "JSON for {} doesn't match its fields",
+        class
+    );
+    let members: Vec<String> = values
+        .iter()
+        .map(|(name, value)| format!("{}:{}", json_string(name), value))
+        .collect();
+    format!("{{{}}}", members.join(","))
+}
+
+fn quantity_json(quantity: &Quantity) -> String {
+    let (integer, constant) = match quantity {
+        Quantity::Integer(n) => (n.to_string(), "null".to_string()),
+        Quantity::Constant(c) => ("null".to_string(), json_string(c)),
+    };
+    json_object("Quantity", &[("integer", integer), ("constant", constant)])
+}
+
+fn type_json(type_: &MaybeParsed<OpenGL_state_table_parser::types::Type>) -> String {
+    let null = || "null".to_string();
+    let values = match type_ {
+        MaybeParsed::Parsed(type_) => {
+            let terms = type_quantity(type_).iter().map(|term| {
+                let (quantity, minimum, unparsed) = match term {
+                    MaybeParsed::Parsed((quantity, minimum)) => {
+                        (quantity_json(quantity), minimum.to_string(), null())
+                    }
+                    MaybeParsed::Unparsed(term) => (null(), false.to_string(), json_string(term)),
+                };
+                json_object(
+                    "Term",
+                    &[
+                        ("quantity", quantity),
+                        ("minimum", minimum),
+                        ("unparsed", unparsed),
+                    ],
+                )
+            });
+            [
+                ("latex", json_string(&type_to_latex(type_))),
+                ("parsed", true.to_string()),
+                ("type_code", json_string(type_code(type_))),
+                ("scalar_kind", json_string(&scalar_kind(type_).to_string())),
+                (
+                    "component_count",
+                    component_count(type_).map_or_else(null, |count| count.to_string()),
+                ),
+                ("quantity", json_list(terms)),
+            ]
+        }
+        MaybeParsed::Unparsed(latex) => [
+            ("latex", json_string(latex)),
+            ("parsed", false.to_string()),
+            ("type_code", null()),
+            ("scalar_kind", null()),
+            ("component_count", null()),
+            ("quantity", "[]".to_string()),
+        ],
+    };
+    json_object("Type", &values)
+}
+
+fn footnotes_json(indices: &[usize]) -> String {
+    json_list(indices.iter().map(usize::to_string))
+}
+
+fn entry_json(entry: &Entry) -> String {
+    let condition = entry.condition.map(|condition| match condition {
+        Condition::Core => "core",
+        Condition::Compatibility => "compatibility",
+        Condition::ImagingSubset => "imaging subset",
+    });
+    json_object(
+        "Entry",
+        &[
+            ("condition", json_option(condition)),
+            ("get_value", json_option(entry.get_value.as_deref())),
+            (
+                "get_value_footnotes",
+                footnotes_json(&entry.get_value_footnotes),
+            ),
+            ("alt_get_value", json_option(entry.alt_get_value.as_deref())),
+            (
+                "series",
+                entry
+                    .series
+                    .as_ref()
+                    .map_or("null".to_string(), quantity_json),
+            ),
+            (
+                "type_",
+                entry.type_.as_ref().map_or("null".to_string(), type_json),
+            ),
+            ("type_footnotes", footnotes_json(&entry.type_footnotes)),
+            ("get_cmnd", json_option(entry.get_cmnd.as_deref())),
+            ("initial_value", json_option(entry.initial_value.as_deref())),
+            (
+                "initial_value_footnotes",
+                footnotes_json(&entry.initial_value_footnotes),
+            ),
+            ("description", json_string(&entry.description)),
+            (
+                "description_footnotes",
+                footnotes_json(&entry.description_footnotes),
+            ),
+            ("attribute", json_option(entry.attribute.as_deref())),
+        ],
+    )
+}
+
+fn table_json(table: &Table) -> String {
+    json_object(
+        "Table",
+        &[
+            ("label", json_string(&table.label)),
+            ("title", json_string(&table.title)),
+            ("caption", json_option(table.caption.as_deref())),
+            (
+                "footnotes",
+                json_list(table.footnotes.iter().map(|footnote| json_string(footnote))),
+            ),
+            ("minimum_values", table.minimum_values.to_string()),
+            ("entries", json_list(table.entries.iter().map(entry_json))),
+        ],
+    )
+}
+
+fn write_loader(out: &mut impl Write, specs: &[SpecTables]) -> io::Result<()> {
+    writeln!(
+        out,
+        "\"\"\"The state tables of the OpenGL and OpenGL ES specs.\n\nGenerated by OpenGL-state-table-parser. Don't edit it by hand.\n\"\"\""
+    )?;
+    writeln!(out)?;
+    write!(out, "{}", LOADER_START)?;
+    writeln!(out)?;
+    writeln!(out)?;
+    // The JSON string escapes are also valid in Python strings
+    writeln!(out, "# Which spec files the data was parsed from")?;
+    writeln!(out, "VERSION = {{")?;
+    writeln!(
+        out,
+        "    \"generator\": {},",
+        json_string(concat!(
+            env!("CARGO_PKG_NAME"),
+            " ",
+            env!("CARGO_PKG_VERSION")
+        ))
+    )?;
+    writeln!(out, "    \"specs\": [")?;
+    for &(suffix, name, _, _) in specs {
+        let source = read_spec(suffix);
+        writeln!(
+            out,
+            "        {{\"suffix\": {}, \"name\": {}, \"file\": {}, \"bytes\": {}, \"fnv1a64\": \"{:016x}\"}},",
+            json_string(suffix),
+            json_string(name),
+            json_string(&format!("tables_src/gettables.{}.tex", suffix)),
+            source.len(),
+            fnv1a(source.as_bytes())
+        )?;
+    }
+    writeln!(out, "    ],")?;
+    writeln!(out, "}}")?;
+
+    for (class, docstring, fields) in CLASSES {
+        writeln!(out)?;
+        writeln!(out)?;
+        writeln!(out, "@dataclass(frozen=True)")?;
+        writeln!(out, "class {}:", class)?;
+        writeln!(out, "    \"\"\"{}\"\"\"", docstring)?;
+        writeln!(out)?;
+        for field in fields {
+            writeln!(out, "    {}: {}", field.name, field.annotation)?;
+        }
+        writeln!(out)?;
+        writeln!(out, "    @classmethod")?;
+        writeln!(out, "    def from_json(cls, data: dict) -> {}:", class)?;
+        writeln!(out, "        return cls(")?;
+        for field in fields {
+            if field.from_json.is_empty() {
+                writeln!(out, "            {}=data[{:?}],", field.name, field.name)?;
+            } else {
+                writeln!(
+                    out,
+                    "            {}={}(data[{:?}]),",
+                    field.name, field.from_json, field.name
+                )?;
+            }
+        }
+        writeln!(out, "        )")?;
+        for &(_, methods) in METHODS.iter().filter(|&&(name, _)| name == class) {
+            write!(out, "{}", methods)?;
+        }
+    }
+    write!(out, "{}", LOADER_END)
+}
+
+/// Write the `gl_state_tables` package to `out_dir`.
+pub fn write_package(out_dir: &Path, specs: &[SpecTables]) -> io::Result<()> {
+    let package_dir = out_dir.join("gl_state_tables");
+    fs::create_dir_all(&package_dir)?;
+
+    let mut loader = BufWriter::new(File::create(package_dir.join("__init__.py"))?);
+    write_loader(&mut loader, specs)?;
+    loader.flush()?;
+
+    let mut data = BufWriter::new(File::create(package_dir.join("data.json"))?);
+    writeln!(data, "[")?;
+    for (index, &(suffix, name, copyright, tables)) in specs.iter().enumerate() {
+        let spec = json_object(
+            "Spec",
+            &[
+                ("suffix", json_string(suffix)),
+                ("name", json_string(name)),
+                ("copyright", json_string(copyright)),
+                ("tables", json_list(tables.iter().map(table_json))),
+            ],
+        );
+        let separator = if index + 1 == specs.len() { "" } else { "," };
+        writeln!(data, "{}{}", spec, separator)?;
+    }
+    writeln!(data, "]")?;
+    data.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write_package;
+    use std::process::Command;
+
+    #[test]
+    fn package() {
+        let out_dir = std::env::temp_dir().join(format!("gl-state-python-{}", std::process::id()));
+        crate::with_specs(|specs| write_package(&out_dir, specs)).unwrap();
+        let package_dir = out_dir.join("gl_state_tables");
+        let loader = std::fs::read_to_string(package_dir.join("__init__.py")).unwrap();
+        for class in ["Quantity", "Term", "Type", "Entry", "Table", "Spec"] {
+            assert!(loader.contains(&format!("@dataclass(frozen=True)\nclass {}:\n", class)));
+        }
+        assert!(loader.contains("\"file\": \"tables_src/gettables.es11.tex\""));
+        let data = std::fs::read_to_string(package_dir.join("data.json")).unwrap();
+        assert!(data.starts_with("[\n{\"suffix\":\"es11\",\"name\":"));
+        assert!(data.contains("\"get_value\":\"LIGHT0\""));
+
+        // Load the data with the loader, if there's a Python to do it with
+        let script = "import gl_state_tables as gst\n\
+                      found = gst.lookup('GL_LIGHT3', 'gl')\n\
+                      print([(spec.suffix, entry.get_value) for spec, _, entry in found])\n\
+                      print(found[0][2].series_names()[3])";
+        match Command::new("python3")
+            .args(["-c", script])
+            .current_dir(&out_dir)
+            .output()
+        {
+            Ok(output) => {
+                assert!(
+                    output.status.success(),
+                    "{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                assert_eq!(
+                    String::from_utf8(output.stdout).unwrap(),
+                    "[('gl', 'LIGHT0')]\nLIGHT3\n"
+                );
+            }
+            Err(err) => assert_eq!(err.kind(), std::io::ErrorKind::NotFound),
+        }
+
+        std::fs::remove_dir_all(&out_dir).unwrap();
+    }
+}
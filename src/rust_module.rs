@@ -0,0 +1,365 @@
+//! A Rust module with every state table entry as static data, so programs can
+//! look up state without parsing anything at runtime. It has no dependencies,
+//! so it can be generated once and checked in, or from a `build.rs` and
+//! `include!`d.
+//!
+//! Entries can be looked up by get value, and if a `gl.xml` from the OpenGL
+//! registry was given, by the numeric value of the enum too:
+//!
+//! ```ignore
+//! for info in gl_state::state_info(gl::TEXTURE_MAG_FILTER) {
+//!     println!("{:?}: {:?}", info.spec, info.initial_value);
+//! }
+//! ```
+
+use crate::SpecTables;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use OpenGL_state_table_parser::types::{type_to_rust, MaybeParsed, Quantity};
+use OpenGL_state_table_parser::Condition;
+
+/// The types used by the generated data. These mirror the library's, but with
+/// public fields and static slices instead of vectors.
+const DEFINITIONS: &str = r#"// Generated by OpenGL-state-table-parser from the state tables of the OpenGL
+// and OpenGL ES specs. Don't edit it by hand.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Spec {
+    /// OpenGL ES 1.1
+    Es11,
+    /// OpenGL ES 3.2
+    Es,
+    /// OpenGL 4.6
+    Gl,
+}
+
+/// A profile that an entry is limited to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Condition {
+    Core,
+    Compatibility,
+    ImagingSubset,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantity {
+    Integer(u32),
+    /// An implementation-dependent maximum, e.g. `MAX_DRAW_BUFFERS`.
+    Constant(&'static str),
+}
+
+/// One of the terms of "a × b × … × type". The boolean says whether the
+/// quantity is a minimum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Term {
+    Parsed(Quantity, bool),
+    Unparsed(&'static str),
+}
+
+/// The type codes from the specs' tables of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BasicType {
+    Boolean,
+    Bmu,
+    Color,
+    Enum,
+    ColorIndex,
+    TexCoords,
+    NormalCoords,
+    Vertex,
+    Integer,
+    NonNegativeInteger,
+    KValuedInteger { k: Quantity, minimum: bool },
+    Float,
+    NonNegativeFloat,
+    ZeroOneRangeFloat,
+    FloatTuple { k: u32 },
+    KValuedFloat { k: u32 },
+    Position,
+    Direction,
+    Matrix,
+    String,
+    Image,
+    AttributeStackEntry,
+    Pointer,
+    Char,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Type {
+    pub basic_type: BasicType,
+    /// If this is empty, there's just one.
+    pub quantity: &'static [Term],
+}
+
+/// A type, or the spec's LaTeX for it if it couldn't be parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaybeParsedType {
+    Parsed(Type),
+    Unparsed(&'static str),
+}
+
+/// One entry in a state table. Text is as it was in the spec's LaTeX source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateInfo {
+    pub spec: Spec,
+    /// The title of the table it's in.
+    pub table: &'static str,
+    /// Without the `GL_` prefix, e.g. `"TEXTURE_MAG_FILTER"`.
+    pub get_value: &'static str,
+    pub alt_get_value: Option<&'static str>,
+    /// The numeric value of the get value's enum, if it's known.
+    pub enum_value: Option<u32>,
+    /// For a series like `LIGHT0`, `LIGHT1`, …: the minimum number of values.
+    pub series_minimum: Option<Quantity>,
+    pub type_: Option<MaybeParsedType>,
+    pub get_cmnd: Option<&'static str>,
+    /// The minimum value instead, for implementation-dependent limits.
+    pub initial_value: Option<&'static str>,
+    pub description: &'static str,
+    pub attribute: Option<&'static str>,
+    pub condition: Option<Condition>,
+}
+"#;
+
+const LOOKUP_BY_NAME: &str = r#"
+/// Every entry for a get value or alternative get value, in any spec. The
+/// `GL_` prefix is optional.
+pub fn by_name(name: &str) -> impl Iterator<Item = &'static StateInfo> + '_ {
+    let name = name.strip_prefix("GL_").unwrap_or(name);
+    let start = BY_NAME.partition_point(|&(other, _)| other < name);
+    BY_NAME[start..]
+        .iter()
+        .take_while(move |&&(other, _)| other == name)
+        .map(|&(_, index)| &STATE[index])
+}
+"#;
+
+const LOOKUP_BY_ENUM: &str = r#"
+/// Every entry for an enum value, like `GL_TEXTURE_MAG_FILTER`, in any spec.
+pub fn state_info(pname: u32) -> impl Iterator<Item = &'static StateInfo> {
+    let start = BY_ENUM.partition_point(|&(other, _)| other < pname);
+    BY_ENUM[start..]
+        .iter()
+        .take_while(move |&&(other, _)| other == pname)
+        .map(|&(_, index)| &STATE[index])
+}
+"#;
+
+/// The value of an attribute of an XML element, e.g. `name` in
+/// ` name="GL_FOG" value="0x0B60"`.
+fn xml_attribute<'a>(attributes: &'a str, name: &str) -> Option<&'a str> {
+    let start = attributes.find(&format!(" {}=\"", name))? + name.len() + 3;
+    let length = attributes[start..].find('"')?;
+    Some(&attributes[start..start + length])
+}
+
+/// Find the enum values in the OpenGL registry's `gl.xml`, by name without
+/// the `GL_` prefix. This just scans for `<enum>` elements rather than
+/// properly parsing the XML, which is enough for the registry. If a name has
+/// more than one value (e.g. for different APIs), the first is used.
+pub fn parse_enums(xml: &str) -> HashMap<String, u32> {
+    let mut enums = HashMap::new();
+    // Keeping the space before the attributes makes them easier to find
+    for element in xml.split("<enum").skip(1) {
+        let attributes = &element[..element.find('>').unwrap_or(element.len())];
+        let (Some(name), Some(value)) = (
+            xml_attribute(attributes, "name"),
+            xml_attribute(attributes, "value"),
+        ) else {
+            continue;
+        };
+        let Some(name) = name.strip_prefix("GL_") else {
+            continue;
+        };
+        let value = match value.strip_prefix("0x") {
+            Some(hex) => u32::from_str_radix(hex, 16),
+            None => value.parse(),
+        };
+        // 64-bit values like TIMEOUT_IGNORED aren't state
+        let Ok(value) = value else {
+            continue;
+        };
+        enums.entry(name.to_string()).or_insert(value);
+    }
+    enums
+}
+
+fn option(value: Option<String>) -> String {
+    match value {
+        Some(value) => format!("Some({})", value),
+        None => "None".to_string(),
+    }
+}
+
+/// Write the module. `enums` are the values from [parse_enums], if there are
+/// any; without them there's no lookup by enum value.
+pub fn write_module(
+    out: &mut impl Write,
+    specs: &[SpecTables],
+    enums: Option<&HashMap<String, u32>>,
+) -> io::Result<()> {
+    write!(out, "{}", DEFINITIONS)?;
+
+    let mut by_name: Vec<(&str, usize)> = Vec::new();
+    let mut by_enum: Vec<(u32, usize)> = Vec::new();
+    let mut index = 0;
+    writeln!(out)?;
+    writeln!(out, "pub static STATE: &[StateInfo] = &[")?;
+    for &(suffix, _, _, tables) in specs {
+        let spec = match suffix {
+            "es11" => "Spec::Es11",
+            "es" => "Spec::Es",
+            "gl" => "Spec::Gl",
+            _ => panic!("Unknown spec {}", suffix),
+        };
+        for table in tables {
+            for entry in &table.entries {
+                let Some(get_value) = entry.get_value.as_deref() else {
+                    continue;
+                };
+                let enum_value = |name: &str| enums.and_then(|enums| enums.get(name).copied());
+                for name in [Some(get_value), entry.alt_get_value.as_deref()]
+                    .into_iter()
+                    .flatten()
+                {
+                    by_name.push((name, index));
+                    if let Some(value) = enum_value(name) {
+                        by_enum.push((value, index));
+                    }
+                }
+
+                let string = |value: &str| format!("{:?}", value);
+                let type_ = entry.type_.as_ref().map(|type_| match type_ {
+                    MaybeParsed::Parsed(type_) => {
+                        format!("MaybeParsedType::Parsed({})", type_to_rust(type_))
+                    }
+                    MaybeParsed::Unparsed(type_) => {
+                        format!("MaybeParsedType::Unparsed({:?})", type_)
+                    }
+                });
+                let series_minimum = entry.series.as_ref().map(|minimum| match minimum {
+                    Quantity::Integer(n) => format!("Quantity::Integer({})", n),
+                    Quantity::Constant(c) => format!("Quantity::Constant({:?})", c),
+                });
+                let condition = entry.condition.map(|condition| {
+                    match condition {
+                        Condition::Core => "Condition::Core",
+                        Condition::Compatibility => "Condition::Compatibility",
+                        Condition::ImagingSubset => "Condition::ImagingSubset",
+                    }
+                    .to_string()
+                });
+                writeln!(out, "    StateInfo {{")?;
+                writeln!(out, "        spec: {},", spec)?;
+                writeln!(out, "        table: {},", string(&table.title))?;
+                writeln!(out, "        get_value: {},", string(get_value))?;
+                writeln!(
+                    out,
+                    "        alt_get_value: {},",
+                    option(entry.alt_get_value.as_deref().map(string))
+                )?;
+                writeln!(
+                    out,
+                    "        enum_value: {},",
+                    option(enum_value(get_value).map(|value| format!("{:#06x}", value)))
+                )?;
+                writeln!(out, "        series_minimum: {},", option(series_minimum))?;
+                writeln!(out, "        type_: {},", option(type_))?;
+                writeln!(
+                    out,
+                    "        get_cmnd: {},",
+                    option(entry.get_cmnd.as_deref().map(string))
+                )?;
+                writeln!(
+                    out,
+                    "        initial_value: {},",
+                    option(entry.initial_value.as_deref().map(string))
+                )?;
+                writeln!(out, "        description: {},", string(&entry.description))?;
+                writeln!(
+                    out,
+                    "        attribute: {},",
+                    option(entry.attribute.as_deref().map(string))
+                )?;
+                writeln!(out, "        condition: {},", option(condition))?;
+                writeln!(out, "    }},")?;
+                index += 1;
+            }
+        }
+    }
+    writeln!(out, "];")?;
+
+    // Sorting is stable, so entries for the same name stay in spec order
+    by_name.sort_by_key(|&(name, _)| name);
+    writeln!(out)?;
+    writeln!(
+        out,
+        "/// Indices into [STATE] by get value and alternative get value, sorted."
+    )?;
+    writeln!(out, "pub static BY_NAME: &[(&str, usize)] = &[")?;
+    for (name, index) in by_name {
+        writeln!(out, "    ({:?}, {}),", name, index)?;
+    }
+    writeln!(out, "];")?;
+    write!(out, "{}", LOOKUP_BY_NAME)?;
+
+    if enums.is_some() {
+        by_enum.sort_by_key(|&(value, _)| value);
+        writeln!(out)?;
+        writeln!(out, "/// Indices into [STATE] by enum value, sorted.")?;
+        writeln!(out, "pub static BY_ENUM: &[(u32, usize)] = &[")?;
+        for (value, index) in by_enum {
+            writeln!(out, "    ({:#06x}, {}),", value, index)?;
+        }
+        writeln!(out, "];")?;
+        write!(out, "{}", LOOKUP_BY_ENUM)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_enums, write_module};
+
+    const GL_XML: &str = r#"<registry><enums namespace="GL">
+        <enum value="0x0BE2" name="GL_BLEND"/>
+        <enum value="0x2800" name="GL_TEXTURE_MAG_FILTER"/>
+        <enum value="0xFFFFFFFFFFFFFFFF" name="GL_TIMEOUT_IGNORED"/>
+        <enum value="1" name="GL_TRUE" group="Boolean"/>
+        <enum value="0x0BE2" name="GL_BLEND" api="gles2"/>
+        <enum value="2" name="EGL_NOT_GL"/>
+    </enums></registry>"#;
+
+    #[test]
+    fn enums() {
+        let enums = parse_enums(GL_XML);
+        assert_eq!(enums.get("BLEND"), Some(&0x0be2));
+        assert_eq!(enums.get("TEXTURE_MAG_FILTER"), Some(&0x2800));
+        assert_eq!(enums.get("TRUE"), Some(&1));
+        assert_eq!(enums.len(), 3);
+    }
+
+    #[test]
+    fn module_is_valid_rust() {
+        let enums = parse_enums(GL_XML);
+        let mut out = Vec::new();
+        crate::with_specs(|specs| write_module(&mut out, specs, Some(&enums))).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let file = syn::parse_file(&out).unwrap();
+        assert!(!file.items.is_empty());
+        assert!(out.contains("        get_value: \"BLEND\",\n        alt_get_value: None,\n        enum_value: Some(0x0be2),\n"));
+        assert!(out.contains("pub static BY_ENUM: &[(u32, usize)] = &[\n    (0x0be2, "));
+
+        // BY_NAME has to be sorted for the binary search
+        let by_name = out.split("pub static BY_NAME").nth(1).unwrap();
+        let names: Vec<&str> = by_name
+            .lines()
+            .skip(1)
+            .take_while(|line| *line != "];")
+            .map(|line| line.split('"').nth(1).unwrap())
+            .collect();
+        assert!(names.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(names.contains(&"TRANSPOSE_MODELVIEW_MATRIX"));
+    }
+}
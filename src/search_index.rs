@@ -0,0 +1,175 @@
+//! A compact index of every entry row on the page, which the page's script
+//! uses for full-text search and filtering by spec, type code, get command and
+//! attribute group.
+//!
+//! It's embedded as JSON of this shape:
+//!
+//! ```json
+//! {
+//!     "specs": ["es11", ...],
+//!     "types": ["", "B", "E", ...],
+//!     "commands": ["", "GetBooleanv", ...],
+//!     "attributes": ["", "current", ...],
+//!     "rows": [[spec, type, command, attribute, "searchable text", ["get value", ...]], ...]
+//! }
+//! ```
+//!
+//! where the first four elements of each row index into the lists above (the
+//! empty string means the field is absent), the last is the entry's get value
+//! and alternative get value, if it has them, for the get value filter, and the
+//! rows are in the same order as the entry rows in the page's tables.
+
+use std::fmt::Write;
+use OpenGL_state_table_parser::types::{type_code, MaybeParsed};
+use OpenGL_state_table_parser::Table;
+
+/// A list of distinct values, which rows refer to by index.
+struct Facet(Vec<String>);
+
+impl Facet {
+    fn new() -> Facet {
+        Facet(vec![String::new()])
+    }
+
+    fn index_of(&mut self, value: Option<&str>) -> usize {
+        let value = value.unwrap_or("");
+        if let Some(index) = self.0.iter().position(|existing| existing == value) {
+            index
+        } else {
+            self.0.push(value.to_string());
+            self.0.len() - 1
+        }
+    }
+}
+
+pub struct SearchIndex {
+    specs: Facet,
+    types: Facet,
+    commands: Facet,
+    attributes: Facet,
+    rows: Vec<[usize; 4]>,
+    texts: Vec<String>,
+    get_values: Vec<Vec<String>>,
+}
+
+impl SearchIndex {
+    pub fn new() -> SearchIndex {
+        SearchIndex {
+            specs: Facet(Vec::new()),
+            types: Facet::new(),
+            commands: Facet::new(),
+            attributes: Facet::new(),
+            rows: Vec::new(),
+            texts: Vec::new(),
+            get_values: Vec::new(),
+        }
+    }
+
+    /// Add the entries of a table. This must be called for each table in the
+    /// same order they are printed in.
+    pub fn add_table(&mut self, spec: &str, table: &Table) {
+        let spec = self.specs.index_of(Some(spec));
+        for entry in &table.entries {
+            let type_code = match entry.type_ {
+                Some(MaybeParsed::Parsed(ref type_)) => Some(type_code(type_)),
+                _ => None,
+            };
+            self.rows.push([
+                spec,
+                self.types.index_of(type_code),
+                self.commands.index_of(entry.get_cmnd.as_deref()),
+                self.attributes.index_of(entry.attribute.as_deref()),
+            ]);
+
+            let mut text = String::new();
+            for field in [
+                entry.get_value.as_deref(),
+                entry.alt_get_value.as_deref(),
+                entry.initial_value.as_deref(),
+                Some(&*entry.description),
+            ]
+            .into_iter()
+            .flatten()
+            {
+                text.push_str(field);
+                text.push('\n');
+            }
+            self.texts.push(text);
+            self.get_values.push(
+                [&entry.get_value, &entry.alt_get_value]
+                    .into_iter()
+                    .flatten()
+                    .map(|name| name.to_string())
+                    .collect(),
+            );
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{");
+        for (name, facet) in [
+            ("specs", &self.specs),
+            ("types", &self.types),
+            ("commands", &self.commands),
+            ("attributes", &self.attributes),
+        ] {
+            write!(json, "\"{}\":[", name).unwrap();
+            for (index, value) in facet.0.iter().enumerate() {
+                if index != 0 {
+                    json.push(',');
+                }
+                json.push_str(&json_string(value));
+            }
+            json.push_str("],\n");
+        }
+        json.push_str("\"rows\":[");
+        let rows = self.rows.iter().zip(&self.texts).zip(&self.get_values);
+        for (index, ((row, text), get_values)) in rows.enumerate() {
+            if index != 0 {
+                json.push_str(",\n");
+            }
+            write!(
+                json,
+                "[{},{},{},{},{},[",
+                row[0],
+                row[1],
+                row[2],
+                row[3],
+                json_string(text)
+            )
+            .unwrap();
+            for (index, get_value) in get_values.iter().enumerate() {
+                if index != 0 {
+                    json.push(',');
+                }
+                json.push_str(&json_string(get_value));
+            }
+            json.push_str("]]");
+        }
+        json.push_str("]}");
+        json
+    }
+}
+
+/// Quote a string for JSON. `<` is escaped too so the JSON can't end the
+/// `<script>` element it's embedded in.
+pub fn json_string(value: &str) -> String {
+    let mut json = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '<' => json.push_str("\\u003c"),
+            c if c.is_control() => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Like [json_string], but `null` for [None].
+pub fn json_option(value: Option<&str>) -> String {
+    value.map_or("null".to_string(), json_string)
+}
@@ -0,0 +1,448 @@
+//! Multi-page static site output. This writes an index page, one page per
+//! spec, one page per state table, and one page per state variable showing it
+//! across all the specs. Every page has the same navigation sidebar, and all
+//! links are relative so the site can be opened straight from the filesystem.
+
+use crate::html::{self, EntryAnchor, EntryIndex, EntryLinks, Equivalent, DISCLAIMER, LICENSE};
+use crate::matrix::Matrix;
+use crate::SPECS;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use OpenGL_state_table_parser::mathml::{escape_html, text_to_html, Macros};
+use OpenGL_state_table_parser::{parse_spec, read_spec, Table};
+
+struct Spec<'src> {
+    suffix: &'static str,
+    name: &'static str,
+    copyright: String,
+    tables: Vec<Table<'src>>,
+    anchors: Vec<Vec<EntryAnchor>>,
+    macros: Macros<'src>,
+}
+
+/// Turn a table label or get value into something safe to use as a file name.
+/// The only unusual characters in practice are the `:` in table labels and
+/// some spaces and brackets in ES 1.1 get values.
+pub fn file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+fn table_page(spec: &Spec, table: &Table) -> String {
+    format!("{}/{}.html", spec.suffix, file_name(&table.label))
+}
+
+fn variable_page(get_value: &str) -> String {
+    format!("variables/{}.html", file_name(get_value))
+}
+
+/// The links to an entry's equivalents in other specs, from a page at `root`.
+fn equivalents_for<'a>(
+    specs: &'a [Spec],
+    equivalents: &HashMap<EntryIndex, Vec<EntryIndex>>,
+    index: EntryIndex,
+    root: &str,
+) -> Vec<Equivalent<'a>> {
+    equivalents
+        .get(&index)
+        .into_iter()
+        .flatten()
+        .map(|&(spec_index, table_index, entry_index)| {
+            let spec = &specs[spec_index];
+            let table = &spec.tables[table_index];
+            let (ref anchor, _) = spec.anchors[table_index][entry_index].as_ref().unwrap();
+            Equivalent {
+                spec_name: spec.name,
+                table_title: &table.title,
+                href: format!("{}{}#{}", root, table_page(spec, table), anchor),
+                entry: &table.entries[entry_index],
+            }
+        })
+        .collect()
+}
+
+/// Write a page of the site, with the navigation sidebar. `root` is the
+/// relative path from the page to the root of the site, and the tables of
+/// `current_spec` are listed in the sidebar.
+fn write_page(
+    out_dir: &Path,
+    path: &str,
+    title: &str,
+    specs: &[Spec],
+    current_spec: Option<&str>,
+    body: impl FnOnce(&mut BufWriter<File>, &str) -> io::Result<()>,
+) -> io::Result<()> {
+    let root = "../".repeat(path.matches('/').count());
+    let mut out = BufWriter::new(File::create(out_dir.join(path))?);
+
+    writeln!(out, "<!doctype html>")?;
+    writeln!(out, "<meta charset=utf-8>")?;
+    writeln!(out, "<title>{}</title>", escape_html(title))?;
+    writeln!(out, "<link rel=stylesheet href=\"{}style.css\">", root)?;
+
+    writeln!(out, "<nav>")?;
+    writeln!(
+        out,
+        "<p><a href=\"{}index.html\">OpenGL and OpenGL ES state tables</a></p>",
+        root
+    )?;
+    for spec in specs {
+        let open = if current_spec == Some(spec.suffix) {
+            " open"
+        } else {
+            ""
+        };
+        writeln!(out, "<details{}>", open)?;
+        writeln!(
+            out,
+            "<summary><a href=\"{}{}.html\">{}</a></summary>",
+            root, spec.suffix, spec.name
+        )?;
+        writeln!(out, "<ul>")?;
+        for table in &spec.tables {
+            writeln!(
+                out,
+                "<li><a href=\"{}{}\">{}</a>",
+                root,
+                table_page(spec, table),
+                escape_html(&table.title)
+            )?;
+        }
+        writeln!(out, "</ul>")?;
+        writeln!(out, "</details>")?;
+    }
+    writeln!(out, "<ul class=legend>")?;
+    writeln!(out, "<li class=no-condition>Core and Compatibility")?;
+    writeln!(out, "<li class=compatibility-only>Compatibility only")?;
+    writeln!(out, "<li class=core-only>Core only")?;
+    writeln!(
+        out,
+        "<li class=imaging-subset>Imaging Subset (compatibility only)"
+    )?;
+    writeln!(out, "</ul>")?;
+    writeln!(out, "</nav>")?;
+
+    writeln!(out, "<main>")?;
+    body(&mut out, &root)?;
+    writeln!(out, "<hr>")?;
+    writeln!(out, "{}", DISCLAIMER)?;
+    writeln!(
+        out,
+        "<p>See also the <a href=\"{}index.html#acknowledgments\">specification acknowledgments</a>.</p>",
+        root
+    )?;
+    writeln!(out, "{}", LICENSE)?;
+    writeln!(out, "</main>")?;
+    out.flush()
+}
+
+pub fn write_site(out_dir: &Path) -> io::Result<()> {
+    let sources: Vec<String> = SPECS.iter().map(|(suffix, _)| read_spec(suffix)).collect();
+    let specs: Vec<Spec> = SPECS
+        .iter()
+        .zip(&sources)
+        .map(|(&(suffix, name), source)| {
+            let (copyright, tables) = parse_spec(suffix, source);
+            let anchors = html::entry_anchors(suffix, &tables);
+            Spec {
+                suffix,
+                name,
+                copyright,
+                tables,
+                anchors,
+                macros: Macros::from_source(source),
+            }
+        })
+        .collect();
+    let equivalents = html::equivalent_entries(
+        &specs
+            .iter()
+            .map(|spec| &spec.tables[..])
+            .collect::<Vec<_>>(),
+    );
+
+    // Every (spec, table, entry) for each get value
+    let mut variables: BTreeMap<&str, Vec<EntryIndex>> = BTreeMap::new();
+    // Alternative names, and the get value they are an alternative for
+    let mut alt_names: BTreeMap<&str, &str> = BTreeMap::new();
+    for (spec_index, spec) in specs.iter().enumerate() {
+        for (table_index, table) in spec.tables.iter().enumerate() {
+            for (entry_index, entry) in table.entries.iter().enumerate() {
+                let Some(ref get_value) = entry.get_value else {
+                    continue;
+                };
+                variables.entry(get_value).or_default().push((
+                    spec_index,
+                    table_index,
+                    entry_index,
+                ));
+                if let Some(ref alt_get_value) = entry.alt_get_value {
+                    alt_names.insert(alt_get_value, get_value);
+                }
+            }
+        }
+    }
+
+    fs::create_dir_all(out_dir.join("variables"))?;
+    for spec in &specs {
+        fs::create_dir_all(out_dir.join(spec.suffix))?;
+    }
+    fs::write(
+        out_dir.join("style.css"),
+        include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/site.css")),
+    )?;
+
+    write_page(
+        out_dir,
+        "index.html",
+        "OpenGL and OpenGL ES state tables",
+        &specs,
+        None,
+        |out, root| {
+            writeln!(out, "<h1>OpenGL and OpenGL ES state tables <a href=\"https://github.com/hikari-no-yume/OpenGL-state-table-parser\">What's this?</a></h1>")?;
+            writeln!(out, "<ul>")?;
+            for spec in &specs {
+                writeln!(
+                    out,
+                    "<li><a href=\"{}{}.html\">{} state tables</a> ({} tables)",
+                    root,
+                    spec.suffix,
+                    spec.name,
+                    spec.tables.len()
+                )?;
+            }
+            writeln!(out, "</ul>")?;
+            writeln!(
+                out,
+                "<p>See also the <a href=\"{}matrix.html\">availability of every state variable</a> across the specs and profiles.</p>",
+                root
+            )?;
+            writeln!(out, "<h2>State variables</h2>")?;
+            writeln!(out, "<ul class=variables>")?;
+            for get_value in variables.keys() {
+                writeln!(
+                    out,
+                    "<li><a href=\"{}{}\"><code>{}</code></a>",
+                    root,
+                    variable_page(get_value),
+                    escape_html(get_value)
+                )?;
+            }
+            for (alt_get_value, get_value) in &alt_names {
+                writeln!(
+                    out,
+                    "<li><a href=\"{}{}\"><code>{}</code></a> (see <code>{}</code>)",
+                    root,
+                    variable_page(get_value),
+                    escape_html(alt_get_value),
+                    escape_html(get_value)
+                )?;
+            }
+            writeln!(out, "</ul>")?;
+            writeln!(
+                out,
+                "<h2 id=acknowledgments>Specification acknowledgments</h2>"
+            )?;
+            for spec in &specs {
+                writeln!(
+                    out,
+                    "{} specification acknowledgments:<br><pre>{}</pre>",
+                    spec.name,
+                    escape_html(&spec.copyright)
+                )?;
+            }
+            Ok(())
+        },
+    )?;
+
+    let matrix = Matrix::new(
+        &specs
+            .iter()
+            .map(|spec| {
+                (
+                    spec.suffix,
+                    spec.name,
+                    &spec.copyright[..],
+                    &spec.tables[..],
+                )
+            })
+            .collect::<Vec<_>>(),
+    );
+    write_page(
+        out_dir,
+        "matrix.html",
+        "State variable availability",
+        &specs,
+        None,
+        |out, root| {
+            writeln!(out, "<h1>State variable availability</h1>")?;
+            matrix.write_html_table(out, |get_value| {
+                Some(format!("{}{}", root, variable_page(get_value)))
+            })
+        },
+    )?;
+
+    for (spec_index, spec) in specs.iter().enumerate() {
+        write_page(
+            out_dir,
+            &format!("{}.html", spec.suffix),
+            &format!("{} state tables", spec.name),
+            &specs,
+            Some(spec.suffix),
+            |out, root| {
+                writeln!(out, "<h1>{} state tables</h1>", spec.name)?;
+                writeln!(out, "<ul>")?;
+                for table in &spec.tables {
+                    write!(
+                        out,
+                        "<li><a href=\"{}{}\">{}</a>",
+                        root,
+                        table_page(spec, table),
+                        escape_html(&table.title)
+                    )?;
+                    if let Some(ref caption) = table.caption {
+                        write!(out, " {}", text_to_html(caption, &spec.macros))?;
+                    }
+                    writeln!(out)?;
+                }
+                writeln!(out, "</ul>")?;
+                writeln!(
+                    out,
+                    "{} specification acknowledgments:<br><pre>{}</pre>",
+                    spec.name,
+                    escape_html(&spec.copyright)
+                )
+            },
+        )?;
+
+        for (table_index, (table, anchors)) in spec.tables.iter().zip(&spec.anchors).enumerate() {
+            write_page(
+                out_dir,
+                &table_page(spec, table),
+                &format!("{}: {}", spec.name, table.title),
+                &specs,
+                Some(spec.suffix),
+                |out, root| {
+                    writeln!(out, "<h1>{}: {}</h1>", spec.name, escape_html(&table.title))?;
+                    let label = html::table_anchor(spec.suffix, table);
+                    writeln!(out, "<table>")?;
+                    html::write_table_columns(out)?;
+                    writeln!(out, "<tbody>")?;
+                    html::write_table_header(out, table, &label, &spec.macros)?;
+                    for (entry_index, (entry, anchor)) in
+                        table.entries.iter().zip(anchors).enumerate()
+                    {
+                        let links = EntryLinks {
+                            spec_prefix: spec.suffix,
+                            table_page: "",
+                            anchor,
+                            variable_page: entry
+                                .get_value
+                                .as_deref()
+                                .map(|get_value| format!("{}{}", root, variable_page(get_value))),
+                            equivalents: equivalents_for(
+                                &specs,
+                                &equivalents,
+                                (spec_index, table_index, entry_index),
+                                root,
+                            ),
+                        };
+                        html::write_entry(out, entry, &label, &links, &spec.macros)?;
+                    }
+                    writeln!(out, "</tbody>")?;
+                    writeln!(out, "</table>")
+                },
+            )?;
+        }
+    }
+
+    for (get_value, occurrences) in &variables {
+        write_page(
+            out_dir,
+            &variable_page(get_value),
+            get_value,
+            &specs,
+            None,
+            |out, root| {
+                writeln!(out, "<h1><code>{}</code></h1>", escape_html(get_value))?;
+                // Entries are grouped by table, and the occurrences are already
+                // in table order.
+                let mut current_table = None;
+                for &(spec_index, table_index, entry_index) in occurrences {
+                    let spec = &specs[spec_index];
+                    let table = &spec.tables[table_index];
+                    let table_page = format!("{}{}", root, table_page(spec, table));
+                    if current_table != Some((spec_index, table_index)) {
+                        if current_table.is_some() {
+                            writeln!(out, "</tbody>")?;
+                            writeln!(out, "</table>")?;
+                        }
+                        current_table = Some((spec_index, table_index));
+                        writeln!(
+                            out,
+                            "<h2>{}: <a href=\"{}\">{}</a></h2>",
+                            spec.name,
+                            table_page,
+                            escape_html(&table.title)
+                        )?;
+                        writeln!(out, "<table>")?;
+                        html::write_table_columns(out)?;
+                        writeln!(out, "<tbody>")?;
+                    }
+                    let links = EntryLinks {
+                        spec_prefix: spec.suffix,
+                        table_page: &table_page,
+                        anchor: &spec.anchors[table_index][entry_index],
+                        variable_page: None,
+                        equivalents: equivalents_for(
+                            &specs,
+                            &equivalents,
+                            (spec_index, table_index, entry_index),
+                            root,
+                        ),
+                    };
+                    let label = html::table_anchor(spec.suffix, table);
+                    html::write_entry(
+                        out,
+                        &table.entries[entry_index],
+                        &label,
+                        &links,
+                        &spec.macros,
+                    )?;
+                }
+                writeln!(out, "</tbody>")?;
+                writeln!(out, "</table>")?;
+
+                let missing: Vec<&str> = specs
+                    .iter()
+                    .enumerate()
+                    .filter(|(spec_index, _)| {
+                        !occurrences
+                            .iter()
+                            .any(|&(occurrence_spec, _, _)| occurrence_spec == *spec_index)
+                    })
+                    .map(|(_, spec)| spec.name)
+                    .collect();
+                if !missing.is_empty() {
+                    writeln!(
+                        out,
+                        "<p>Not in the {} state tables.</p>",
+                        missing.join(" or ")
+                    )?;
+                }
+                Ok(())
+            },
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,311 @@
+//! Export to an SQLite database, for ad-hoc querying, e.g.:
+//!
+//! ```sql
+//! SELECT get_value, initial_value FROM entries
+//! JOIN types ON types.id = entries.type_id
+//! WHERE scalar_kind = 'float' AND initial_value != '0';
+//! ```
+//!
+//! See [SCHEMA] for the tables. Text is as it was in the spec's LaTeX source.
+
+use crate::SpecTables;
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+use std::error::Error;
+use std::io;
+use std::path::Path;
+use OpenGL_state_table_parser::types::{
+    scalar_kind, type_code, type_quantity, type_to_latex, MaybeParsed, Quantity,
+};
+use OpenGL_state_table_parser::Condition;
+
+const SCHEMA: &str = "
+CREATE TABLE specs (
+    id INTEGER PRIMARY KEY,
+    suffix TEXT NOT NULL UNIQUE, -- es11, es or gl
+    name TEXT NOT NULL,
+    copyright TEXT NOT NULL
+);
+CREATE TABLE state_tables (
+    id INTEGER PRIMARY KEY,
+    spec_id INTEGER NOT NULL REFERENCES specs(id),
+    position INTEGER NOT NULL, -- order within the spec
+    label TEXT NOT NULL,
+    title TEXT NOT NULL,
+    caption TEXT
+);
+CREATE TABLE footnotes (
+    id INTEGER PRIMARY KEY,
+    state_table_id INTEGER NOT NULL REFERENCES state_tables(id),
+    position INTEGER NOT NULL, -- order within the table
+    text TEXT NOT NULL
+);
+-- Each distinct type. Types that could be parsed are written canonically.
+CREATE TABLE types (
+    id INTEGER PRIMARY KEY,
+    latex TEXT NOT NULL UNIQUE,
+    parsed INTEGER NOT NULL, -- boolean
+    type_code TEXT, -- e.g. Z+, or NULL if not parsed
+    scalar_kind TEXT -- e.g. float, or NULL if not parsed
+);
+-- The terms of \"a × b × … × type\" for parsed types
+CREATE TABLE quantity_terms (
+    id INTEGER PRIMARY KEY,
+    type_id INTEGER NOT NULL REFERENCES types(id),
+    position INTEGER NOT NULL,
+    integer INTEGER, -- exactly one of these three is set
+    constant TEXT, -- an implementation-dependent maximum, e.g. MAX_LIGHTS
+    unparsed TEXT,
+    minimum INTEGER NOT NULL -- boolean: the term is a minimum (n*)
+);
+CREATE TABLE attribute_groups (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE
+);
+CREATE TABLE entries (
+    id INTEGER PRIMARY KEY,
+    state_table_id INTEGER NOT NULL REFERENCES state_tables(id),
+    position INTEGER NOT NULL, -- order within the table
+    condition TEXT, -- core, compatibility, imaging subset, or NULL
+    get_value TEXT,
+    alt_get_value TEXT,
+    -- For a series like LIGHT0, LIGHT1, …: the minimum number of values in it,
+    -- as either an integer or an implementation-dependent maximum.
+    series_minimum_integer INTEGER,
+    series_minimum_constant TEXT,
+    type_id INTEGER REFERENCES types(id),
+    get_cmnd TEXT,
+    initial_value TEXT,
+    description TEXT NOT NULL
+);
+CREATE INDEX entries_get_value ON entries(get_value);
+CREATE INDEX entries_alt_get_value ON entries(alt_get_value);
+-- Attributes like color-buffer/enable are in both groups
+CREATE TABLE entry_attribute_groups (
+    entry_id INTEGER NOT NULL REFERENCES entries(id),
+    attribute_group_id INTEGER NOT NULL REFERENCES attribute_groups(id),
+    PRIMARY KEY (entry_id, attribute_group_id)
+);
+CREATE TABLE entry_footnotes (
+    entry_id INTEGER NOT NULL REFERENCES entries(id),
+    footnote_id INTEGER NOT NULL REFERENCES footnotes(id),
+    field TEXT NOT NULL CHECK (field IN ('get_value', 'type', 'initial_value', 'description'))
+);
+";
+
+/// Write the tables of each spec to a new database at `path`, replacing any
+/// file that's already there.
+pub fn write_database(path: &Path, specs: &[SpecTables]) -> Result<(), Box<dyn Error>> {
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err.into()),
+        _ => (),
+    }
+    let mut connection = Connection::open(path)?;
+    connection.execute_batch("PRAGMA foreign_keys = ON;")?;
+    let transaction = connection.transaction()?;
+    transaction.execute_batch(SCHEMA)?;
+
+    let mut type_ids: HashMap<String, i64> = HashMap::new();
+    let mut attribute_group_ids: HashMap<&str, i64> = HashMap::new();
+    for &(suffix, name, copyright, tables) in specs {
+        transaction.execute(
+            "INSERT INTO specs (suffix, name, copyright) VALUES (?1, ?2, ?3)",
+            params![suffix, name, copyright],
+        )?;
+        let spec_id = transaction.last_insert_rowid();
+
+        for (table_position, table) in tables.iter().enumerate() {
+            transaction.execute(
+                "INSERT INTO state_tables (spec_id, position, label, title, caption)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    spec_id,
+                    table_position as i64,
+                    table.label,
+                    table.title,
+                    table.caption
+                ],
+            )?;
+            let state_table_id = transaction.last_insert_rowid();
+
+            let mut footnote_ids = Vec::new();
+            for (position, footnote) in table.footnotes.iter().enumerate() {
+                transaction.execute(
+                    "INSERT INTO footnotes (state_table_id, position, text) VALUES (?1, ?2, ?3)",
+                    params![state_table_id, position as i64, footnote],
+                )?;
+                footnote_ids.push(transaction.last_insert_rowid());
+            }
+
+            for (entry_position, entry) in table.entries.iter().enumerate() {
+                let type_id = match entry.type_ {
+                    Some(ref type_) => {
+                        let latex = match type_ {
+                            MaybeParsed::Parsed(t) => type_to_latex(t),
+                            MaybeParsed::Unparsed(s) => s.clone(),
+                        };
+                        if let Some(&id) = type_ids.get(&latex) {
+                            Some(id)
+                        } else {
+                            let parsed = match type_ {
+                                MaybeParsed::Parsed(t) => Some(t),
+                                MaybeParsed::Unparsed(_) => None,
+                            };
+                            transaction.execute(
+                                "INSERT INTO types (latex, parsed, type_code, scalar_kind)
+                                 VALUES (?1, ?2, ?3, ?4)",
+                                params![
+                                    latex,
+                                    parsed.is_some(),
+                                    parsed.map(type_code),
+                                    parsed.map(|t| scalar_kind(t).to_string())
+                                ],
+                            )?;
+                            let id = transaction.last_insert_rowid();
+                            for (position, term) in
+                                parsed.map(type_quantity).unwrap_or(&[]).iter().enumerate()
+                            {
+                                let (integer, constant, unparsed, minimum) = match term {
+                                    MaybeParsed::Parsed((Quantity::Integer(n), minimum)) => {
+                                        (Some(*n), None, None, *minimum)
+                                    }
+                                    MaybeParsed::Parsed((Quantity::Constant(c), minimum)) => {
+                                        (None, Some(*c), None, *minimum)
+                                    }
+                                    MaybeParsed::Unparsed(s) => (None, None, Some(s), false),
+                                };
+                                transaction.execute(
+                                    "INSERT INTO quantity_terms
+                                     (type_id, position, integer, constant, unparsed, minimum)
+                                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                                    params![
+                                        id,
+                                        position as i64,
+                                        integer,
+                                        constant,
+                                        unparsed,
+                                        minimum
+                                    ],
+                                )?;
+                            }
+                            type_ids.insert(latex, id);
+                            Some(id)
+                        }
+                    }
+                    None => None,
+                };
+
+                let condition = entry.condition.map(|condition| match condition {
+                    Condition::Core => "core",
+                    Condition::Compatibility => "compatibility",
+                    Condition::ImagingSubset => "imaging subset",
+                });
+                let (series_integer, series_constant) = match entry.series {
+                    Some(Quantity::Integer(n)) => (Some(n), None),
+                    Some(Quantity::Constant(c)) => (None, Some(c)),
+                    None => (None, None),
+                };
+                transaction.execute(
+                    "INSERT INTO entries (state_table_id, position, condition, get_value,
+                     alt_get_value, series_minimum_integer, series_minimum_constant, type_id,
+                     get_cmnd, initial_value, description)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                    params![
+                        state_table_id,
+                        entry_position as i64,
+                        condition,
+                        entry.get_value,
+                        entry.alt_get_value,
+                        series_integer,
+                        series_constant,
+                        type_id,
+                        entry.get_cmnd,
+                        entry.initial_value,
+                        entry.description
+                    ],
+                )?;
+                let entry_id = transaction.last_insert_rowid();
+
+                for group in entry.attribute.iter().flat_map(|a| a.split('/')) {
+                    let group_id = match attribute_group_ids.get(group) {
+                        Some(&id) => id,
+                        None => {
+                            transaction.execute(
+                                "INSERT INTO attribute_groups (name) VALUES (?1)",
+                                params![group],
+                            )?;
+                            let id = transaction.last_insert_rowid();
+                            attribute_group_ids.insert(group, id);
+                            id
+                        }
+                    };
+                    transaction.execute(
+                        "INSERT INTO entry_attribute_groups (entry_id, attribute_group_id)
+                         VALUES (?1, ?2)",
+                        params![entry_id, group_id],
+                    )?;
+                }
+
+                for (field, indices) in [
+                    ("get_value", &entry.get_value_footnotes),
+                    ("type", &entry.type_footnotes),
+                    ("initial_value", &entry.initial_value_footnotes),
+                    ("description", &entry.description_footnotes),
+                ] {
+                    for &index in indices {
+                        transaction.execute(
+                            "INSERT INTO entry_footnotes (entry_id, footnote_id, field)
+                             VALUES (?1, ?2, ?3)",
+                            params![entry_id, footnote_ids[index], field],
+                        )?;
+                    }
+                }
+            }
+        }
+    }
+    transaction.commit()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write_database;
+    use rusqlite::Connection;
+
+    #[test]
+    fn row_counts() {
+        let path = std::env::temp_dir().join(format!("gl-state-test-{}.db", std::process::id()));
+        let entries = crate::with_specs(|specs| {
+            // Writing twice replaces the first database
+            write_database(&path, specs).unwrap();
+            write_database(&path, specs).unwrap();
+            specs
+                .iter()
+                .flat_map(|&(_, _, _, tables)| tables)
+                .map(|table| table.entries.len())
+                .sum::<usize>()
+        });
+        let connection = Connection::open(&path).unwrap();
+        let count = |table: &str| {
+            connection
+                .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+                    row.get::<_, i64>(0)
+                })
+                .unwrap() as usize
+        };
+        assert_eq!(count("specs"), 3);
+        assert_eq!(count("entries"), entries);
+        drop(connection);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn unremovable_file() {
+        // A directory can't be removed like a file, so this is an error, not
+        // a panic
+        let path = std::env::temp_dir().join(format!("gl-state-test-{}-dir", std::process::id()));
+        std::fs::create_dir_all(&path).unwrap();
+        assert!(crate::with_specs(|specs| write_database(&path, specs)).is_err());
+        std::fs::remove_dir(&path).unwrap();
+    }
+}
@@ -0,0 +1,501 @@
+//! Plain-text tables for the terminal, drawn with box-drawing characters and
+//! wrapped to fit the terminal's width. Rows can be coloured by condition, in
+//! the same colours as the HTML.
+
+use crate::html::{differing_fields, equivalent_entries, footnote_symbol, COMPARED_FIELDS};
+use crate::SpecTables;
+use std::io::{self, IsTerminal, Write};
+use OpenGL_state_table_parser::types::{type_to_unicode, MaybeParsed};
+use OpenGL_state_table_parser::{Condition, Entry, Table};
+
+pub struct Options {
+    /// The width to wrap to, in characters.
+    pub width: usize,
+    pub colour: bool,
+}
+
+impl Options {
+    /// Work out the options for standard output. The width is the terminal's,
+    /// unless the `COLUMNS` environment variable overrides it, or 80 if it's
+    /// not a terminal. Colour is used if standard output is a terminal and
+    /// `NO_COLOR` isn't set.
+    pub fn for_stdout() -> Options {
+        let width = std::env::var("COLUMNS")
+            .ok()
+            .and_then(|columns| columns.parse().ok())
+            .or_else(terminal_width)
+            .unwrap_or(80);
+        Options {
+            width,
+            colour: io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+        }
+    }
+}
+
+/// The width of the terminal standard output is connected to, if it is one.
+#[cfg(unix)]
+fn terminal_width() -> Option<usize> {
+    // SAFETY: winsize is plain data, and TIOCGWINSZ only writes to it
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    let result = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) };
+    (result == 0 && size.ws_col > 0).then_some(size.ws_col.into())
+}
+
+#[cfg(not(unix))]
+fn terminal_width() -> Option<usize> {
+    None
+}
+
+const HEADINGS: [&str; 6] = [
+    "Get value",
+    "Type",
+    "Get command",
+    "Initial value",
+    "Description",
+    "Attribute",
+];
+
+/// The smallest width a column is squeezed to.
+const MIN_COLUMN_WIDTH: usize = 6;
+
+/// ANSI escape for black text on the condition's colour from the HTML.
+fn colour_for_condition(condition: Option<Condition>) -> Option<&'static str> {
+    match condition {
+        // pink
+        Some(Condition::Compatibility) => Some("\x1b[30;48;2;255;192;203m"),
+        // lightgreen
+        Some(Condition::Core) => Some("\x1b[30;48;2;144;238;144m"),
+        // silver
+        Some(Condition::ImagingSubset) => Some("\x1b[30;48;2;192;192;192m"),
+        None => None,
+    }
+}
+
+/// Append the symbols for the footnotes with these indices to a cell's text.
+pub fn with_footnotes(text: String, indices: &[usize]) -> String {
+    indices
+        .iter()
+        .fold(text, |text, &index| text + &footnote_symbol(index))
+}
+
+fn cells(entry: &Entry) -> [String; 6] {
+    let or_dash = |text: Option<&str>| text.unwrap_or("—").to_string();
+
+    let mut get_value = or_dash(entry.get_value.as_deref());
+    if let Some(ref alt_get_value) = entry.alt_get_value {
+        get_value = format!("{} or {}", get_value, alt_get_value);
+    }
+    if let Some(ref minimum) = entry.series {
+        get_value = format!(
+            "{} … {} + (n-1) where n ≥ {}",
+            get_value,
+            entry.get_value.as_deref().unwrap(),
+            minimum
+        );
+    }
+
+    let mut type_ = match entry.type_ {
+        Some(MaybeParsed::Parsed(ref type_)) => type_to_unicode(type_),
+        Some(MaybeParsed::Unparsed(ref type_)) => type_.clone(),
+        None if entry.type_footnotes.is_empty() => "—".to_string(),
+        None => String::new(),
+    };
+    if entry.series.is_some() {
+        type_ = format!("n × {}", type_);
+    }
+
+    let initial_value = match entry.initial_value {
+        Some(ref initial_value) => initial_value.to_string(),
+        None if entry.initial_value_footnotes.is_empty() => "—".to_string(),
+        None => String::new(),
+    };
+
+    [
+        with_footnotes(get_value, &entry.get_value_footnotes),
+        with_footnotes(type_, &entry.type_footnotes),
+        or_dash(entry.get_cmnd.as_deref()),
+        with_footnotes(initial_value, &entry.initial_value_footnotes),
+        with_footnotes(entry.description.to_string(), &entry.description_footnotes),
+        or_dash(entry.attribute.as_deref()),
+    ]
+}
+
+/// Wrap text to lines of at most `width` characters, breaking between words
+/// where possible.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let line_length = line.chars().count();
+        let word_length = word.chars().count();
+        if line_length > 0 && line_length + 1 + word_length <= width {
+            line.push(' ');
+            line.push_str(word);
+            continue;
+        }
+        if line_length > 0 {
+            lines.push(std::mem::take(&mut line));
+        }
+        // Words that are too long by themselves get split
+        let mut chars: Vec<char> = word.chars().collect();
+        while chars.len() > width {
+            lines.push(chars.drain(..width).collect());
+        }
+        line = chars.into_iter().collect();
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Share out `available` characters between columns that would like to be
+/// `natural` wide. Each column first gets enough room for its longest word, if
+/// possible.
+fn column_widths(natural: &[usize], longest_word: &[usize], available: usize) -> Vec<usize> {
+    if natural.iter().sum::<usize>() <= available {
+        return natural.to_vec();
+    }
+    let mut widths: Vec<usize> = longest_word.to_vec();
+    if widths.iter().sum::<usize>() > available {
+        let share = (available / widths.len()).max(MIN_COLUMN_WIDTH);
+        widths = longest_word.iter().map(|&width| width.min(share)).collect();
+    }
+    let mut extra = available.saturating_sub(widths.iter().sum());
+    // Columns that are nearly wide enough are finished off first, so as few
+    // columns wrap as possible, then whatever's left goes to the widest.
+    let mut by_shortfall: Vec<usize> = (0..widths.len()).collect();
+    by_shortfall.sort_by_key(|&column| natural[column] - widths[column]);
+    for column in by_shortfall {
+        let shortfall = natural[column] - widths[column];
+        if shortfall <= extra {
+            widths[column] += shortfall;
+            extra -= shortfall;
+        } else {
+            widths[column] += extra;
+            break;
+        }
+    }
+    widths
+}
+
+fn write_border(
+    out: &mut impl Write,
+    widths: &[usize],
+    [left, middle, right]: [char; 3],
+) -> io::Result<()> {
+    write!(out, "{}", left)?;
+    for (index, &width) in widths.iter().enumerate() {
+        if index != 0 {
+            write!(out, "{}", middle)?;
+        }
+        write!(out, "{}", "─".repeat(width + 2))?;
+    }
+    writeln!(out, "{}", right)
+}
+
+fn write_row(
+    out: &mut impl Write,
+    widths: &[usize],
+    cells: &[String],
+    colour: Option<&str>,
+) -> io::Result<()> {
+    let wrapped: Vec<Vec<String>> = cells
+        .iter()
+        .zip(widths)
+        .map(|(cell, &width)| wrap(cell, width))
+        .collect();
+    let height = wrapped.iter().map(Vec::len).max().unwrap();
+    for line_index in 0..height {
+        if let Some(colour) = colour {
+            write!(out, "{}", colour)?;
+        }
+        write!(out, "│")?;
+        for (lines, &width) in wrapped.iter().zip(widths) {
+            let line = lines.get(line_index).map(String::as_str).unwrap_or("");
+            let padding = width - line.chars().count();
+            write!(out, " {}{} │", line, " ".repeat(padding))?;
+        }
+        if colour.is_some() {
+            write!(out, "\x1b[0m")?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Write some of the entries of a table, with the table's title and the
+/// footnotes they refer to.
+pub fn write_entries(
+    out: &mut impl Write,
+    table: &Table,
+    entries: &[&Entry],
+    options: &Options,
+) -> io::Result<()> {
+    writeln!(out, "{}", table.title)?;
+    if let Some(ref caption) = table.caption {
+        writeln!(out, "{}", wrap(caption, options.width).join("\n"))?;
+    }
+
+    let rows: Vec<[String; 6]> = entries.iter().map(|entry| cells(entry)).collect();
+    let column_cells = |column: usize| {
+        rows.iter()
+            .map(move |row| row[column].as_str())
+            .chain([HEADINGS[column]])
+    };
+    let natural: Vec<usize> = (0..HEADINGS.len())
+        .map(|column| {
+            column_cells(column)
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap()
+        })
+        .collect();
+    let longest_word: Vec<usize> = (0..HEADINGS.len())
+        .map(|column| {
+            column_cells(column)
+                .flat_map(str::split_whitespace)
+                .map(|word| word.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    // Each column has a border and a space either side, and there's one more
+    // border at the end.
+    let available = options.width.saturating_sub(HEADINGS.len() * 3 + 1);
+    let widths = column_widths(&natural, &longest_word, available);
+
+    write_border(out, &widths, ['┌', '┬', '┐'])?;
+    let headings = HEADINGS.map(String::from);
+    write_row(out, &widths, &headings, None)?;
+    write_border(out, &widths, ['├', '┼', '┤'])?;
+    for (entry, row) in entries.iter().zip(&rows) {
+        let colour = colour_for_condition(entry.condition).filter(|_| options.colour);
+        write_row(out, &widths, row, colour)?;
+    }
+    write_border(out, &widths, ['└', '┴', '┘'])?;
+
+    let mut footnotes: Vec<usize> = entries
+        .iter()
+        .flat_map(|entry| {
+            [
+                &entry.get_value_footnotes,
+                &entry.type_footnotes,
+                &entry.initial_value_footnotes,
+                &entry.description_footnotes,
+            ]
+        })
+        .flatten()
+        .copied()
+        .collect();
+    footnotes.sort();
+    footnotes.dedup();
+    for index in footnotes {
+        let footnote = format!("{} {}", footnote_symbol(index), table.footnotes[index]);
+        writeln!(out, "{}", wrap(&footnote, options.width).join("\n"))?;
+    }
+    Ok(())
+}
+
+/// Write every entry for a state variable, by get value, alternative get value
+/// or name of a series member, in each spec. The `GL_` prefix and case are
+/// ignored. Returns whether there were any.
+pub fn write_lookup(
+    out: &mut impl Write,
+    name: &str,
+    specs: &[SpecTables],
+    options: &Options,
+) -> io::Result<bool> {
+    let name = name.to_ascii_uppercase();
+    let name = name.strip_prefix("GL_").unwrap_or(&name);
+    let mut found = false;
+    for &(_, spec_name, _, tables) in specs {
+        let mut spec_found = false;
+        for table in tables {
+            let entries: Vec<&Entry> = table
+                .entries
+                .iter()
+                .filter(|entry| entry.is_named(name))
+                .collect();
+            if entries.is_empty() {
+                continue;
+            }
+            if !spec_found {
+                writeln!(out, "{}", spec_name)?;
+                writeln!(out)?;
+                spec_found = true;
+            }
+            write_entries(out, table, &entries, options)?;
+            writeln!(out)?;
+        }
+        found |= spec_found;
+    }
+    Ok(found)
+}
+
+/// Write the differences between two specs: the state variables only in one of
+/// them, and those whose type, get command, initial value or attribute differ.
+/// Variables are matched by get value and alternative get value, and a
+/// variable only counts as different if none of its entries in the other spec
+/// is the same, so e.g. a core profile variant can match.
+pub fn write_diff(
+    out: &mut impl Write,
+    [a, b]: [&SpecTables; 2],
+    options: &Options,
+) -> io::Result<()> {
+    let (_, a_name, _, a_tables) = *a;
+    let (_, b_name, _, b_tables) = *b;
+    let equivalents = equivalent_entries(&[a_tables, b_tables]);
+
+    for (spec_index, (spec_name, tables)) in [(a_name, a_tables), (b_name, b_tables)]
+        .into_iter()
+        .enumerate()
+    {
+        writeln!(out, "Only in {}", spec_name)?;
+        writeln!(out)?;
+        for (table_index, table) in tables.iter().enumerate() {
+            let entries: Vec<&Entry> = table
+                .entries
+                .iter()
+                .enumerate()
+                .filter(|&(entry_index, entry)| {
+                    // Entries without a get value can't be matched up
+                    entry.get_value.is_some()
+                        && !equivalents.contains_key(&(spec_index, table_index, entry_index))
+                })
+                .map(|(_, entry)| entry)
+                .collect();
+            if !entries.is_empty() {
+                write_entries(out, table, &entries, options)?;
+                writeln!(out)?;
+            }
+        }
+    }
+
+    writeln!(out, "Different in {} and {}", a_name, b_name)?;
+    writeln!(out)?;
+    for (table_index, table) in a_tables.iter().enumerate() {
+        for (entry_index, entry) in table.entries.iter().enumerate() {
+            let Some(others) = equivalents.get(&(0, table_index, entry_index)) else {
+                continue;
+            };
+            let differences: Vec<[bool; 4]> = others
+                .iter()
+                .map(|&(_, other_table, other_entry)| {
+                    differing_fields(entry, &b_tables[other_table].entries[other_entry])
+                })
+                .collect();
+            if differences.iter().any(|fields| !fields.contains(&true)) {
+                continue;
+            }
+            let fields: Vec<&str> = COMPARED_FIELDS
+                .iter()
+                .enumerate()
+                .filter(|&(field, _)| differences.iter().any(|fields| fields[field]))
+                .map(|(_, &name)| name)
+                .collect();
+            writeln!(
+                out,
+                "{} differs in {}",
+                entry.get_value.as_deref().unwrap(),
+                fields.join(", ")
+            )?;
+            writeln!(out)?;
+            writeln!(out, "{}", a_name)?;
+            write_entries(out, table, &[entry], options)?;
+            // The other spec's entries, grouped by table
+            let mut others = others.clone();
+            others.sort();
+            others.dedup();
+            writeln!(out, "{}", b_name)?;
+            for group in others.chunk_by(|x, y| x.1 == y.1) {
+                let other_table = &b_tables[group[0].1];
+                let entries: Vec<&Entry> = group
+                    .iter()
+                    .map(|&(_, _, other_entry)| &other_table.entries[other_entry])
+                    .collect();
+                write_entries(out, other_table, &entries, options)?;
+            }
+            writeln!(out)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{write_diff, write_lookup, Options};
+
+    fn lookup(name: &str) -> Option<String> {
+        let options = Options {
+            width: 100,
+            colour: false,
+        };
+        let mut out = Vec::new();
+        let found =
+            crate::with_specs(|specs| write_lookup(&mut out, name, specs, &options)).unwrap();
+        found.then(|| String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn lookup_by_name() {
+        let out = lookup("gl_texture_mag_filter").unwrap();
+        for spec in ["OpenGL ES 1.1", "OpenGL ES 3.2", "OpenGL 4.6"] {
+            assert!(out.lines().any(|line| line == spec), "{} missing", spec);
+        }
+        assert!(out.lines().all(|line| line.chars().count() <= 100));
+        assert!(lookup("NOT_A_STATE_VARIABLE").is_none());
+    }
+
+    fn diff(a: &str, b: &str) -> String {
+        let options = Options {
+            width: 100,
+            colour: false,
+        };
+        let mut out = Vec::new();
+        crate::with_specs(|specs| {
+            let spec = |suffix| specs.iter().find(|spec| spec.0 == suffix).unwrap();
+            write_diff(&mut out, [spec(a), spec(b)], &options)
+        })
+        .unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn diff_specs() {
+        let out = diff("es11", "es");
+        let sections: Vec<&str> = out
+            .lines()
+            .filter(|line| line.starts_with("Only in") || line.starts_with("Different in"))
+            .collect();
+        assert_eq!(
+            sections,
+            [
+                "Only in OpenGL ES 1.1",
+                "Only in OpenGL ES 3.2",
+                "Different in OpenGL ES 1.1 and OpenGL ES 3.2"
+            ]
+        );
+        assert!(out.lines().all(|line| line.chars().count() <= 100));
+        let only_in_es = out.find("Only in OpenGL ES 3.2").unwrap();
+        let different = out.find("Different in").unwrap();
+        // Fixed-function lighting is gone from OpenGL ES 3.2
+        assert!(out[..only_in_es].contains("│ LIGHTING "));
+        assert!(out[only_in_es..different].contains("│ PATCH_VERTICES "));
+        assert!(out[different..]
+            .lines()
+            .any(|line| line == "BUFFER_SIZE differs in get command"));
+        // The same in both, so not in the diff at all
+        assert!(!out.contains("│ LINE_WIDTH "));
+
+        let out = diff("gl", "gl");
+        assert!(!out.contains('│'), "{}", out);
+    }
+
+    #[test]
+    fn lookup_series_member() {
+        let out = lookup("LIGHT3").unwrap();
+        assert!(out.contains("LIGHT0"));
+        assert!(lookup("LIGHT8").is_none());
+    }
+}
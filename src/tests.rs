@@ -0,0 +1,415 @@
+//! Tests for the parser. Most of the parser's behaviour is only really
+//! visible in aggregate, so the bulk of the testing is done by comparing a
+//! dump of everything parsed from each spec against a checked-in "golden"
+//! file in `tests/golden/`. When a change to the output is intended, run the
+//! tests with `UPDATE_GOLDEN=1` to regenerate these, and review the diff.
+
+use super::*;
+use proptest::prelude::*;
+use std::fmt::Write;
+
+/// Produce a canonical text dump of the parsed tables, with one line per
+/// field. Empty fields are omitted to keep the dump readable.
+fn dump_tables(copyright: &str, tables: &[Table]) -> String {
+    let mut out = String::new();
+    for line in copyright.lines() {
+        writeln!(out, "copyright: {:?}", line).unwrap();
+    }
+    for table in tables {
+        writeln!(out).unwrap();
+        writeln!(out, "table {:?}", table.label).unwrap();
+        writeln!(out, "  title: {:?}", table.title).unwrap();
+        if let Some(ref caption) = table.caption {
+            writeln!(out, "  caption: {:?}", caption).unwrap();
+        }
+        if table.minimum_values {
+            writeln!(out, "  minimum values").unwrap();
+        }
+        for (index, footnote) in table.footnotes.iter().enumerate() {
+            writeln!(out, "  footnote {}: {:?}", index, footnote).unwrap();
+        }
+        for entry in &table.entries {
+            let Entry {
+                span: _,
+                condition,
+                get_value,
+                get_value_footnotes,
+                alt_get_value,
+                series,
+                type_,
+                type_footnotes,
+                get_cmnd,
+                initial_value,
+                initial_value_footnotes,
+                description,
+                description_footnotes,
+                attribute,
+            } = entry;
+            writeln!(out, "  entry {:?}", get_value).unwrap();
+            let mut field = |name: &str, value: &dyn std::fmt::Debug| {
+                writeln!(out, "    {}: {:?}", name, value).unwrap();
+            };
+            if let Some(condition) = condition {
+                field("condition", condition);
+            }
+            if !get_value_footnotes.is_empty() {
+                field("get_value_footnotes", get_value_footnotes);
+            }
+            if let Some(alt_get_value) = alt_get_value {
+                field("alt_get_value", alt_get_value);
+            }
+            if let Some(series) = series {
+                field("series", series);
+            }
+            if let Some(type_) = type_ {
+                field("type", type_);
+            }
+            if !type_footnotes.is_empty() {
+                field("type_footnotes", type_footnotes);
+            }
+            if let Some(get_cmnd) = get_cmnd {
+                field("get_cmnd", get_cmnd);
+            }
+            if let Some(initial_value) = initial_value {
+                field("initial_value", initial_value);
+            }
+            if !initial_value_footnotes.is_empty() {
+                field("initial_value_footnotes", initial_value_footnotes);
+            }
+            field("description", description);
+            if !description_footnotes.is_empty() {
+                field("description_footnotes", description_footnotes);
+            }
+            if let Some(attribute) = attribute {
+                field("attribute", attribute);
+            }
+        }
+    }
+    out
+}
+
+fn check_golden(spec: &str) {
+    let source = read_spec(spec);
+    let (copyright, tables) = parse_spec(spec, &source);
+    let actual = dump_tables(&copyright, &tables);
+    let path = format!("{}/tests/golden/{}.txt", env!("CARGO_MANIFEST_DIR"), spec);
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("Can't read {}, run with UPDATE_GOLDEN=1 to create it", path));
+    if actual != expected {
+        let (line_number, (expected_line, actual_line)) = expected
+            .lines()
+            .chain(std::iter::repeat(""))
+            .zip(actual.lines().chain(std::iter::repeat("")))
+            .enumerate()
+            .find(|(_, (expected_line, actual_line))| expected_line != actual_line)
+            .unwrap();
+        panic!(
+            "Output for {} differs from {} at line {}:\nexpected: {}\n  actual: {}\n\
+             If this is intended, run with UPDATE_GOLDEN=1 and review the diff.",
+            spec,
+            path,
+            line_number + 1,
+            expected_line,
+            actual_line
+        );
+    }
+}
+
+#[test]
+fn golden_es11() {
+    check_golden("es11");
+}
+
+#[test]
+fn golden_es() {
+    check_golden("es");
+}
+
+#[test]
+fn golden_gl() {
+    check_golden("gl");
+}
+
+#[test]
+fn read_cell_nested() {
+    assert_eq!(read_cell("{a {b} {c {d}}} {e}"), ("a {b} {c {d}}", " {e}"));
+    assert_eq!(read_cell("  {}rest"), ("", "rest"));
+}
+
+#[test]
+fn read_cell_escaped_braces() {
+    assert_eq!(read_cell("{\\{x\\}}y"), ("\\{x\\}", "y"));
+}
+
+/// Generate text with balanced (unescaped) braces, which may also contain
+/// escaped braces and backslashes.
+fn balanced_text() -> impl Strategy<Value = String> {
+    let leaf = prop_oneof![
+        "[^{}\\\\]*",
+        Just("\\{".to_string()),
+        Just("\\}".to_string()),
+        Just("\\\\".to_string()),
+    ];
+    leaf.prop_recursive(4, 64, 8, |inner| {
+        prop::collection::vec(
+            prop_oneof![
+                inner.clone(),
+                inner.prop_map(|text| format!("{{{}}}", text))
+            ],
+            0..8,
+        )
+        .prop_map(|parts| parts.concat())
+    })
+}
+
+proptest! {
+    #[test]
+    fn read_cell_round_trip(
+        whitespace in "[ \t\n]*",
+        cell in balanced_text(),
+        remainder in ".*",
+    ) {
+        let text = format!("{}{{{}}}{}", whitespace, cell, remainder);
+        prop_assert_eq!(try_read_cell(&text), Some((cell.as_str(), remainder.as_str())));
+    }
+
+    #[test]
+    fn read_cell_never_panics(text in ".*") {
+        let _ = try_read_cell(&text);
+    }
+
+    #[test]
+    fn read_cell_never_panics_on_braces(text in "[ {}\\\\a]*") {
+        let _ = try_read_cell(&text);
+    }
+}
+
+#[test]
+fn read_cell_unbalanced() {
+    assert_eq!(try_read_cell("{a"), None);
+    assert_eq!(try_read_cell("}a{"), None);
+    assert_eq!(try_read_cell("a{b}"), None);
+    assert_eq!(try_read_cell(""), None);
+    // An escaped backslash doesn't escape the brace after it
+    assert_eq!(try_read_cell("{a\\\\}b"), Some(("a\\\\", "b")));
+}
+
+#[test]
+fn divide_removes_multiplication() {
+    assert_eq!(divide("$3 \\times B$", 3), "$B$");
+    assert_eq!(divide("$2 \\times 3 \\times B$", 3), "$2 \\times B$");
+    // Nothing to divide by
+    assert_eq!(divide("$B$", 3), "$B$");
+}
+
+fn test_entry(condition: Option<Condition>, get_value: &str) -> Entry<'_> {
+    Entry {
+        span: Span { start: 0, end: 0 },
+        condition,
+        get_value: Some(get_value.into()),
+        get_value_footnotes: Vec::new(),
+        alt_get_value: None,
+        series: None,
+        type_: None,
+        type_footnotes: Vec::new(),
+        get_cmnd: Some("GetIntegerv".into()),
+        initial_value: None,
+        initial_value_footnotes: Vec::new(),
+        description: "Description".into(),
+        description_footnotes: Vec::new(),
+        attribute: None,
+    }
+}
+
+#[test]
+fn entry_in_profile() {
+    let all = test_entry(None, "A");
+    let core = test_entry(Some(Condition::Core), "A");
+    let compatibility = test_entry(Some(Condition::Compatibility), "A");
+    let imaging = test_entry(Some(Condition::ImagingSubset), "A");
+    for profile in [None, Some(Condition::Core), Some(Condition::Compatibility)] {
+        assert!(all.in_profile(profile));
+    }
+    assert!(core.in_profile(Some(Condition::Core)));
+    assert!(!core.in_profile(Some(Condition::Compatibility)));
+    assert!(!compatibility.in_profile(Some(Condition::Core)));
+    assert!(imaging.in_profile(Some(Condition::Compatibility)));
+    assert!(!imaging.in_profile(Some(Condition::Core)));
+    assert!(imaging.in_profile(None));
+}
+
+#[test]
+fn push_entry_merges_identical_variants() {
+    let mut entries = Vec::new();
+    push_entry(
+        &mut entries,
+        test_entry(Some(Condition::Compatibility), "A"),
+    );
+    push_entry(
+        &mut entries,
+        test_entry(Some(Condition::Compatibility), "B"),
+    );
+    push_entry(&mut entries, test_entry(Some(Condition::Core), "A"));
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].get_value.as_deref(), Some("A"));
+    assert_eq!(entries[0].condition, None);
+    assert_eq!(entries[1].condition, Some(Condition::Compatibility));
+}
+
+#[test]
+fn push_entry_keeps_different_variants() {
+    let mut entries = Vec::new();
+    push_entry(
+        &mut entries,
+        test_entry(Some(Condition::Compatibility), "A"),
+    );
+    let mut core = test_entry(Some(Condition::Core), "A");
+    core.description = "Different description".into();
+    push_entry(&mut entries, core);
+    // Unconditional entries are never merged
+    push_entry(&mut entries, test_entry(None, "A"));
+    assert_eq!(entries.len(), 3);
+}
+
+#[test]
+fn extract_footnote_refs_suffixes() {
+    assert_eq!(
+        extract_footnote_refs("256\\fn1".into()),
+        (Some("256".into()), vec![0])
+    );
+    assert_eq!(
+        extract_footnote_refs("- 1 ULP\\fnb".into()),
+        (Some("- 1 ULP".into()), vec![1])
+    );
+    assert_eq!(
+        extract_footnote_refs("\\footnotemark[1]".into()),
+        (None, vec![0])
+    );
+    assert_eq!(
+        extract_footnote_refs("x \\fn1 \\fnc".into()),
+        (Some("x".into()), vec![0, 2])
+    );
+}
+
+#[test]
+fn extract_footnote_refs_ignores_non_suffixes() {
+    assert_eq!(
+        extract_footnote_refs("size. \\fn1 Max. size".into()),
+        (Some("size. \\fn1 Max. size".into()), vec![])
+    );
+    assert_eq!(
+        extract_footnote_refs("\\fnord".into()),
+        (Some("\\fnord".into()), vec![])
+    );
+}
+
+#[test]
+fn spec_types_latex_round_trip() {
+    for spec in ["es11", "es", "gl"] {
+        for table in parse_spec(spec, &read_spec(spec)).1 {
+            for entry in table.entries {
+                if let Some(MaybeParsed::Parsed(type_)) = entry.type_ {
+                    let latex = types::type_to_latex(&type_);
+                    assert_eq!(parse_type(&latex), Some(type_), "{}", latex);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn unescape_change_markers() {
+    assert_eq!(unescape("a{\\change b}c".into()), "a bc");
+    assert_eq!(
+        unescape("+0.5 \\change\\cbext{gitlab \\#149} - 1 ULP".into()),
+        "+0.5 - 1 ULP"
+    );
+    assert_eq!(unescape("\\ochange foo\\change".into()), "foo");
+    // Only whole control words are change markers
+    assert_eq!(unescape("\\changed".into()), "\\changed");
+}
+
+#[test]
+fn unescape_borrows_unchanged_text() {
+    assert!(matches!(unescape("MAX_VALUE".into()), Cow::Borrowed(_)));
+    assert!(matches!(unescape("MAX\\_VALUE".into()), Cow::Owned(_)));
+}
+
+#[test]
+fn parse_spec_borrows_from_source() {
+    let source = read_spec("gl");
+    let (_, tables) = parse_spec("gl", &source);
+    let entry = tables
+        .iter()
+        .flat_map(|table| &table.entries)
+        .find(|entry| entry.get_value.as_deref() == Some("TEXTURE_MAG_FILTER"))
+        .unwrap();
+    // Underscores are escaped in the source, so this is a real rewrite
+    assert!(matches!(entry.get_value, Some(Cow::Owned(_))));
+    assert!(matches!(
+        entry.get_cmnd,
+        Some(Cow::Borrowed("GetTexParameteriv"))
+    ));
+    assert!(matches!(
+        entry.description,
+        Cow::Borrowed("Magnification function")
+    ));
+    let row = &source[entry.span.start..entry.span.end];
+    assert!(row.starts_with("\\doentry{TEXTURE\\_MAG\\_FILTER}"));
+    assert!(row.ends_with("{texture}"));
+}
+
+#[test]
+fn unescape_text() {
+    assert_eq!(
+        unescape("  ``MAX\\_\\-VALUE''\n   \\small is {\\em big} ".into()),
+        "“MAX_VALUE” is {\\em big}"
+    );
+    assert_eq!(unescape("\\small\\tt x".into()), "\\small\\tt x");
+}
+
+#[test]
+fn parse_spec_minimum_value_tables() {
+    let source = read_spec("es");
+    let (_, tables) = parse_spec("es", &source);
+    let table_of = |get_value: &str| {
+        tables
+            .iter()
+            .find(|table| {
+                table
+                    .entries
+                    .iter()
+                    .any(|entry| entry.get_value.as_deref() == Some(get_value))
+            })
+            .unwrap()
+    };
+    assert!(!table_of("VIEWPORT").minimum_values);
+    assert!(table_of("MAX_TEXTURE_SIZE").minimum_values);
+    // The macros are \def'd before the first table, which doesn't count
+    assert!(!tables[0].minimum_values);
+}
+
+#[test]
+fn entry_is_named() {
+    let source = read_spec("es11");
+    let (_, tables) = parse_spec("es11", &source);
+    let find = |name: &str| {
+        tables
+            .iter()
+            .flat_map(|table| &table.entries)
+            .filter(|entry| entry.is_named(name))
+            .map(|entry| entry.get_value.as_deref().unwrap())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(find("LIGHTING"), ["LIGHTING"]);
+    // LIGHT0 ... LIGHT7
+    assert_eq!(find("LIGHT3"), ["LIGHT0"]);
+    assert!(find("LIGHT8").is_empty());
+    assert!(find("LIGHT03").is_empty());
+}
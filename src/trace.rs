@@ -0,0 +1,505 @@
+//! Replaying recorded GL call traces against [MockContext], to see what state
+//! a program changes from its initial values, which calls set state to what it
+//! already was, and which calls touch state that isn't in the target's tables.
+//!
+//! Traces are text with one call per line, like `apitrace dump` prints them:
+//! `123 glEnable(cap = GL_BLEND)`. The call number and argument names are
+//! optional, so `glEnable(GL_BLEND)` works too. Lines that aren't calls, like
+//! the rest of a multi-line shader source, are skipped. Frames end at calls to
+//! `glXSwapBuffers`, `eglSwapBuffers` and the like.
+//!
+//! Only the calls [MockContext] has setters for are replayed, plus `Get*` and
+//! `IsEnabled` queries, which are only checked for unknown names. The rest are
+//! counted and listed at the end. Calls that touch state [MockContext] can't
+//! model are listed on their own, since they might be fine.
+
+use crate::lint::Target;
+use crate::SpecTables;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use OpenGL_state_table_parser::mock::{Error, MockContext, Value};
+
+/// How to analyse a trace.
+pub struct Options<'a> {
+    /// Report state after every draw call, rather than once per frame
+    pub per_draw: bool,
+    /// The window size, which is the initial viewport and scissor box. It's
+    /// 640×480 unless it's given.
+    pub width: u32,
+    pub height: u32,
+    pub path: &'a str,
+}
+
+impl Options<'_> {
+    /// Parse the command line after the target: `[--per-draw] [--window
+    /// <width>x<height>] <trace file>`.
+    pub fn parse<'a>(args: &[&'a str]) -> Option<Options<'a>> {
+        let mut options = Options {
+            per_draw: false,
+            width: 640,
+            height: 480,
+            path: "",
+        };
+        let mut args = args.iter();
+        while let Some(&arg) = args.next() {
+            match arg {
+                "--per-draw" => options.per_draw = true,
+                "--window" => {
+                    let (width, height) = args.next()?.split_once('x')?;
+                    options.width = width.parse().ok()?;
+                    options.height = height.parse().ok()?;
+                }
+                path if options.path.is_empty() && !path.starts_with("--") => options.path = path,
+                _ => return None,
+            }
+        }
+        (!options.path.is_empty()).then_some(options)
+    }
+}
+
+/// A call from a trace.
+struct Call<'a> {
+    /// The call number from the trace, or the line number if it doesn't have
+    /// them
+    number: usize,
+    name: &'a str,
+    arguments: Vec<&'a str>,
+    /// The whole line, for reports
+    text: &'a str,
+}
+
+/// Parse a line of a trace, if it's a call.
+fn parse_call(line_number: usize, line: &str) -> Option<Call<'_>> {
+    let text = line.trim();
+    let (number, rest) = match text.split_once(' ') {
+        Some((number, rest)) if number.bytes().all(|b| b.is_ascii_digit()) => {
+            (number.parse().ok()?, rest.trim_start())
+        }
+        _ => (line_number, text),
+    };
+    let (name, rest) = rest.split_once('(')?;
+    if name.is_empty() || !name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_') {
+        return None;
+    }
+
+    // Split the arguments at top-level commas, up to the closing parenthesis.
+    // Anything after that is the return value.
+    let mut arguments = Vec::new();
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, c) in rest.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => (),
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '(' | '{' | '[' => depth += 1,
+            ')' if depth == 0 => {
+                let last = rest[start..i].trim();
+                if !last.is_empty() || !arguments.is_empty() {
+                    arguments.push(last);
+                }
+                break;
+            }
+            ')' | '}' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                arguments.push(rest[start..i].trim());
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    // Drop argument names, which apitrace writes as `name = value`
+    for argument in &mut arguments {
+        if let Some((label, value)) = argument.split_once(" = ") {
+            if label
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'_')
+            {
+                *argument = value;
+            }
+        }
+    }
+    Some(Call {
+        number,
+        name,
+        arguments,
+        text,
+    })
+}
+
+fn is_frame_end(name: &str) -> bool {
+    name.ends_with("SwapBuffers") || name == "CGLFlushDrawable"
+}
+
+fn is_draw(name: &str) -> bool {
+    (name.starts_with("glDraw") && !name.starts_with("glDrawBuffer"))
+        || name.starts_with("glMultiDraw")
+}
+
+fn integer(argument: &str) -> Option<i32> {
+    match argument.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok().map(|i| i as i32),
+        None => argument.parse().ok(),
+    }
+}
+
+fn unsigned(argument: &str) -> Option<u32> {
+    integer(argument).map(|i| i as u32)
+}
+
+fn float(argument: &str) -> Option<f32> {
+    argument.parse().ok()
+}
+
+fn boolean(argument: &str) -> Option<bool> {
+    match argument {
+        "GL_TRUE" | "true" | "1" => Some(true),
+        "GL_FALSE" | "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Replay a call. Returns `None` if it isn't one that can be replayed, or its
+/// arguments couldn't be parsed, and otherwise whether it's a query and
+/// whether it changed any state.
+fn replay(
+    context: &mut MockContext,
+    name: &str,
+    args: &[&str],
+) -> Option<(bool, Result<bool, Error>)> {
+    let set = |result| Some((false, result));
+    match (name, args) {
+        ("glEnable", &[cap]) => set(context.enable(cap)),
+        ("glDisable", &[cap]) => set(context.disable(cap)),
+        ("glEnablei", &[cap, index]) => set(context.enablei(cap, unsigned(index)?)),
+        ("glDisablei", &[cap, index]) => set(context.disablei(cap, unsigned(index)?)),
+        ("glBlendFunc", &[sfactor, dfactor]) => set(context.blend_func(sfactor, dfactor)),
+        ("glBlendFuncSeparate", &[src_rgb, dst_rgb, src_alpha, dst_alpha]) => {
+            set(context.blend_func_separate(src_rgb, dst_rgb, src_alpha, dst_alpha))
+        }
+        ("glBlendEquation", &[mode]) => set(context.blend_equation(mode)),
+        ("glViewport", &[x, y, width, height]) => {
+            set(context.viewport(integer(x)?, integer(y)?, integer(width)?, integer(height)?))
+        }
+        ("glScissor", &[x, y, width, height]) => {
+            set(context.scissor(integer(x)?, integer(y)?, integer(width)?, integer(height)?))
+        }
+        ("glClearColor", &[red, green, blue, alpha]) => {
+            set(context.clear_color(float(red)?, float(green)?, float(blue)?, float(alpha)?))
+        }
+        ("glClearDepth" | "glClearDepthf", &[depth]) => {
+            set(context.clear_depth(depth.parse().ok()?))
+        }
+        ("glClearStencil", &[stencil]) => set(context.clear_stencil(integer(stencil)?)),
+        ("glColorMask", &[red, green, blue, alpha]) => set(context.color_mask(
+            boolean(red)?,
+            boolean(green)?,
+            boolean(blue)?,
+            boolean(alpha)?,
+        )),
+        ("glDepthMask", &[flag]) => set(context.depth_mask(boolean(flag)?)),
+        ("glDepthFunc", &[func]) => set(context.depth_func(func)),
+        ("glCullFace", &[mode]) => set(context.cull_face(mode)),
+        ("glFrontFace", &[mode]) => set(context.front_face(mode)),
+        ("glLineWidth", &[width]) => set(context.line_width(float(width)?)),
+        ("glActiveTexture", &[texture]) => set(context.active_texture(texture)),
+        ("glBindTexture", &[target, texture]) => {
+            set(context.bind_texture(target, unsigned(texture)?))
+        }
+        ("glBindBuffer", &[target, buffer]) => set(context.bind_buffer(target, unsigned(buffer)?)),
+        ("glBindFramebuffer", &[target, framebuffer]) => {
+            set(context.bind_framebuffer(target, unsigned(framebuffer)?))
+        }
+        ("glBindVertexArray", &[array]) => set(context.bind_vertex_array(unsigned(array)?)),
+        ("glUseProgram", &[program]) => set(context.use_program(unsigned(program)?)),
+        ("glIsEnabled", &[cap]) => Some((true, context.is_enabled(cap).map(|_| false))),
+        ("glIsEnabledi", &[cap, index]) => Some((
+            true,
+            context.is_enabledi(cap, unsigned(index)?).map(|_| false),
+        )),
+        (
+            "glGetBooleanv" | "glGetIntegerv" | "glGetInteger64v" | "glGetFloatv" | "glGetDoublev",
+            &[pname, _],
+        ) => Some((true, context.get(name, pname).map(|_| false))),
+        (
+            "glGetBooleani_v" | "glGetIntegeri_v" | "glGetInteger64i_v" | "glGetFloati_v"
+            | "glGetDoublei_v",
+            &[pname, index, _],
+        ) => Some((
+            true,
+            context
+                .get_indexed(name, pname, unsigned(index)?)
+                .map(|_| false),
+        )),
+        _ => None,
+    }
+}
+
+/// What's been seen since the last report.
+#[derive(Default)]
+struct Report {
+    redundant: Vec<String>,
+    /// Calls that touch state [MockContext] can't model, which might be fine
+    unmodelled: Vec<String>,
+    problems: Vec<String>,
+}
+
+fn write_values(out: &mut impl Write, values: &[Value]) -> io::Result<()> {
+    for (i, value) in values.iter().enumerate() {
+        write!(out, "{}{}", if i == 0 { "" } else { ", " }, value)?;
+    }
+    Ok(())
+}
+
+fn write_report(
+    out: &mut impl Write,
+    heading: &str,
+    report: &Report,
+    context: &MockContext,
+    initial: &MockContext,
+) -> io::Result<()> {
+    writeln!(out, "{}:", heading)?;
+    let differences = context.differences(initial);
+    if differences.is_empty() {
+        writeln!(out, "  All state has its initial value")?;
+    } else {
+        writeln!(out, "  Non-default state:")?;
+        for (name, values) in &differences {
+            write!(out, "    GL_{} = ", name)?;
+            write_values(out, values)?;
+            writeln!(out)?;
+        }
+    }
+    for (title, calls) in [
+        ("Redundant calls", &report.redundant),
+        ("Calls touching unmodelled state", &report.unmodelled),
+        ("Problems", &report.problems),
+    ] {
+        if !calls.is_empty() {
+            writeln!(out, "  {}:", title)?;
+            for call in calls {
+                writeln!(out, "    {}", call)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Replay a trace against a context for the target, writing a report for each
+/// frame (or draw) to `out`. Returns how many problems there were, not
+/// counting calls that touch unmodelled state.
+pub fn analyse(
+    out: &mut impl Write,
+    target: Target,
+    specs: &[SpecTables],
+    options: &Options,
+    source: &str,
+) -> io::Result<usize> {
+    let &(_, spec_name, _, tables) = specs
+        .iter()
+        .find(|&&(suffix, ..)| suffix == target.suffix)
+        .unwrap();
+    let target_name = target.name(spec_name);
+    let initial = MockContext::new(tables, target.profile, options.width, options.height);
+    let mut context = initial.clone();
+
+    let mut frame = 1;
+    let mut draw = 1;
+    let mut report = Report::default();
+    let mut problems = 0;
+    // Whether there have been calls since the last report
+    let mut pending = false;
+    let mut not_replayed: HashMap<&str, usize> = HashMap::new();
+    for (line_number, line) in source.lines().enumerate() {
+        let Some(call) = parse_call(line_number + 1, line) else {
+            continue;
+        };
+        pending = true;
+        if is_frame_end(call.name) {
+            let heading = if options.per_draw {
+                format!("Frame {}, end ({} {})", frame, call.number, call.name)
+            } else {
+                format!("Frame {}", frame)
+            };
+            write_report(out, &heading, &report, &context, &initial)?;
+            report = Report::default();
+            pending = false;
+            frame += 1;
+            draw = 1;
+            continue;
+        }
+        if is_draw(call.name) {
+            if options.per_draw {
+                let heading = format!("Frame {}, draw {} ({})", frame, draw, call.text);
+                write_report(out, &heading, &report, &context, &initial)?;
+                report = Report::default();
+                pending = false;
+            }
+            draw += 1;
+            continue;
+        }
+        match replay(&mut context, call.name, &call.arguments) {
+            None => *not_replayed.entry(call.name).or_default() += 1,
+            Some((false, Ok(false))) => report.redundant.push(call.text.to_string()),
+            Some((_, Ok(_))) => (),
+            Some((_, Err(err @ Error::Unmodelled(..)))) => {
+                report.unmodelled.push(format!("{}: {}", call.text, err))
+            }
+            Some((_, Err(err))) => {
+                problems += 1;
+                let message = match err {
+                    Error::UnknownName(name) => format!("GL_{} isn't in {}", name, target_name),
+                    err => err.to_string(),
+                };
+                report.problems.push(format!("{}: {}", call.text, message));
+            }
+        }
+    }
+    if pending {
+        let heading = format!("Frame {} (unfinished)", frame);
+        write_report(out, &heading, &report, &context, &initial)?;
+    }
+
+    if !not_replayed.is_empty() {
+        let mut not_replayed: Vec<_> = not_replayed.into_iter().collect();
+        not_replayed.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+        writeln!(out, "Calls that weren't replayed:")?;
+        for (name, count) in not_replayed {
+            writeln!(out, "  {} ({})", name, count)?;
+        }
+    }
+    Ok(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{analyse, parse_call, Options};
+    use crate::lint::Target;
+
+    /// A line, and its number, name and arguments if it's a call.
+    type Line<'a> = (&'a str, Option<(usize, &'a str, &'a [&'a str])>);
+
+    #[test]
+    fn calls() {
+        let cases: [Line; 8] = [
+            ("glEnable(GL_BLEND)", Some((7, "glEnable", &["GL_BLEND"]))),
+            (
+                "123 glEnable(cap = GL_BLEND)",
+                Some((123, "glEnable", &["GL_BLEND"])),
+            ),
+            (
+                "  5 glViewport(x = 0, y = 0, width = 640, height = 480)",
+                Some((5, "glViewport", &["0", "0", "640", "480"])),
+            ),
+            ("12 glFinish()", Some((12, "glFinish", &[]))),
+            (
+                "8 glShaderSource(shader = 3, count = 1, string = {\"a(b, \\\"c)\"}, length = NULL)",
+                Some((8, "glShaderSource", &["3", "1", "{\"a(b, \\\"c)\"}", "NULL"])),
+            ),
+            (
+                "9 glGetIntegerv(pname = GL_VIEWPORT, params = {0, 0, 640, 480})",
+                Some((9, "glGetIntegerv", &["GL_VIEWPORT", "{0, 0, 640, 480}"])),
+            ),
+            (
+                "10 glCreateShader(type = GL_VERTEX_SHADER) = 3",
+                Some((10, "glCreateShader", &["GL_VERTEX_SHADER"])),
+            ),
+            ("void main() {", None),
+        ];
+        for (line, expected) in cases {
+            let actual = parse_call(7, line).map(|call| (call.number, call.name, call.arguments));
+            assert_eq!(
+                actual,
+                expected.map(|(number, name, arguments)| (number, name, arguments.to_vec())),
+                "{:?}",
+                line
+            );
+        }
+    }
+
+    fn analysed(target: &str, per_draw: bool, source: &str) -> (String, usize) {
+        let options = Options {
+            per_draw,
+            width: 640,
+            height: 480,
+            path: "test.trace",
+        };
+        let mut out = Vec::new();
+        let problems = crate::with_specs(|specs| {
+            let target = Target::parse(target).unwrap();
+            analyse(&mut out, target, specs, &options, source)
+        })
+        .unwrap();
+        (String::from_utf8(out).unwrap(), problems)
+    }
+
+    #[test]
+    fn frames() {
+        let source = "1 glEnable(cap = GL_BLEND)
+2 glEnable(cap = GL_BLEND)
+3 glViewport(x = 0, y = 0, width = 640, height = 480)
+4 glGetIntegerv(pname = GL_TEXTURE_MAG_FILTER, params = &0)
+5 glEnable(cap = GL_NOT_A_THING)
+6 glDrawArrays(mode = GL_TRIANGLES, first = 0, count = 3)
+7 glUniform1f(location = 0, v0 = 1)
+8 glXSwapBuffers(dpy = 0x1, drawable = 0x2)
+9 glDisable(cap = GL_BLEND)
+";
+        let (out, problems) = analysed("es", false, source);
+        assert_eq!(problems, 1);
+        assert_eq!(
+            out,
+            "Frame 1:
+  Non-default state:
+    GL_BLEND = TRUE
+  Redundant calls:
+    2 glEnable(cap = GL_BLEND)
+    3 glViewport(x = 0, y = 0, width = 640, height = 480)
+  Calls touching unmodelled state:
+    4 glGetIntegerv(pname = GL_TEXTURE_MAG_FILTER, params = &0): GL_TEXTURE_MAG_FILTER isn't modelled: it's queried with GetTexParameter
+  Problems:
+    5 glEnable(cap = GL_NOT_A_THING): GL_NOT_A_THING isn't in OpenGL ES 3.2
+Frame 2 (unfinished):
+  All state has its initial value
+Calls that weren't replayed:
+  glUniform1f (1)
+"
+        );
+    }
+
+    #[test]
+    fn draws() {
+        let source = "glEnable(GL_DEPTH_TEST)
+glDrawArrays(GL_TRIANGLES, 0, 3)
+glDepthFunc(GL_LEQUAL)
+glDrawArrays(GL_TRIANGLES, 0, 3)
+eglSwapBuffers(0x1, 0x2)
+";
+        let (out, problems) = analysed("gl-core", true, source);
+        assert_eq!(problems, 0);
+        assert_eq!(
+            out,
+            "Frame 1, draw 1 (glDrawArrays(GL_TRIANGLES, 0, 3)):
+  Non-default state:
+    GL_DEPTH_TEST = TRUE
+Frame 1, draw 2 (glDrawArrays(GL_TRIANGLES, 0, 3)):
+  Non-default state:
+    GL_DEPTH_FUNC = GL_LEQUAL
+    GL_DEPTH_TEST = TRUE
+Frame 1, end (5 eglSwapBuffers):
+  Non-default state:
+    GL_DEPTH_FUNC = GL_LEQUAL
+    GL_DEPTH_TEST = TRUE
+"
+        );
+    }
+}
@@ -1,14 +1,17 @@
+use crate::mathml::escape_html;
+use std::io::{self, Write};
+
 /// Some fields can be parsed into a structured form, but this won't always
 /// succeed. This enum is used in such cases: it either contains the parsed form
 /// (`T`) or an unparsed [String].
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum MaybeParsed<T> {
     Parsed(T),
     Unparsed(String),
 }
 
 /// A parsed representation of a type.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Type {
     /// The basic type, which is usually but not always a scalar.
     basic_type: BasicType,
@@ -21,7 +24,7 @@ pub struct Type {
 
 /// A parsed representation of a type code. The descriptions here come from the
 /// OpenGL 4.6 spec.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 enum BasicType {
     /// _B_: Boolean
     Boolean,
@@ -107,7 +110,7 @@ enum BasicType {
 }
 
 /// Parsed representation of a quantity.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Quantity {
     /// Simple integer quantity.
     Integer(u32),
@@ -192,12 +195,17 @@ fn parse_basic_type(basic_type: &str) -> Option<BasicType> {
                 })
             })
             .or_else(|| {
-                basic_type.strip_prefix("R_").and_then(|k| {
-                    parse_quantity(k).map(|k| {
-                        let Quantity::Integer(k) = k else { panic!() };
-                        BasicType::KValuedFloat { k }
+                basic_type
+                    .strip_prefix("R_")
+                    .map(|k| {
+                        k.strip_prefix('{')
+                            .and_then(|k| k.strip_suffix('}'))
+                            .unwrap_or(k)
+                    })
+                    .and_then(|k| match parse_quantity(k)? {
+                        Quantity::Integer(k) => Some(BasicType::KValuedFloat { k }),
+                        Quantity::Constant(_) => None,
                     })
-                })
             })
             .or_else(|| {
                 basic_type
@@ -207,80 +215,236 @@ fn parse_basic_type(basic_type: &str) -> Option<BasicType> {
                             .and_then(|k| k.strip_suffix('}'))
                             .unwrap_or(k)
                     })
-                    .and_then(|k| {
-                        parse_quantity(k).map(|k| {
-                            let Quantity::Integer(k) = k else { panic!() };
-                            BasicType::FloatTuple { k }
-                        })
+                    .and_then(|k| match parse_quantity(k)? {
+                        Quantity::Integer(k) => Some(BasicType::FloatTuple { k }),
+                        Quantity::Constant(_) => None,
                     })
             }),
     }
 }
 
-pub fn print_quantity(quantity: &Quantity) {
-    match quantity {
-        Quantity::Integer(n) => print!("{}", n),
-        Quantity::Constant(c) => print!("<code>{}</code>", c),
+/// How [write_type_with] marks up the parts of a type, so the same type can be
+/// rendered as HTML ([Html]) or as plain text ([Unicode]).
+pub trait Markup {
+    /// A type code like _Z_, with its optional superscript and subscript, and
+    /// what it means. The subscript's boolean says whether it's a minimum.
+    fn type_code(
+        &self,
+        out: &mut impl Write,
+        code: &str,
+        superscript: Option<&str>,
+        subscript: Option<(&Quantity, bool)>,
+        meaning: &str,
+    ) -> io::Result<()>;
+    fn quantity(&self, out: &mut impl Write, quantity: &Quantity) -> io::Result<()>;
+    /// The asterisk that marks a term of a type's quantity as a minimum.
+    fn minimum(&self, out: &mut impl Write) -> io::Result<()>;
+    /// A term of a type's quantity that couldn't be parsed, as LaTeX.
+    fn unparsed(&self, out: &mut impl Write, term: &str) -> io::Result<()>;
+    /// A type that isn't a type code, like `char`.
+    fn c_type(&self, out: &mut impl Write, name: &str) -> io::Result<()>;
+}
+
+/// HTML markup, with `<abbr>` tags explaining the type codes.
+pub struct Html;
+
+impl Markup for Html {
+    fn type_code(
+        &self,
+        out: &mut impl Write,
+        code: &str,
+        superscript: Option<&str>,
+        subscript: Option<(&Quantity, bool)>,
+        meaning: &str,
+    ) -> io::Result<()> {
+        write!(out, "<abbr title=\"{}\">{}", meaning, code)?;
+        if let Some(superscript) = superscript {
+            write!(out, "<sup>{}</sup>", superscript)?;
+        }
+        if let Some((subscript, minimum)) = subscript {
+            write!(out, "<sub>")?;
+            self.quantity(out, subscript)?;
+            if minimum {
+                write!(out, "*")?;
+            }
+            write!(out, "</sub>")?;
+        }
+        write!(out, "</abbr>")
+    }
+    fn quantity(&self, out: &mut impl Write, quantity: &Quantity) -> io::Result<()> {
+        match quantity {
+            Quantity::Integer(n) => write!(out, "{}", n),
+            Quantity::Constant(c) => write!(out, "<code>{}</code>", c),
+        }
+    }
+    fn minimum(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "<abbr title=\"quantity is a minimum\">*</abbr>")
+    }
+    fn unparsed(&self, out: &mut impl Write, term: &str) -> io::Result<()> {
+        write!(out, "<code>{}</code>", escape_html(term))
+    }
+    fn c_type(&self, out: &mut impl Write, name: &str) -> io::Result<()> {
+        write!(out, "<code>{}</code>", name)
     }
 }
 
-fn print_basic_type(basic_type: &BasicType) {
-    match basic_type {
-        BasicType::Boolean => print!("<abbr title=\"Boolean\">B</abbr>"),
-        BasicType::Bmu => print!("<abbr title=\"Basic machine units\">BMU</abbr>"),
-        BasicType::Color => print!("<abbr title=\"Color\">C</abbr>"),
-        BasicType::Enum => print!("<abbr title=\"Enumerated value\">E</abbr>"),
-        BasicType::ColorIndex => print!("<abbr title=\"Color index\">CI</abbr>"),
-        BasicType::TexCoords => print!("<abbr title=\"Texture coordinates\">T</abbr>"),
-        BasicType::NormalCoords => print!("<abbr title=\"Normal coordinates\">N</abbr>"),
-        BasicType::Vertex => print!("<abbr title=\"Vertex\">V</abbr>"),
-        BasicType::Integer => print!("<abbr title=\"Integer\">Z</abbr>"),
-        BasicType::NonNegativeInteger => {
-            print!("<abbr title=\"Non-negative integer\">Z<sup>+</sup></abbr>")
+/// Plain text, with Unicode superscripts and subscripts where possible, e.g.
+/// `Z⁺`, `R⁴` or `Z₁₆*`. Scripts that have no Unicode equivalent are written
+/// like `R^[0,1]` instead.
+pub struct Unicode;
+
+impl Unicode {
+    fn script(
+        out: &mut impl Write,
+        text: &str,
+        marker: char,
+        chars: &[char; 11],
+    ) -> io::Result<()> {
+        let mapped: Option<String> = text
+            .chars()
+            .map(|c| match c {
+                '0'..='9' => Some(chars[c as usize - '0' as usize]),
+                '+' => Some(chars[10]),
+                _ => None,
+            })
+            .collect();
+        match mapped {
+            Some(mapped) => write!(out, "{}", mapped),
+            None => write!(out, "{}{}", marker, text),
         }
-        BasicType::KValuedInteger { k, minimum: false } => {
-            print!("<abbr title=\"{}-valued integer\">Z<sub>", k);
-            print_quantity(k);
-            print!("</sub></abbr>");
+    }
+}
+
+impl Markup for Unicode {
+    fn type_code(
+        &self,
+        out: &mut impl Write,
+        code: &str,
+        superscript: Option<&str>,
+        subscript: Option<(&Quantity, bool)>,
+        _meaning: &str,
+    ) -> io::Result<()> {
+        write!(out, "{}", code)?;
+        if let Some(superscript) = superscript {
+            Unicode::script(
+                out,
+                superscript,
+                '^',
+                &['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹', '⁺'],
+            )?;
         }
-        BasicType::KValuedInteger { k, minimum: true } => {
-            print!(
-                "<abbr title=\"{}-valued integer ({} is a minimum)\">Z<sub>",
-                k, k
-            );
-            print_quantity(k);
-            print!("*</sub></abbr>");
+        if let Some((subscript, minimum)) = subscript {
+            let subscript = match subscript {
+                Quantity::Integer(n) => n.to_string(),
+                Quantity::Constant(c) => format!("({})", c),
+            };
+            Unicode::script(
+                out,
+                &subscript,
+                '_',
+                &['₀', '₁', '₂', '₃', '₄', '₅', '₆', '₇', '₈', '₉', '₊'],
+            )?;
+            if minimum {
+                write!(out, "*")?;
+            }
         }
-        BasicType::Float => print!("<abbr title=\"Floating-point number\">R</abbr>"),
-        BasicType::NonNegativeFloat => {
-            print!("<abbr title=\"Non-negative floating-point number\">R<sup>+</sup></abbr>")
+        Ok(())
+    }
+    fn quantity(&self, out: &mut impl Write, quantity: &Quantity) -> io::Result<()> {
+        write!(out, "{}", quantity)
+    }
+    fn minimum(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "*")
+    }
+    fn unparsed(&self, out: &mut impl Write, term: &str) -> io::Result<()> {
+        write!(out, "{}", term)
+    }
+    fn c_type(&self, out: &mut impl Write, name: &str) -> io::Result<()> {
+        write!(out, "{}", name)
+    }
+}
+
+pub fn write_quantity(out: &mut impl Write, quantity: &Quantity) -> io::Result<()> {
+    Html.quantity(out, quantity)
+}
+
+fn write_basic_type(
+    out: &mut impl Write,
+    markup: &impl Markup,
+    basic_type: &BasicType,
+) -> io::Result<()> {
+    let simple = |out: &mut _, code, meaning| markup.type_code(out, code, None, None, meaning);
+    match basic_type {
+        BasicType::Boolean => simple(out, "B", "Boolean"),
+        BasicType::Bmu => simple(out, "BMU", "Basic machine units"),
+        BasicType::Color => simple(out, "C", "Color"),
+        BasicType::Enum => simple(out, "E", "Enumerated value"),
+        BasicType::ColorIndex => simple(out, "CI", "Color index"),
+        BasicType::TexCoords => simple(out, "T", "Texture coordinates"),
+        BasicType::NormalCoords => simple(out, "N", "Normal coordinates"),
+        BasicType::Vertex => simple(out, "V", "Vertex"),
+        BasicType::Integer => simple(out, "Z", "Integer"),
+        BasicType::NonNegativeInteger => {
+            markup.type_code(out, "Z", Some("+"), None, "Non-negative integer")
         }
-        BasicType::ZeroOneRangeFloat => print!(
-            "<abbr title=\"Floating-point number in the range [0,1]\">R<sup>[0,1]</sup></abbr>"
+        BasicType::KValuedInteger { k, minimum: false } => markup.type_code(
+            out,
+            "Z",
+            None,
+            Some((k, false)),
+            &format!("{}-valued integer", k),
+        ),
+        BasicType::KValuedInteger { k, minimum: true } => markup.type_code(
+            out,
+            "Z",
+            None,
+            Some((k, true)),
+            &format!("{}-valued integer ({} is a minimum)", k, k),
+        ),
+        BasicType::Float => simple(out, "R", "Floating-point number"),
+        BasicType::NonNegativeFloat => markup.type_code(
+            out,
+            "R",
+            Some("+"),
+            None,
+            "Non-negative floating-point number",
         ),
-        BasicType::FloatTuple { k } => print!(
-            "<abbr title=\"{}-tuple of floating-point numbers\">R<sup>{}</sup></abbr>",
-            k, k
+        BasicType::ZeroOneRangeFloat => markup.type_code(
+            out,
+            "R",
+            Some("[0,1]"),
+            None,
+            "Floating-point number in the range [0,1]",
         ),
-        BasicType::KValuedFloat { k } => print!(
-            "<abbr title=\"{}-valued floating-point number\">R<sub>{}</sub></abbr>",
-            k, k
+        BasicType::FloatTuple { k } => markup.type_code(
+            out,
+            "R",
+            Some(&k.to_string()),
+            None,
+            &format!("{}-tuple of floating-point numbers", k),
         ),
-        BasicType::Position => print!("<abbr title=\"Position\">P</abbr>"),
-        BasicType::Direction => print!("<abbr title=\"Direction\">D</abbr>"),
+        BasicType::KValuedFloat { k } => markup.type_code(
+            out,
+            "R",
+            None,
+            Some((&Quantity::Integer(*k), false)),
+            &format!("{}-valued floating-point number", k),
+        ),
+        BasicType::Position => simple(out, "P", "Position"),
+        BasicType::Direction => simple(out, "D", "Direction"),
         BasicType::Matrix => {
-            print!("<abbr title=\"4 × 4 floating-point matrix\">M<sup>4</sup></abbr>")
+            markup.type_code(out, "M", Some("4"), None, "4 × 4 floating-point matrix")
         }
-        BasicType::String => print!("<abbr title=\"Null-terminated string\">S</abbr>"),
-        BasicType::Image => print!("<abbr title=\"Image\">I</abbr>"),
-        BasicType::AttributeStackEntry => print!("<abbr title=\"Attribute stack entry\">A</abbr>"),
-        BasicType::Pointer => print!("<abbr title=\"Pointer\">Y</abbr>"),
-        BasicType::Char => print!("<code>char</code>"),
+        BasicType::String => simple(out, "S", "Null-terminated string"),
+        BasicType::Image => simple(out, "I", "Image"),
+        BasicType::AttributeStackEntry => simple(out, "A", "Attribute stack entry"),
+        BasicType::Pointer => simple(out, "Y", "Pointer"),
+        BasicType::Char => markup.c_type(out, "char"),
     }
 }
 
 pub fn parse_type(type_: &str) -> Option<Type> {
-    let type_ = type_.strip_prefix('$').unwrap().strip_suffix('$').unwrap();
+    let type_ = type_.strip_prefix('$')?.strip_suffix('$')?;
 
     let mut quantity = Vec::new();
     let mut basic_type = type_;
@@ -314,7 +478,13 @@ pub fn parse_type(type_: &str) -> Option<Type> {
     })
 }
 
-pub fn print_type(type_: &Type) {
+/// Write a type as HTML.
+pub fn write_type(out: &mut impl Write, type_: &Type) -> io::Result<()> {
+    write_type_with(out, &Html, type_)
+}
+
+/// Write a type with the given markup.
+pub fn write_type_with(out: &mut impl Write, markup: &impl Markup, type_: &Type) -> io::Result<()> {
     let Type {
         basic_type,
         quantity,
@@ -322,14 +492,519 @@ pub fn print_type(type_: &Type) {
     for term in quantity {
         match term {
             MaybeParsed::Parsed((term, minimum)) => {
-                print_quantity(term);
+                markup.quantity(out, term)?;
                 if *minimum {
-                    print!("<abbr title=\"quantity is a minimum\">*</abbr>");
+                    markup.minimum(out)?;
                 }
             }
-            MaybeParsed::Unparsed(term) => print!("<code>{}</code>", term),
+            MaybeParsed::Unparsed(term) => markup.unparsed(out, term)?,
+        }
+        write!(out, " × ")?;
+    }
+    write_basic_type(out, markup, basic_type)
+}
+
+/// A type as plain text with Unicode superscripts and subscripts, e.g.
+/// `4 × Z⁺`. See [Unicode].
+pub fn type_to_unicode(type_: &Type) -> String {
+    let mut text = Vec::new();
+    write_type_with(&mut text, &Unicode, type_).unwrap();
+    String::from_utf8(text).unwrap()
+}
+
+/// The inverse of [parse_quantity].
+fn quantity_to_latex(quantity: &Quantity) -> String {
+    match quantity {
+        Quantity::Integer(n) => n.to_string(),
+        Quantity::Constant(c) => match *c {
+            "MAX_TEXTURE_COORDS" => "\\mtexcoord",
+            "MAX_TEXTURE_UNITS" => "\\mtexunit",
+            "MAX_COMBINED_TEXTURE_IMAGE_UNITS" => "\\mteximage",
+            "MAX_IMAGE_UNITS" => "\\mimageunit",
+            "MAX_VERTEX_ATTRIBS" => "\\mvtxattr",
+            "MAX_DRAW_BUFFERS" => "\\mdrawbuf",
+            "MAX_VERTEX_UNIFORM_BLOCKS" => "\\mblockstage",
+            "MAX_COMBINED_UNIFORM_BLOCKS" => "\\mblockcombined",
+            _ => unreachable!("Unknown constant: {}", c),
+        }
+        .to_string(),
+    }
+}
+
+/// The inverse of [parse_basic_type].
+fn basic_type_to_latex(basic_type: &BasicType) -> String {
+    match basic_type {
+        BasicType::Boolean => "B".to_string(),
+        BasicType::Bmu => "BMU".to_string(),
+        BasicType::Color => "C".to_string(),
+        BasicType::Enum => "\\Enum".to_string(),
+        BasicType::ColorIndex => "CI".to_string(),
+        BasicType::TexCoords => "T".to_string(),
+        BasicType::NormalCoords => "N".to_string(),
+        BasicType::Vertex => "V".to_string(),
+        BasicType::Integer => "Z".to_string(),
+        BasicType::NonNegativeInteger => "Z^{+}".to_string(),
+        BasicType::KValuedInteger { k, minimum } => format!(
+            "Z_{{{}{}}}",
+            quantity_to_latex(k),
+            if *minimum { "*" } else { "" }
+        ),
+        BasicType::Float => "R".to_string(),
+        BasicType::NonNegativeFloat => "R^{+}".to_string(),
+        BasicType::ZeroOneRangeFloat => "R^{[0,1]}".to_string(),
+        BasicType::FloatTuple { k } => format!("R^{{{}}}", k),
+        BasicType::KValuedFloat { k } => format!("R_{{{}}}", k),
+        BasicType::Position => "P".to_string(),
+        BasicType::Direction => "D".to_string(),
+        BasicType::Matrix => "M^{4}".to_string(),
+        BasicType::String => "S".to_string(),
+        BasicType::Image => "I".to_string(),
+        BasicType::AttributeStackEntry => "A".to_string(),
+        BasicType::Pointer => "Y".to_string(),
+        BasicType::Char => "\\glt{char}".to_string(),
+    }
+}
+
+/// Produce the LaTeX source for a type, in the same notation the specs use.
+/// This is the inverse of [parse_type], so it can also serve as a canonical
+/// plain-text representation.
+pub fn type_to_latex(type_: &Type) -> String {
+    let Type {
+        basic_type,
+        quantity,
+    } = type_;
+    let mut latex = String::from("$");
+    for term in quantity {
+        match term {
+            MaybeParsed::Parsed((term, minimum)) => {
+                latex.push_str(&quantity_to_latex(term));
+                if *minimum {
+                    latex.push('*');
+                }
+            }
+            MaybeParsed::Unparsed(term) => latex.push_str(term),
+        }
+        latex.push_str(" \\times ");
+    }
+    latex.push_str(&basic_type_to_latex(basic_type));
+    latex.push('$');
+    latex
+}
+
+fn quantity_to_rust(quantity: &Quantity) -> String {
+    match quantity {
+        Quantity::Integer(n) => format!("Quantity::Integer({})", n),
+        Quantity::Constant(c) => format!("Quantity::Constant({:?})", c),
+    }
+}
+
+/// Produce a Rust expression for a type, for generated code that has its own
+/// copies of these types (see the `rust-module` mode of the binary). Unlike
+/// here, its quantity is a `&'static [Term]`, with `Term::Parsed(quantity,
+/// minimum)` and `Term::Unparsed(text)`.
+pub fn type_to_rust(type_: &Type) -> String {
+    let Type {
+        basic_type,
+        quantity,
+    } = type_;
+    let basic_type = match basic_type {
+        BasicType::KValuedInteger { k, minimum } => format!(
+            "BasicType::KValuedInteger {{ k: {}, minimum: {} }}",
+            quantity_to_rust(k),
+            minimum
+        ),
+        // The other variants' Debug output is already valid Rust
+        basic_type => format!("BasicType::{:?}", basic_type),
+    };
+    let terms: Vec<String> = quantity
+        .iter()
+        .map(|term| match term {
+            MaybeParsed::Parsed((term, minimum)) => {
+                format!("Term::Parsed({}, {})", quantity_to_rust(term), minimum)
+            }
+            MaybeParsed::Unparsed(term) => format!("Term::Unparsed({:?})", term),
+        })
+        .collect();
+    format!(
+        "Type {{ basic_type: {}, quantity: &[{}] }}",
+        basic_type,
+        terms.join(", ")
+    )
+}
+
+/// The type code of a type's basic type, ignoring its quantity and any
+/// particular _k_, e.g. `"Z+"` for _Z_<sup>+</sup> or `"Z_k"` for
+/// _Z_<sub>16</sub>. Useful for grouping entries by type.
+pub fn type_code(type_: &Type) -> &'static str {
+    match type_.basic_type {
+        BasicType::Boolean => "B",
+        BasicType::Bmu => "BMU",
+        BasicType::Color => "C",
+        BasicType::Enum => "E",
+        BasicType::ColorIndex => "CI",
+        BasicType::TexCoords => "T",
+        BasicType::NormalCoords => "N",
+        BasicType::Vertex => "V",
+        BasicType::Integer => "Z",
+        BasicType::NonNegativeInteger => "Z+",
+        BasicType::KValuedInteger { .. } => "Z_k",
+        BasicType::Float => "R",
+        BasicType::NonNegativeFloat => "R+",
+        BasicType::ZeroOneRangeFloat => "R[0,1]",
+        BasicType::FloatTuple { .. } => "R^k",
+        BasicType::KValuedFloat { .. } => "R_k",
+        BasicType::Position => "P",
+        BasicType::Direction => "D",
+        BasicType::Matrix => "M4",
+        BasicType::String => "S",
+        BasicType::Image => "I",
+        BasicType::AttributeStackEntry => "A",
+        BasicType::Pointer => "Y",
+        BasicType::Char => "char",
+    }
+}
+
+/// The quantity of a type, i.e. the terms of "a × b × … × type". This is empty
+/// if there's just one.
+pub fn type_quantity(type_: &Type) -> &[MaybeParsed<(Quantity, bool)>] {
+    &type_.quantity
+}
+
+/// What kind of scalar values a type is made of, as far as querying it goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScalarKind {
+    Boolean,
+    Integer,
+    Enum,
+    Float,
+    Pointer,
+    /// Characters of a string
+    String,
+    /// Something that can't be queried as scalars at all, like an image or
+    /// the vertices used during primitive assembly.
+    Opaque,
+}
+impl std::fmt::Display for ScalarKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            ScalarKind::Boolean => "boolean",
+            ScalarKind::Integer => "integer",
+            ScalarKind::Enum => "enum",
+            ScalarKind::Float => "float",
+            ScalarKind::Pointer => "pointer",
+            ScalarKind::String => "string",
+            ScalarKind::Opaque => "opaque",
+        })
+    }
+}
+
+/// The kind of scalars in a type. Colors, coordinates and matrices are all
+/// floating-point.
+pub fn scalar_kind(type_: &Type) -> ScalarKind {
+    match type_.basic_type {
+        BasicType::Boolean => ScalarKind::Boolean,
+        BasicType::Enum => ScalarKind::Enum,
+        BasicType::Integer | BasicType::NonNegativeInteger | BasicType::KValuedInteger { .. } => {
+            ScalarKind::Integer
+        }
+        BasicType::Color
+        | BasicType::ColorIndex
+        | BasicType::TexCoords
+        | BasicType::NormalCoords
+        | BasicType::Float
+        | BasicType::NonNegativeFloat
+        | BasicType::ZeroOneRangeFloat
+        | BasicType::FloatTuple { .. }
+        | BasicType::KValuedFloat { .. }
+        | BasicType::Position
+        | BasicType::Direction
+        | BasicType::Matrix => ScalarKind::Float,
+        BasicType::Pointer => ScalarKind::Pointer,
+        BasicType::String | BasicType::Char => ScalarKind::String,
+        BasicType::Bmu | BasicType::Vertex | BasicType::Image | BasicType::AttributeStackEntry => {
+            ScalarKind::Opaque
+        }
+    }
+}
+
+/// How many scalars there are in one value of a type's basic type, ignoring
+/// its quantity, e.g. 4 for a color. This is [None] for strings and opaque
+/// types.
+pub fn basic_component_count(type_: &Type) -> Option<u32> {
+    match type_.basic_type {
+        BasicType::Color | BasicType::TexCoords | BasicType::Position => Some(4),
+        BasicType::NormalCoords | BasicType::Direction => Some(3),
+        BasicType::Matrix => Some(16),
+        BasicType::FloatTuple { k } => Some(k),
+        BasicType::String
+        | BasicType::Char
+        | BasicType::Bmu
+        | BasicType::Vertex
+        | BasicType::Image
+        | BasicType::AttributeStackEntry => None,
+        _ => Some(1),
+    }
+}
+
+/// Whether a type is an RGBA color, whose components convert to integers as
+/// normalized values.
+pub fn is_color(type_: &Type) -> bool {
+    type_.basic_type == BasicType::Color
+}
+
+/// Whether a type is an integer type whose values can't be negative, like
+/// `Z⁺` or `Zₖ`.
+pub fn is_non_negative(type_: &Type) -> bool {
+    matches!(
+        type_.basic_type,
+        BasicType::NonNegativeInteger | BasicType::KValuedInteger { .. }
+    )
+}
+
+/// Whether a type is a 4×4 matrix.
+pub fn is_matrix(type_: &Type) -> bool {
+    type_.basic_type == BasicType::Matrix
+}
+
+/// How many scalars there are in a value of a type, e.g. 4 for a color or 32
+/// for "2 × 16 × Z". This is [None] if it isn't a fixed number: the quantity
+/// depends on the implementation or couldn't be parsed, or the type is a string
+/// or something opaque.
+pub fn component_count(type_: &Type) -> Option<u32> {
+    type_
+        .quantity
+        .iter()
+        .try_fold(basic_component_count(type_)?, |count, term| match term {
+            MaybeParsed::Parsed((Quantity::Integer(n), _)) => count.checked_mul(*n),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn parse_quantity_integers_and_constants() {
+        assert_eq!(parse_quantity("16"), Some(Quantity::Integer(16)));
+        assert_eq!(
+            parse_quantity("\\mdrawbuf"),
+            Some(Quantity::Constant("MAX_DRAW_BUFFERS"))
+        );
+        assert_eq!(parse_quantity("\\mprogstage"), Some(Quantity::Integer(6)));
+        assert_eq!(parse_quantity("n"), None);
+        assert_eq!(parse_quantity(""), None);
+    }
+
+    #[test]
+    fn parse_type_basic() {
+        assert_eq!(
+            parse_type("$\\Zplus$"),
+            Some(Type {
+                basic_type: BasicType::NonNegativeInteger,
+                quantity: Vec::new(),
+            })
+        );
+        assert_eq!(
+            parse_type("$R^{[0,1]}$"),
+            Some(Type {
+                basic_type: BasicType::ZeroOneRangeFloat,
+                quantity: Vec::new(),
+            })
+        );
+        assert_eq!(
+            parse_type("$R^{4}$"),
+            Some(Type {
+                basic_type: BasicType::FloatTuple { k: 4 },
+                quantity: Vec::new(),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_type_k_valued() {
+        assert_eq!(
+            parse_type("$Z_{16*}$"),
+            Some(Type {
+                basic_type: BasicType::KValuedInteger {
+                    k: Quantity::Integer(16),
+                    minimum: true,
+                },
+                quantity: Vec::new(),
+            })
+        );
+        // The minimum suffix is ignored for constants
+        assert_eq!(
+            parse_type("$Z_{\\mdrawbuf*}$"),
+            Some(Type {
+                basic_type: BasicType::KValuedInteger {
+                    k: Quantity::Constant("MAX_DRAW_BUFFERS"),
+                    minimum: false,
+                },
+                quantity: Vec::new(),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_type_quantities() {
+        assert_eq!(
+            parse_type("$16* \\times \\mvtxattr \\times n \\times B$"),
+            Some(Type {
+                basic_type: BasicType::Boolean,
+                quantity: vec![
+                    MaybeParsed::Parsed((Quantity::Integer(16), true)),
+                    MaybeParsed::Parsed((Quantity::Constant("MAX_VERTEX_ATTRIBS"), false)),
+                    MaybeParsed::Unparsed("n".to_string()),
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn type_codes() {
+        let code = |type_| type_code(&parse_type(type_).unwrap());
+        assert_eq!(code("$\\Enum$"), "E");
+        assert_eq!(code("$4 \\times Z^{+}$"), "Z+");
+        assert_eq!(code("$Z_{16*}$"), "Z_k");
+        assert_eq!(code("$R^{4}$"), "R^k");
+    }
+
+    #[test]
+    fn scalar_kinds() {
+        let kind = |type_| scalar_kind(&parse_type(type_).unwrap());
+        assert_eq!(kind("$B$"), ScalarKind::Boolean);
+        assert_eq!(kind("$\\Enum$"), ScalarKind::Enum);
+        assert_eq!(kind("$16 \\times Z_{3}$"), ScalarKind::Integer);
+        assert_eq!(kind("$C$"), ScalarKind::Float);
+        assert_eq!(kind("$I$"), ScalarKind::Opaque);
+        assert!(is_color(&parse_type("$C$").unwrap()));
+        assert!(!is_color(&parse_type("$R^{4}$").unwrap()));
+        assert!(is_matrix(&parse_type("$32* \\times M^{4}$").unwrap()));
+        assert!(is_non_negative(&parse_type("$10* \\times Z^{+}$").unwrap()));
+        assert!(is_non_negative(&parse_type("$Z_{3}$").unwrap()));
+        assert!(!is_non_negative(&parse_type("$4 \\times Z$").unwrap()));
+    }
+
+    #[test]
+    fn component_counts() {
+        let count = |type_| component_count(&parse_type(type_).unwrap());
+        assert_eq!(count("$B$"), Some(1));
+        assert_eq!(count("$C$"), Some(4));
+        assert_eq!(count("$R^{3}$"), Some(3));
+        assert_eq!(count("$2 \\times 16 \\times Z$"), Some(32));
+        assert_eq!(count("$\\mdrawbuf \\times \\Enum$"), None);
+        assert_eq!(count("$I$"), None);
+    }
+
+    #[test]
+    fn rust_types() {
+        let rust = |type_| type_to_rust(&parse_type(type_).unwrap());
+        assert_eq!(
+            rust("$R^{4}$"),
+            "Type { basic_type: BasicType::FloatTuple { k: 4 }, quantity: &[] }"
+        );
+        assert_eq!(
+            rust("$16* \\times n \\times Z_{\\mdrawbuf}$"),
+            "Type { basic_type: BasicType::KValuedInteger { k: Quantity::Constant(\"MAX_DRAW_BUFFERS\"), minimum: false }, \
+             quantity: &[Term::Parsed(Quantity::Integer(16), true), Term::Unparsed(\"n\")] }"
+        );
+    }
+
+    #[test]
+    fn unicode_types() {
+        let unicode = |type_| type_to_unicode(&parse_type(type_).unwrap());
+        assert_eq!(unicode("$\\Zplus$"), "Z⁺");
+        assert_eq!(unicode("$R^{4}$"), "R⁴");
+        assert_eq!(unicode("$16* \\times B$"), "16* × B");
+        assert_eq!(unicode("$n \\times B$"), "n × B");
+        assert_eq!(unicode("$Z_{16*}$"), "Z₁₆*");
+        assert_eq!(unicode("$R^{[0,1]}$"), "R^[0,1]");
+    }
+
+    #[test]
+    fn parse_type_failure() {
+        assert_eq!(parse_type("$R^n$"), None);
+    }
+
+    const CONSTANTS: &[&str] = &[
+        "MAX_TEXTURE_COORDS",
+        "MAX_TEXTURE_UNITS",
+        "MAX_COMBINED_TEXTURE_IMAGE_UNITS",
+        "MAX_IMAGE_UNITS",
+        "MAX_VERTEX_ATTRIBS",
+        "MAX_DRAW_BUFFERS",
+        "MAX_VERTEX_UNIFORM_BLOCKS",
+        "MAX_COMBINED_UNIFORM_BLOCKS",
+    ];
+
+    /// Generate a quantity and whether it is a minimum. As in [parse_type],
+    /// constants are never minimums.
+    fn quantity() -> impl Strategy<Value = (Quantity, bool)> {
+        prop_oneof![
+            (any::<u32>(), any::<bool>()).prop_map(|(n, minimum)| (Quantity::Integer(n), minimum)),
+            prop::sample::select(CONSTANTS).prop_map(|c| (Quantity::Constant(c), false)),
+        ]
+    }
+
+    fn basic_type() -> impl Strategy<Value = BasicType> {
+        prop_oneof![
+            Just(BasicType::Boolean),
+            Just(BasicType::Bmu),
+            Just(BasicType::Color),
+            Just(BasicType::Enum),
+            Just(BasicType::ColorIndex),
+            Just(BasicType::TexCoords),
+            Just(BasicType::NormalCoords),
+            Just(BasicType::Vertex),
+            Just(BasicType::Integer),
+            Just(BasicType::NonNegativeInteger),
+            quantity().prop_map(|(k, minimum)| BasicType::KValuedInteger { k, minimum }),
+            Just(BasicType::Float),
+            Just(BasicType::NonNegativeFloat),
+            Just(BasicType::ZeroOneRangeFloat),
+            any::<u32>().prop_map(|k| BasicType::FloatTuple { k }),
+            any::<u32>().prop_map(|k| BasicType::KValuedFloat { k }),
+            Just(BasicType::Position),
+            Just(BasicType::Direction),
+            Just(BasicType::Matrix),
+            Just(BasicType::String),
+            Just(BasicType::Image),
+            Just(BasicType::AttributeStackEntry),
+            Just(BasicType::Pointer),
+            Just(BasicType::Char),
+        ]
+    }
+
+    fn type_() -> impl Strategy<Value = Type> {
+        let term = prop_oneof![
+            quantity().prop_map(MaybeParsed::Parsed),
+            "[a-z]{1,3}".prop_map(MaybeParsed::Unparsed),
+        ];
+        (basic_type(), prop::collection::vec(term, 0..4)).prop_map(|(basic_type, quantity)| Type {
+            basic_type,
+            quantity,
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn type_latex_round_trip(type_ in type_()) {
+            let latex = type_to_latex(&type_);
+            prop_assert_eq!(parse_type(&latex), Some(type_), "{}", latex);
+        }
+
+        #[test]
+        fn parse_type_never_panics(text in ".*") {
+            let _ = parse_type(&text);
+        }
+
+        #[test]
+        fn parse_type_never_panics_in_math(text in "\\$[ -~]*\\$") {
+            if let Some(type_) = parse_type(&text) {
+                prop_assert_eq!(parse_type(&type_to_latex(&type_)), Some(type_));
+            }
         }
-        print!(" × ");
     }
-    print_basic_type(basic_type);
 }
@@ -0,0 +1,309 @@
+//! TypeScript declarations and JSON data for WebGL 2, from the OpenGL ES 3.2
+//! tables. WebGL 2 is based on ES 3.0, so this has some state WebGL doesn't.
+//!
+//! `gl-state.d.ts` has `ParameterTypes`, the type that each WebGL query method
+//! like `getParameter` returns for each parameter, and `StateEntry`, the type
+//! of the entries in `gl-state.json`:
+//!
+//! ```ts
+//! import type { StateEntry } from "./gl-state";
+//! import data from "./gl-state.json";
+//! const entries = data as StateEntry[];
+//! ```
+//!
+//! Entries whose types can't be mapped to JavaScript ones have an `unmapped`
+//! reason in the JSON, and are `unknown` in `ParameterTypes`.
+
+use crate::search_index::{json_option, json_string};
+use crate::SpecTables;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use OpenGL_state_table_parser::types::{
+    basic_component_count, is_non_negative, scalar_kind, type_quantity, type_to_latex, MaybeParsed,
+    Quantity, ScalarKind, Type,
+};
+use OpenGL_state_table_parser::Entry;
+
+const HEADER: &str = "// Generated by OpenGL-state-table-parser from the state tables of the
+// OpenGL ES 3.2 spec. Don't edit it by hand.";
+
+const STATE_ENTRY: &str =
+    "/** An entry in gl-state.json. Text is as it was in the spec's LaTeX source. */
+export interface StateEntry {
+    /** Without the GL_ prefix, e.g. \"TEXTURE_MAG_FILTER\" */
+    getValue: string;
+    altGetValue: string | null;
+    /** The title of the table it's in */
+    table: string;
+    /** The spec's type, in its LaTeX notation */
+    type: string | null;
+    /** The OpenGL ES command, e.g. \"GetTexParameter\" */
+    getCommand: string | null;
+    /** The WebGL method, or null if WebGL doesn't have one */
+    webglMethod: keyof ParameterTypes | null;
+    /** The type the WebGL method returns, or null if it's unmapped */
+    returnType: string | null;
+    /** Why there's no return type, if there isn't */
+    unmapped: string | null;
+    initialValue: string | null;
+    description: string;
+}";
+
+/// The WebGL method for an OpenGL ES get command.
+fn webgl_method(get_cmnd: &str) -> Option<&'static str> {
+    Some(match get_cmnd {
+        "GetBooleanv" | "GetIntegerv" | "GetInteger64v" | "GetFloatv" | "GetString" => {
+            "getParameter"
+        }
+        "GetBooleani_v" | "GetIntegeri_v" | "GetInteger64i_v" => "getIndexedParameter",
+        "IsEnabled" => "isEnabled",
+        "GetTexParameter" | "GetTexParameteriv" | "GetTexParameterfv" => "getTexParameter",
+        "GetSamplerParameter" | "GetSamplerParameteriv" | "GetSamplerParameterfv" => {
+            "getSamplerParameter"
+        }
+        "GetBufferParameteriv" | "GetBufferParameteri64v" => "getBufferParameter",
+        "GetFramebufferAttachmentParameteriv" => "getFramebufferAttachmentParameter",
+        "GetRenderbufferParameteriv" => "getRenderbufferParameter",
+        "GetProgramiv" => "getProgramParameter",
+        "GetShaderiv" => "getShaderParameter",
+        "GetVertexAttribiv" | "GetVertexAttribfv" => "getVertexAttrib",
+        "GetQueryiv" => "getQuery",
+        "GetQueryObjectuiv" => "getQueryParameter",
+        "GetSynciv" => "getSyncParameter",
+        "GetActiveUniformBlockiv" => "getActiveUniformBlockParameter",
+        "GetActiveUniformsiv" => "getActiveUniforms",
+        "GetInternalformativ" => "getInternalformatParameter",
+        _ => return None,
+    })
+}
+
+/// The WebGL object type for a get value that's the binding of an object.
+fn binding_type(get_value: &str) -> Option<&'static str> {
+    Some(match get_value {
+        "CURRENT_PROGRAM" => "WebGLProgram",
+        "CURRENT_QUERY" => "WebGLQuery",
+        "SAMPLER_BINDING" => "WebGLSampler",
+        "TRANSFORM_FEEDBACK_BINDING" => "WebGLTransformFeedback",
+        "VERTEX_ARRAY_BINDING" => "WebGLVertexArrayObject",
+        "RENDERBUFFER_BINDING" => "WebGLRenderbuffer",
+        "FRAMEBUFFER_ATTACHMENT_OBJECT_NAME" => "WebGLRenderbuffer | WebGLTexture",
+        _ if get_value.ends_with("FRAMEBUFFER_BINDING") => "WebGLFramebuffer",
+        _ if get_value.ends_with("BUFFER_BINDING") => "WebGLBuffer",
+        _ if get_value.starts_with("TEXTURE_BINDING_") => "WebGLTexture",
+        _ => return None,
+    })
+}
+
+/// A JavaScript type, or why there isn't one.
+type ReturnType = Result<String, String>;
+
+/// The type a WebGL `method` returns for a value of `type_`, or why there
+/// isn't one.
+fn return_type(method: &str, get_value: &str, type_: &Type) -> ReturnType {
+    let kind = scalar_kind(type_);
+    if kind == ScalarKind::String {
+        return Ok("string".to_string());
+    }
+    let Some(mut count) = basic_component_count(type_) else {
+        return Err(format!("{} values have no JavaScript equivalent", kind));
+    };
+    let terms = type_quantity(type_);
+    // The first term of an indexed method's type counts the indices it takes,
+    // and getBufferParameter and getActiveUniforms are per buffer or uniform.
+    // Otherwise a leading implementation-dependent term counts the units the
+    // state is per, like texture units, which are selected some other way.
+    let selector = match method {
+        "getIndexedParameter"
+        | "getVertexAttrib"
+        | "getQuery"
+        | "getBufferParameter"
+        | "getActiveUniforms" => 1,
+        _ => match terms.first() {
+            Some(MaybeParsed::Parsed((Quantity::Constant(_), _))) => 1,
+            _ => 0,
+        },
+    };
+    // The rest are the value's own length, which might be a minimum or depend
+    // on the implementation or object, e.g. COMPRESSED_TEXTURE_FORMATS.
+    let mut variable = false;
+    for term in terms.iter().skip(selector) {
+        match term {
+            MaybeParsed::Parsed((Quantity::Integer(n), false)) => count *= n,
+            _ => variable = true,
+        }
+    }
+
+    let type_name = match (kind, count, variable) {
+        (ScalarKind::Integer, 1, false) => match binding_type(get_value) {
+            Some(object) => format!("{} | null", object),
+            None => "number".to_string(),
+        },
+        (ScalarKind::Boolean, 1, false) => "boolean".to_string(),
+        (ScalarKind::Boolean, _, _) => "boolean[]".to_string(),
+        (ScalarKind::Enum, 1, false) => "number".to_string(),
+        (ScalarKind::Integer | ScalarKind::Enum, _, false) => "Int32Array".to_string(),
+        (ScalarKind::Enum, _, true) => "Uint32Array".to_string(),
+        (ScalarKind::Integer, _, true) if is_non_negative(type_) => "Uint32Array".to_string(),
+        (ScalarKind::Integer, _, true) => "Int32Array".to_string(),
+        (ScalarKind::Float, 1, false) => "number".to_string(),
+        (ScalarKind::Float, _, _) => "Float32Array".to_string(),
+        (kind, _, _) => return Err(format!("{} values have no JavaScript equivalent", kind)),
+    };
+    // getActiveUniforms returns an array with a value for each uniform asked
+    // for
+    Ok(match method {
+        "getActiveUniforms" if type_name.contains(' ') => format!("({})[]", type_name),
+        "getActiveUniforms" => format!("{}[]", type_name),
+        _ => type_name,
+    })
+}
+
+/// The WebGL method and return type for an entry, or why they're unknown.
+fn map_entry(get_value: &str, entry: &Entry) -> (Option<&'static str>, ReturnType) {
+    let Some(ref get_cmnd) = entry.get_cmnd else {
+        return (None, Err("it has no get command".to_string()));
+    };
+    let Some(method) = webgl_method(get_cmnd) else {
+        return (
+            None,
+            Err(format!("WebGL has no equivalent of {}", get_cmnd)),
+        );
+    };
+    let return_type = match entry.type_ {
+        Some(MaybeParsed::Parsed(ref type_)) => return_type(method, get_value, type_),
+        Some(MaybeParsed::Unparsed(_)) => Err("its type couldn't be parsed".to_string()),
+        None => Err("it has no type".to_string()),
+    };
+    (Some(method), return_type)
+}
+
+/// Write `gl-state.d.ts` and `gl-state.json` to `out_dir`.
+pub fn write_webgl(out_dir: &Path, specs: &[SpecTables]) -> io::Result<()> {
+    let &(_, _, _, tables) = specs
+        .iter()
+        .find(|&&(suffix, _, _, _)| suffix == "es")
+        .unwrap();
+    fs::create_dir_all(out_dir)?;
+
+    // The return types for each parameter of each method. Some parameters
+    // have more than one entry, which might have different types.
+    let mut parameter_types: BTreeMap<&str, BTreeMap<&str, BTreeSet<ReturnType>>> = BTreeMap::new();
+    let mut json = BufWriter::new(File::create(out_dir.join("gl-state.json"))?);
+    writeln!(json, "[")?;
+    let mut first = true;
+    for table in tables {
+        for entry in &table.entries {
+            let Some(get_value) = entry.get_value.as_deref() else {
+                continue;
+            };
+            let (method, return_type) = map_entry(get_value, entry);
+            if let Some(method) = method {
+                parameter_types
+                    .entry(method)
+                    .or_default()
+                    .entry(get_value)
+                    .or_default()
+                    .insert(return_type.clone());
+            }
+
+            if !first {
+                writeln!(json, ",")?;
+            }
+            first = false;
+            let type_ = entry.type_.as_ref().map(|type_| match type_ {
+                MaybeParsed::Parsed(type_) => type_to_latex(type_),
+                MaybeParsed::Unparsed(type_) => type_.clone(),
+            });
+            write!(
+                json,
+                "{{\"getValue\":{},\"altGetValue\":{},\"table\":{},\"type\":{},\
+                 \"getCommand\":{},\"webglMethod\":{},\"returnType\":{},\"unmapped\":{},\
+                 \"initialValue\":{},\"description\":{}}}",
+                json_string(get_value),
+                json_option(entry.alt_get_value.as_deref()),
+                json_string(&table.title),
+                json_option(type_.as_deref()),
+                json_option(entry.get_cmnd.as_deref()),
+                json_option(method),
+                json_option(return_type.as_deref().ok()),
+                json_option(return_type.as_ref().err().map(String::as_str)),
+                json_option(entry.initial_value.as_deref()),
+                json_string(&entry.description)
+            )?;
+        }
+    }
+    writeln!(json)?;
+    writeln!(json, "]")?;
+    json.flush()?;
+
+    let mut dts = BufWriter::new(File::create(out_dir.join("gl-state.d.ts"))?);
+    writeln!(dts, "{}", HEADER)?;
+    writeln!(dts)?;
+    writeln!(
+        dts,
+        "/** What each WebGL query method returns, by parameter name (without GL_). */"
+    )?;
+    writeln!(dts, "export interface ParameterTypes {{")?;
+    for (method, parameters) in &parameter_types {
+        writeln!(dts, "    {}: {{", method)?;
+        for (get_value, types) in parameters {
+            let mapped: Vec<&str> = types.iter().filter_map(|t| t.as_deref().ok()).collect();
+            let unmapped: Vec<&str> = types
+                .iter()
+                .filter_map(|t| t.as_ref().err().map(String::as_str))
+                .collect();
+            if unmapped.is_empty() {
+                writeln!(dts, "        {}: {};", get_value, mapped.join(" | "))?;
+            } else {
+                writeln!(dts, "        /** Unmapped: {} */", unmapped.join("; "))?;
+                writeln!(dts, "        {}: unknown;", get_value)?;
+            }
+        }
+        writeln!(dts, "    }};")?;
+    }
+    writeln!(dts, "}}")?;
+    writeln!(dts)?;
+    writeln!(dts, "{}", STATE_ENTRY)?;
+    dts.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write_webgl;
+
+    /// The declarations are compared against `tests/golden/webgl.d.ts`. Run
+    /// with `UPDATE_GOLDEN=1` to regenerate it after an intended change.
+    #[test]
+    fn declarations() {
+        let out_dir = std::env::temp_dir().join(format!("gl-state-webgl-{}", std::process::id()));
+        crate::with_specs(|specs| write_webgl(&out_dir, specs)).unwrap();
+        let actual = std::fs::read_to_string(out_dir.join("gl-state.d.ts")).unwrap();
+        std::fs::remove_dir_all(&out_dir).unwrap();
+
+        for line in [
+            "        COMPRESSED_TEXTURE_FORMATS: Uint32Array;",
+            "        UNIFORM_BLOCK_ACTIVE_UNIFORM_INDICES: Uint32Array;",
+            "        UNIFORM_IS_ROW_MAJOR: boolean[];",
+            "        UNIFORM_TYPE: number[];",
+            "        TEXTURE_BINDING_2D: WebGLTexture | null;",
+            "        COLOR_WRITEMASK: boolean[];",
+            "        VIEWPORT: Int32Array;",
+        ] {
+            assert!(actual.lines().any(|l| l == line), "no {:?}", line);
+        }
+
+        let path = format!("{}/tests/golden/webgl.d.ts", env!("CARGO_MANIFEST_DIR"));
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            std::fs::write(&path, &actual).unwrap();
+            return;
+        }
+        let expected = std::fs::read_to_string(&path).unwrap();
+        assert!(
+            actual == expected,
+            "{} differs from the output, run with UPDATE_GOLDEN=1 if that's intended and review the diff",
+            path
+        );
+    }
+}
@@ -0,0 +1,473 @@
+// Generated by OpenGL-state-table-parser from the state tables of the
+// OpenGL ES 3.2 spec. Don't edit it by hand.
+
+/** What each WebGL query method returns, by parameter name (without GL_). */
+export interface ParameterTypes {
+    getActiveUniformBlockParameter: {
+        UNIFORM_BLOCK_ACTIVE_UNIFORMS: number;
+        UNIFORM_BLOCK_ACTIVE_UNIFORM_INDICES: Uint32Array;
+        UNIFORM_BLOCK_BINDING: number;
+        UNIFORM_BLOCK_DATA_SIZE: number;
+        UNIFORM_BLOCK_NAME_LENGTH: number;
+        UNIFORM_BLOCK_REFERENCED_BY_FRAGMENT_SHADER: boolean;
+        UNIFORM_BLOCK_REFERENCED_BY_VERTEX_SHADER: boolean;
+    };
+    getActiveUniforms: {
+        UNIFORM_ARRAY_STRIDE: number[];
+        UNIFORM_BLOCK_INDEX: number[];
+        UNIFORM_IS_ROW_MAJOR: boolean[];
+        UNIFORM_MATRIX_STRIDE: number[];
+        UNIFORM_NAME_LENGTH: number[];
+        UNIFORM_OFFSET: number[];
+        UNIFORM_SIZE: number[];
+        UNIFORM_TYPE: number[];
+    };
+    getBufferParameter: {
+        BUFFER_ACCESS_FLAGS: number;
+        BUFFER_MAPPED: boolean;
+        BUFFER_MAP_LENGTH: number;
+        BUFFER_MAP_OFFSET: number;
+        BUFFER_SIZE: number;
+        BUFFER_USAGE: number;
+    };
+    getFramebufferAttachmentParameter: {
+        FRAMEBUFFER_ATTACHMENT_ALPHA_SIZE: number;
+        FRAMEBUFFER_ATTACHMENT_BLUE_SIZE: number;
+        FRAMEBUFFER_ATTACHMENT_COLOR_ENCODING: number;
+        FRAMEBUFFER_ATTACHMENT_COMPONENT_TYPE: number;
+        FRAMEBUFFER_ATTACHMENT_DEPTH_SIZE: number;
+        FRAMEBUFFER_ATTACHMENT_GREEN_SIZE: number;
+        FRAMEBUFFER_ATTACHMENT_LAYERED: boolean;
+        FRAMEBUFFER_ATTACHMENT_OBJECT_NAME: WebGLRenderbuffer | WebGLTexture | null;
+        FRAMEBUFFER_ATTACHMENT_OBJECT_TYPE: number;
+        FRAMEBUFFER_ATTACHMENT_RED_SIZE: number;
+        FRAMEBUFFER_ATTACHMENT_STENCIL_SIZE: number;
+        FRAMEBUFFER_ATTACHMENT_TEXTURE_CUBE_MAP_FACE: number;
+        FRAMEBUFFER_ATTACHMENT_TEXTURE_LAYER: number;
+        FRAMEBUFFER_ATTACHMENT_TEXTURE_LEVEL: number;
+    };
+    getIndexedParameter: {
+        ATOMIC_COUNTER_BUFFER_BINDING: WebGLBuffer | null;
+        ATOMIC_COUNTER_BUFFER_SIZE: number;
+        ATOMIC_COUNTER_BUFFER_START: number;
+        BLEND_DST_ALPHA: number;
+        BLEND_DST_RGB: number;
+        BLEND_EQUATION_ALPHA: number;
+        BLEND_EQUATION_RGB: number;
+        BLEND_SRC_ALPHA: number;
+        BLEND_SRC_RGB: number;
+        COLOR_WRITEMASK: boolean[];
+        IMAGE_BINDING_ACCESS: number;
+        IMAGE_BINDING_FORMAT: number;
+        IMAGE_BINDING_LAYER: number;
+        IMAGE_BINDING_LAYERED: boolean;
+        IMAGE_BINDING_LEVEL: number;
+        IMAGE_BINDING_NAME: number;
+        MAX_COMPUTE_WORK_GROUP_COUNT: number;
+        MAX_COMPUTE_WORK_GROUP_SIZE: number;
+        SAMPLE_MASK_VALUE: number;
+        SHADER_STORAGE_BUFFER_BINDING: WebGLBuffer | null;
+        SHADER_STORAGE_BUFFER_SIZE: number;
+        SHADER_STORAGE_BUFFER_START: number;
+        TRANSFORM_FEEDBACK_BUFFER_BINDING: WebGLBuffer | null;
+        TRANSFORM_FEEDBACK_BUFFER_SIZE: number;
+        TRANSFORM_FEEDBACK_BUFFER_START: number;
+        UNIFORM_BUFFER_BINDING: WebGLBuffer | null;
+        UNIFORM_BUFFER_SIZE: number;
+        UNIFORM_BUFFER_START: number;
+        VERTEX_BINDING_BUFFER: number;
+        VERTEX_BINDING_DIVISOR: number;
+        VERTEX_BINDING_OFFSET: number;
+        VERTEX_BINDING_STRIDE: number;
+    };
+    getParameter: {
+        ACTIVE_TEXTURE: number;
+        ALIASED_LINE_WIDTH_RANGE: Float32Array;
+        ALIASED_POINT_SIZE_RANGE: Float32Array;
+        ALPHA_BITS: number;
+        ARRAY_BUFFER_BINDING: WebGLBuffer | null;
+        ATOMIC_COUNTER_BUFFER_BINDING: WebGLBuffer | null;
+        BLEND_COLOR: Float32Array;
+        BLUE_BITS: number;
+        COLOR_CLEAR_VALUE: Float32Array;
+        COMPRESSED_TEXTURE_FORMATS: Uint32Array;
+        CONTEXT_FLAGS: number;
+        COPY_READ_BUFFER_BINDING: WebGLBuffer | null;
+        COPY_WRITE_BUFFER_BINDING: WebGLBuffer | null;
+        CULL_FACE_MODE: number;
+        CURRENT_PROGRAM: WebGLProgram | null;
+        DEBUG_GROUP_STACK_DEPTH: number;
+        DEBUG_LOGGED_MESSAGES: number;
+        DEBUG_NEXT_LOGGED_MESSAGE_LENGTH: number;
+        DEPTH_BITS: number;
+        DEPTH_CLEAR_VALUE: number;
+        DEPTH_FUNC: number;
+        DEPTH_RANGE: Float32Array;
+        DEPTH_WRITEMASK: boolean;
+        DISPATCH_INDIRECT_BUFFER_BINDING: WebGLBuffer | null;
+        DRAW_BUFFER0: number;
+        DRAW_FRAMEBUFFER_BINDING: WebGLFramebuffer | null;
+        DRAW_INDIRECT_BUFFER_BINDING: WebGLBuffer | null;
+        ELEMENT_ARRAY_BUFFER_BINDING: WebGLBuffer | null;
+        FRAGMENT_INTERPOLATION_OFFSET_BITS: number;
+        FRAGMENT_SHADER_DERIVATIVE_HINT: number;
+        FRONT_FACE: number;
+        GENERATE_MIPMAP_HINT: number;
+        GREEN_BITS: number;
+        IMPLEMENTATION_COLOR_READ_FORMAT: number;
+        IMPLEMENTATION_COLOR_READ_TYPE: number;
+        LAYER_PROVOKING_VERTEX: number;
+        LINE_WIDTH: number;
+        MAJOR_VERSION: number;
+        MAX_3D_TEXTURE_SIZE: number;
+        MAX_ARRAY_TEXTURE_LAYERS: number;
+        MAX_ATOMIC_COUNTER_BUFFER_BINDINGS: number;
+        MAX_ATOMIC_COUNTER_BUFFER_SIZE: number;
+        MAX_COLOR_ATTACHMENTS: number;
+        MAX_COLOR_TEXTURE_SAMPLES: number;
+        MAX_COMBINED_ATOMIC_COUNTERS: number;
+        MAX_COMBINED_ATOMIC_COUNTER_BUFFERS: number;
+        MAX_COMBINED_COMPUTE_UNIFORM_COMPONENTS: number;
+        MAX_COMBINED_FRAGMENT_UNIFORM_COMPONENTS: number;
+        MAX_COMBINED_GEOMETRY_UNIFORM_COMPONENTS: number;
+        MAX_COMBINED_IMAGE_UNIFORMS: number;
+        MAX_COMBINED_SHADER_OUTPUT_RESOURCES: number;
+        MAX_COMBINED_SHADER_STORAGE_BLOCKS: number;
+        MAX_COMBINED_TESS_CONTROL_UNIFORM_COMPONENTS: number;
+        MAX_COMBINED_TESS_EVALUATION_UNIFORM_COMPONENTS: number;
+        MAX_COMBINED_TEXTURE_IMAGE_UNITS: number;
+        MAX_COMBINED_UNIFORM_BLOCKS: number;
+        MAX_COMBINED_VERTEX_UNIFORM_COMPONENTS: number;
+        MAX_COMPUTE_ATOMIC_COUNTERS: number;
+        MAX_COMPUTE_ATOMIC_COUNTER_BUFFERS: number;
+        MAX_COMPUTE_IMAGE_UNIFORMS: number;
+        MAX_COMPUTE_SHADER_STORAGE_BLOCKS: number;
+        MAX_COMPUTE_SHARED_MEMORY_SIZE: number;
+        MAX_COMPUTE_TEXTURE_IMAGE_UNITS: number;
+        MAX_COMPUTE_UNIFORM_BLOCKS: number;
+        MAX_COMPUTE_UNIFORM_COMPONENTS: number;
+        MAX_COMPUTE_WORK_GROUP_INVOCATIONS: number;
+        MAX_CUBE_MAP_TEXTURE_SIZE: number;
+        MAX_DEBUG_GROUP_STACK_DEPTH: number;
+        MAX_DEBUG_LOGGED_MESSAGES: number;
+        MAX_DEBUG_MESSAGE_LENGTH: number;
+        MAX_DEPTH_TEXTURE_SAMPLES: number;
+        MAX_DRAW_BUFFERS: number;
+        MAX_ELEMENTS_INDICES: number;
+        MAX_ELEMENTS_VERTICES: number;
+        MAX_ELEMENT_INDEX: number;
+        MAX_FRAGMENT_ATOMIC_COUNTERS: number;
+        MAX_FRAGMENT_ATOMIC_COUNTER_BUFFERS: number;
+        MAX_FRAGMENT_IMAGE_UNIFORMS: number;
+        MAX_FRAGMENT_INPUT_COMPONENTS: number;
+        MAX_FRAGMENT_INTERPOLATION_OFFSET: number;
+        MAX_FRAGMENT_SHADER_STORAGE_BLOCKS: number;
+        MAX_FRAGMENT_UNIFORM_BLOCKS: number;
+        MAX_FRAGMENT_UNIFORM_COMPONENTS: number;
+        MAX_FRAGMENT_UNIFORM_VECTORS: number;
+        MAX_FRAMEBUFFER_HEIGHT: number;
+        MAX_FRAMEBUFFER_LAYERS: number;
+        MAX_FRAMEBUFFER_SAMPLES: number;
+        MAX_FRAMEBUFFER_WIDTH: number;
+        MAX_GEOMETRY_ATOMIC_COUNTERS: number;
+        MAX_GEOMETRY_ATOMIC_COUNTER_BUFFERS: number;
+        MAX_GEOMETRY_IMAGE_UNIFORMS: number;
+        MAX_GEOMETRY_INPUT_COMPONENTS: number;
+        MAX_GEOMETRY_OUTPUT_COMPONENTS: number;
+        MAX_GEOMETRY_OUTPUT_VERTICES: number;
+        MAX_GEOMETRY_SHADER_INVOCATIONS: number;
+        MAX_GEOMETRY_SHADER_STORAGE_BLOCKS: number;
+        MAX_GEOMETRY_TEXTURE_IMAGE_UNITS: number;
+        MAX_GEOMETRY_TOTAL_OUTPUT_COMPONENTS: number;
+        MAX_GEOMETRY_UNIFORM_BLOCKS: number;
+        MAX_GEOMETRY_UNIFORM_COMPONENTS: number;
+        MAX_IMAGE_UNITS: number;
+        MAX_INTEGER_SAMPLES: number;
+        MAX_LABEL_LENGTH: number;
+        MAX_PATCH_VERTICES: number;
+        MAX_PROGRAM_TEXEL_OFFSET: number;
+        MAX_PROGRAM_TEXTURE_GATHER_OFFSET: number;
+        MAX_RENDERBUFFER_SIZE: number;
+        MAX_SAMPLES: number;
+        MAX_SAMPLE_MASK_WORDS: number;
+        MAX_SERVER_WAIT_TIMEOUT: number;
+        MAX_SHADER_STORAGE_BLOCK_SIZE: number;
+        MAX_SHADER_STORAGE_BUFFER_BINDINGS: number;
+        MAX_TESS_CONTROL_ATOMIC_COUNTERS: number;
+        MAX_TESS_CONTROL_ATOMIC_COUNTER_BUFFERS: number;
+        MAX_TESS_CONTROL_IMAGE_UNIFORMS: number;
+        MAX_TESS_CONTROL_INPUT_COMPONENTS: number;
+        MAX_TESS_CONTROL_OUTPUT_COMPONENTS: number;
+        MAX_TESS_CONTROL_SHADER_STORAGE_BLOCKS: number;
+        MAX_TESS_CONTROL_TEXTURE_IMAGE_UNITS: number;
+        MAX_TESS_CONTROL_TOTAL_OUTPUT_COMPONENTS: number;
+        MAX_TESS_CONTROL_UNIFORM_BLOCKS: number;
+        MAX_TESS_CONTROL_UNIFORM_COMPONENTS: number;
+        MAX_TESS_EVALUATION_ATOMIC_COUNTERS: number;
+        MAX_TESS_EVALUATION_ATOMIC_COUNTER_BUFFERS: number;
+        MAX_TESS_EVALUATION_IMAGE_UNIFORMS: number;
+        MAX_TESS_EVALUATION_INPUT_COMPONENTS: number;
+        MAX_TESS_EVALUATION_OUTPUT_COMPONENTS: number;
+        MAX_TESS_EVALUATION_SHADER_STORAGE_BLOCKS: number;
+        MAX_TESS_EVALUATION_TEXTURE_IMAGE_UNITS: number;
+        MAX_TESS_EVALUATION_UNIFORM_BLOCKS: number;
+        MAX_TESS_EVALUATION_UNIFORM_COMPONENTS: number;
+        MAX_TESS_GEN_LEVEL: number;
+        MAX_TESS_PATCH_COMPONENTS: number;
+        MAX_TEXTURE_BUFFER_SIZE: number;
+        MAX_TEXTURE_IMAGE_UNITS: number;
+        MAX_TEXTURE_LOD_BIAS: number;
+        MAX_TEXTURE_SIZE: number;
+        MAX_TRANSFORM_FEEDBACK_INTERLEAVED_COMPONENTS: number;
+        MAX_TRANSFORM_FEEDBACK_SEPARATE_ATTRIBS: number;
+        MAX_TRANSFORM_FEEDBACK_SEPARATE_COMPONENTS: number;
+        MAX_UNIFORM_BLOCK_SIZE: number;
+        MAX_UNIFORM_BUFFER_BINDINGS: number;
+        MAX_UNIFORM_LOCATIONS: number;
+        MAX_VARYING_COMPONENTS: number;
+        MAX_VARYING_VECTORS: number;
+        MAX_VERTEX_ATOMIC_COUNTERS: number;
+        MAX_VERTEX_ATOMIC_COUNTER_BUFFERS: number;
+        MAX_VERTEX_ATTRIBS: number;
+        MAX_VERTEX_ATTRIB_BINDINGS: number;
+        MAX_VERTEX_ATTRIB_RELATIVE_OFFSET: number;
+        MAX_VERTEX_ATTRIB_STRIDE: number;
+        MAX_VERTEX_IMAGE_UNIFORMS: number;
+        MAX_VERTEX_OUTPUT_COMPONENTS: number;
+        MAX_VERTEX_SHADER_STORAGE_BLOCKS: number;
+        MAX_VERTEX_TEXTURE_IMAGE_UNITS: number;
+        MAX_VERTEX_UNIFORM_BLOCKS: number;
+        MAX_VERTEX_UNIFORM_COMPONENTS: number;
+        MAX_VERTEX_UNIFORM_VECTORS: number;
+        MAX_VIEWPORT_DIMS: Int32Array;
+        MINOR_VERSION: number;
+        MIN_FRAGMENT_INTERPOLATION_OFFSET: number;
+        MIN_PROGRAM_TEXEL_OFFSET: number;
+        MIN_PROGRAM_TEXTURE_GATHER_OFFSET: number;
+        MIN_SAMPLE_SHADING_VALUE: number;
+        MULTISAMPLE_LINE_WIDTH_GRANULARITY: number;
+        MULTISAMPLE_LINE_WIDTH_RANGE: Float32Array;
+        NUM_COMPRESSED_TEXTURE_FORMATS: number;
+        NUM_EXTENSIONS: number;
+        NUM_PROGRAM_BINARY_FORMATS: number;
+        NUM_SHADER_BINARY_FORMATS: number;
+        PACK_ALIGNMENT: number;
+        PACK_ROW_LENGTH: number;
+        PACK_SKIP_PIXELS: number;
+        PACK_SKIP_ROWS: number;
+        PATCH_VERTICES: number;
+        PIXEL_PACK_BUFFER_BINDING: WebGLBuffer | null;
+        PIXEL_UNPACK_BUFFER_BINDING: WebGLBuffer | null;
+        POLYGON_OFFSET_FACTOR: number;
+        POLYGON_OFFSET_UNITS: number;
+        PRIMITIVE_BOUNDING_BOX: Float32Array;
+        PRIMITIVE_RESTART_FOR_PATCHES_SUPPORTED: boolean;
+        PROGRAM_BINARY_FORMATS: Uint32Array;
+        PROGRAM_PIPELINE_BINDING: number;
+        READ_BUFFER: number;
+        READ_FRAMEBUFFER_BINDING: WebGLFramebuffer | null;
+        RED_BITS: number;
+        RENDERBUFFER_BINDING: WebGLRenderbuffer | null;
+        RENDERER: string;
+        RESET_NOTIFICATION_STRATEGY: number;
+        SAMPLER_BINDING: WebGLSampler | null;
+        SAMPLES: number;
+        SAMPLE_BUFFERS: number;
+        SAMPLE_COVERAGE_INVERT: boolean;
+        SAMPLE_COVERAGE_VALUE: number;
+        SCISSOR_BOX: Int32Array;
+        SHADER_BINARY_FORMATS: Uint32Array;
+        SHADER_COMPILER: boolean;
+        SHADER_STORAGE_BUFFER_BINDING: WebGLBuffer | null;
+        SHADER_STORAGE_BUFFER_OFFSET_ALIGNMENT: number;
+        SHADING_LANGUAGE_VERSION: string;
+        STENCIL_BACK_FAIL: number;
+        STENCIL_BACK_FUNC: number;
+        STENCIL_BACK_PASS_DEPTH_FAIL: number;
+        STENCIL_BACK_PASS_DEPTH_PASS: number;
+        STENCIL_BACK_REF: number;
+        STENCIL_BACK_VALUE_MASK: number;
+        STENCIL_BACK_WRITEMASK: number;
+        STENCIL_BITS: number;
+        STENCIL_CLEAR_VALUE: number;
+        STENCIL_FAIL: number;
+        STENCIL_FUNC: number;
+        STENCIL_PASS_DEPTH_FAIL: number;
+        STENCIL_PASS_DEPTH_PASS: number;
+        STENCIL_REF: number;
+        STENCIL_VALUE_MASK: number;
+        STENCIL_WRITEMASK: number;
+        SUBPIXEL_BITS: number;
+        TEXTURE_BINDING_2D: WebGLTexture | null;
+        TEXTURE_BINDING_2D_ARRAY: WebGLTexture | null;
+        TEXTURE_BINDING_2D_MULTISAMPLE: WebGLTexture | null;
+        TEXTURE_BINDING_2D_MULTISAMPLE_ARRAY: WebGLTexture | null;
+        TEXTURE_BINDING_3D: WebGLTexture | null;
+        TEXTURE_BINDING_BUFFER: WebGLTexture | null;
+        TEXTURE_BINDING_CUBE_MAP: WebGLTexture | null;
+        TEXTURE_BINDING_CUBE_MAP_ARRAY: WebGLTexture | null;
+        TEXTURE_BUFFER_BINDING: WebGLBuffer | null;
+        TEXTURE_BUFFER_OFFSET_ALIGNMENT: number;
+        TRANSFORM_FEEDBACK_ACTIVE: boolean;
+        TRANSFORM_FEEDBACK_BINDING: WebGLTransformFeedback | null;
+        TRANSFORM_FEEDBACK_BUFFER_BINDING: WebGLBuffer | null;
+        TRANSFORM_FEEDBACK_PAUSED: boolean;
+        UNIFORM_BUFFER_BINDING: WebGLBuffer | null;
+        UNIFORM_BUFFER_OFFSET_ALIGNMENT: number;
+        UNPACK_ALIGNMENT: number;
+        UNPACK_IMAGE_HEIGHT: number;
+        UNPACK_ROW_LENGTH: number;
+        UNPACK_SKIP_IMAGES: number;
+        UNPACK_SKIP_PIXELS: number;
+        UNPACK_SKIP_ROWS: number;
+        VENDOR: string;
+        VERSION: string;
+        VERTEX_ARRAY_BINDING: WebGLVertexArrayObject | null;
+        VIEWPORT: Int32Array;
+    };
+    getProgramParameter: {
+        ACTIVE_ATOMIC_COUNTER_BUFFERS: number;
+        ACTIVE_ATTRIBUTES: number;
+        ACTIVE_ATTRIBUTE_MAX_LENGTH: number;
+        ACTIVE_UNIFORMS: number;
+        ACTIVE_UNIFORM_BLOCKS: number;
+        ACTIVE_UNIFORM_BLOCK_MAX_NAME_LENGTH: number;
+        ACTIVE_UNIFORM_MAX_LENGTH: number;
+        ATTACHED_SHADERS: number;
+        COMPUTE_WORK_GROUP_SIZE: Int32Array;
+        DELETE_STATUS: boolean;
+        GEOMETRY_INPUT_TYPE: number;
+        GEOMETRY_OUTPUT_TYPE: number;
+        GEOMETRY_SHADER_INVOCATIONS: number;
+        GEOMETRY_VERTICES_OUT: number;
+        INFO_LOG_LENGTH: number;
+        LINK_STATUS: boolean;
+        PROGRAM_BINARY_LENGTH: number;
+        PROGRAM_BINARY_RETRIEVABLE_HINT: boolean;
+        PROGRAM_SEPARABLE: boolean;
+        TESS_CONTROL_OUTPUT_VERTICES: number;
+        TESS_GEN_MODE: number;
+        TESS_GEN_POINT_MODE: boolean;
+        TESS_GEN_SPACING: number;
+        TESS_GEN_VERTEX_ORDER: number;
+        TRANSFORM_FEEDBACK_BUFFER_MODE: number;
+        TRANSFORM_FEEDBACK_VARYINGS: number;
+        TRANSFORM_FEEDBACK_VARYING_MAX_LENGTH: number;
+        VALIDATE_STATUS: boolean;
+    };
+    getQuery: {
+        CURRENT_QUERY: WebGLQuery | null;
+    };
+    getQueryParameter: {
+        QUERY_RESULT: number;
+        QUERY_RESULT_AVAILABLE: boolean;
+    };
+    getRenderbufferParameter: {
+        RENDERBUFFER_ALPHA_SIZE: number;
+        RENDERBUFFER_BLUE_SIZE: number;
+        RENDERBUFFER_DEPTH_SIZE: number;
+        RENDERBUFFER_GREEN_SIZE: number;
+        RENDERBUFFER_HEIGHT: number;
+        RENDERBUFFER_INTERNAL_FORMAT: number;
+        RENDERBUFFER_RED_SIZE: number;
+        RENDERBUFFER_SAMPLES: number;
+        RENDERBUFFER_STENCIL_SIZE: number;
+        RENDERBUFFER_WIDTH: number;
+    };
+    getSamplerParameter: {
+        TEXTURE_BORDER_COLOR: Float32Array;
+        TEXTURE_COMPARE_FUNC: number;
+        TEXTURE_COMPARE_MODE: number;
+        TEXTURE_MAG_FILTER: number;
+        TEXTURE_MAX_LOD: number;
+        TEXTURE_MIN_FILTER: number;
+        TEXTURE_MIN_LOD: number;
+        TEXTURE_WRAP_R: number;
+        TEXTURE_WRAP_S: number;
+        TEXTURE_WRAP_T: number;
+    };
+    getShaderParameter: {
+        COMPILE_STATUS: boolean;
+        DELETE_STATUS: boolean;
+        INFO_LOG_LENGTH: number;
+        SHADER_SOURCE_LENGTH: number;
+        SHADER_TYPE: number;
+    };
+    getSyncParameter: {
+        OBJECT_TYPE: number;
+        SYNC_CONDITION: number;
+        SYNC_FLAGS: number;
+        SYNC_STATUS: number;
+    };
+    getTexParameter: {
+        DEPTH_STENCIL_TEXTURE_MODE: number;
+        TEXTURE_BASE_LEVEL: number;
+        TEXTURE_BORDER_COLOR: Float32Array;
+        TEXTURE_COMPARE_FUNC: number;
+        TEXTURE_COMPARE_MODE: number;
+        TEXTURE_IMMUTABLE_FORMAT: boolean;
+        TEXTURE_IMMUTABLE_LEVELS: number;
+        TEXTURE_MAG_FILTER: number;
+        TEXTURE_MAX_LEVEL: number;
+        TEXTURE_MAX_LOD: number;
+        TEXTURE_MIN_FILTER: number;
+        TEXTURE_MIN_LOD: number;
+        TEXTURE_SWIZZLE_A: number;
+        TEXTURE_SWIZZLE_B: number;
+        TEXTURE_SWIZZLE_G: number;
+        TEXTURE_SWIZZLE_R: number;
+        TEXTURE_WRAP_R: number;
+        TEXTURE_WRAP_S: number;
+        TEXTURE_WRAP_T: number;
+    };
+    getVertexAttrib: {
+        CURRENT_VERTEX_ATTRIB: Float32Array;
+        VERTEX_ATTRIB_ARRAY_BUFFER_BINDING: WebGLBuffer | null;
+        VERTEX_ATTRIB_ARRAY_DIVISOR: number;
+        VERTEX_ATTRIB_ARRAY_ENABLED: boolean;
+        VERTEX_ATTRIB_ARRAY_INTEGER: boolean;
+        VERTEX_ATTRIB_ARRAY_NORMALIZED: boolean;
+        VERTEX_ATTRIB_ARRAY_SIZE: number;
+        VERTEX_ATTRIB_ARRAY_STRIDE: number;
+        VERTEX_ATTRIB_ARRAY_TYPE: number;
+        VERTEX_ATTRIB_BINDING: number;
+        VERTEX_ATTRIB_RELATIVE_OFFSET: number;
+    };
+    isEnabled: {
+        CULL_FACE: boolean;
+        DEBUG_OUTPUT: boolean;
+        DEBUG_OUTPUT_SYNCHRONOUS: boolean;
+        DEPTH_TEST: boolean;
+        DITHER: boolean;
+        POLYGON_OFFSET_FILL: boolean;
+        PRIMITIVE_RESTART_FIXED_INDEX: boolean;
+        RASTERIZER_DISCARD: boolean;
+        SAMPLE_ALPHA_TO_COVERAGE: boolean;
+        SAMPLE_COVERAGE: boolean;
+        SAMPLE_MASK: boolean;
+        SAMPLE_SHADING: boolean;
+        SCISSOR_TEST: boolean;
+        STENCIL_TEST: boolean;
+    };
+}
+
+/** An entry in gl-state.json. Text is as it was in the spec's LaTeX source. */
+export interface StateEntry {
+    /** Without the GL_ prefix, e.g. "TEXTURE_MAG_FILTER" */
+    getValue: string;
+    altGetValue: string | null;
+    /** The title of the table it's in */
+    table: string;
+    /** The spec's type, in its LaTeX notation */
+    type: string | null;
+    /** The OpenGL ES command, e.g. "GetTexParameter" */
+    getCommand: string | null;
+    /** The WebGL method, or null if WebGL doesn't have one */
+    webglMethod: keyof ParameterTypes | null;
+    /** The type the WebGL method returns, or null if it's unmapped */
+    returnType: string | null;
+    /** Why there's no return type, if there isn't */
+    unmapped: string | null;
+    initialValue: string | null;
+    description: string;
+}